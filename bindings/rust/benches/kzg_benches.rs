@@ -1,28 +1,17 @@
 use std::path::PathBuf;
 
+use c_kzg::test_utils::random_valid_blob;
 use c_kzg::*;
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use rand::{rngs::ThreadRng, Rng};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
 use std::sync::Arc;
 
-fn generate_random_blob_for_bench(rng: &mut ThreadRng) -> Blob {
-    let mut arr: Blob = [0; BYTES_PER_BLOB];
-    rng.fill(&mut arr[..]);
-    // Ensure that the blob is canonical by ensuring that
-    // each field element contained in the blob is < BLS_MODULUS
-    for i in 0..FIELD_ELEMENTS_PER_BLOB {
-        arr[i * BYTES_PER_FIELD_ELEMENT + BYTES_PER_FIELD_ELEMENT - 1] = 0;
-    }
-    arr
-}
-
 pub fn criterion_benchmark(c: &mut Criterion) {
     let mut rng = rand::thread_rng();
     let trusted_setup_file = PathBuf::from("../../src/trusted_setup.txt");
     assert!(trusted_setup_file.exists());
     let kzg_settings = Arc::new(KzgSettings::load_trusted_setup_file(trusted_setup_file).unwrap());
 
-    let blob = generate_random_blob_for_bench(&mut rng);
+    let blob = random_valid_blob(&mut rng);
     c.bench_function("blob_to_kzg_commitment", |b| {
         b.iter(|| KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings))
     });
@@ -31,7 +20,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         let mut group = c.benchmark_group("kzg operations");
 
         let blobs: Vec<Blob> = (0..*num_blobs)
-            .map(|_| generate_random_blob_for_bench(&mut rng))
+            .map(|_| random_valid_blob(&mut rng))
             .collect();
 
         group.bench_with_input(
@@ -43,7 +32,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         let kzg_commitments: Vec<KzgCommitment> = blobs
             .clone()
             .into_iter()
-            .map(|blob| KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings))
+            .map(|blob| KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings).unwrap())
             .collect();
         let proof = KzgProof::compute_aggregate_kzg_proof(&blobs, &kzg_settings).unwrap();
 
@@ -61,5 +50,34 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, criterion_benchmark);
+/// Startup cost: parsing the setup and computing the settings from it.
+pub fn trusted_setup_benchmark(c: &mut Criterion) {
+    let trusted_setup_file = PathBuf::from("../../src/trusted_setup.txt");
+    let text = std::fs::read_to_string(&trusted_setup_file).unwrap();
+    let (g1_bytes, g2_bytes) = trusted_setup_from_text(&text).unwrap();
+    let bin = trusted_setup_to_bin(&g1_bytes, &g2_bytes);
+
+    let mut group = c.benchmark_group("trusted setup");
+    // Each load computes the roots of unity and takes long, so take fewer samples.
+    group.sample_size(10);
+    group.bench_function("load_trusted_setup_file", |b| {
+        b.iter(|| KzgSettings::load_trusted_setup_file(&trusted_setup_file).unwrap())
+    });
+    group.bench_function("load_trusted_setup", |b| {
+        b.iter_batched(
+            || (g1_bytes.clone(), g2_bytes.clone()),
+            |(g1_bytes, g2_bytes)| KzgSettings::load_trusted_setup(g1_bytes, g2_bytes).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("trusted_setup_from_text", |b| {
+        b.iter(|| trusted_setup_from_text(&text).unwrap())
+    });
+    group.bench_function("trusted_setup_from_bin", |b| {
+        b.iter(|| trusted_setup_from_bin(&bin).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark, trusted_setup_benchmark);
 criterion_main!(benches);
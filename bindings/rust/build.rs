@@ -13,23 +13,97 @@ fn move_file(src: &Path, dst: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// Links the blst and c-kzg libraries that pkg-config finds instead of building the vendored
+/// sources. The C library has no version of its own, so the `ckzg` package must have the version of
+/// this crate, and its `field_elements_per_blob` variable must match the spec feature, since the
+/// layout of the types shared with Rust depends on it.
+#[cfg(feature = "system-libs")]
+fn link_system_libs(field_elements_per_blob: usize) {
+    for (feature, enabled) in [
+        ("lto", cfg!(feature = "lto")),
+        ("portable", cfg!(feature = "portable")),
+        ("force-adx", cfg!(feature = "force-adx")),
+        ("small-stack", cfg!(feature = "small-stack")),
+        ("constant-time", cfg!(feature = "constant-time")),
+        ("sanitize", cfg!(feature = "sanitize")),
+    ] {
+        if enabled {
+            println!(
+                "cargo:warning=`{}` is ignored with `system-libs`, the system libraries are linked as built",
+                feature
+            );
+        }
+    }
+
+    let version = env::var("CARGO_PKG_VERSION").unwrap();
+    pkg_config::Config::new()
+        .exactly_version(&version)
+        .probe("ckzg")
+        .unwrap_or_else(|error| panic!("Cannot link the system c-kzg library: {}", error));
+    let system_field_elements_per_blob =
+        pkg_config::get_variable("ckzg", "field_elements_per_blob").unwrap_or_else(|error| {
+            panic!(
+                "Cannot read FIELD_ELEMENTS_PER_BLOB of the system c-kzg library: {}",
+                error
+            )
+        });
+    if system_field_elements_per_blob != field_elements_per_blob.to_string() {
+        panic!(
+            "The system c-kzg library has FIELD_ELEMENTS_PER_BLOB={}, expected {}",
+            system_field_elements_per_blob, field_elements_per_blob
+        );
+    }
+    pkg_config::probe_library("blst")
+        .unwrap_or_else(|error| panic!("Cannot link the system blst library: {}", error));
+}
+
+#[cfg(not(feature = "system-libs"))]
+fn link_system_libs(_field_elements_per_blob: usize) {
+    unreachable!("only called with the `system-libs` feature")
+}
+
+/// The flags that instrument the C code with the `sanitize` feature: the sanitizers that
+/// `-Zsanitizer` enables for the Rust code, whose runtime rustc then links, and
+/// UndefinedBehaviorSanitizer, which has no Rust counterpart and traps instead of needing a runtime.
+fn sanitizer_flags() -> Vec<String> {
+    let rustflags = env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+    let mut sanitizers = vec!["undefined".to_string()];
+    let mut previous = "";
+    for flag in rustflags.split('\x1f') {
+        let value = flag
+            .strip_prefix("-Zsanitizer=")
+            .or_else(|| flag.strip_prefix("sanitizer=").filter(|_| previous == "-Z"));
+        previous = flag;
+        for sanitizer in value.into_iter().flat_map(|value| value.split(',')) {
+            match sanitizer {
+                "address" | "hwaddress" | "leak" | "memory" | "thread" => {
+                    sanitizers.push(sanitizer.to_string())
+                }
+                _ => println!(
+                    "cargo:warning=the C code is not instrumented for the `{}` sanitizer",
+                    sanitizer
+                ),
+            }
+        }
+    }
+    let mut flags = vec![
+        format!("-fsanitize={}", sanitizers.join(",")),
+        "-fsanitize-undefined-trap-on-error".to_string(),
+        "-fno-omit-frame-pointer".to_string(),
+        "-g".to_string(),
+    ];
+    // MemorySanitizer reports the stores of uninstrumented assembly as uninitialized reads.
+    if sanitizers.iter().any(|sanitizer| sanitizer == "memory") {
+        flags.push("-fsanitize-memory-track-origins".to_string());
+        flags.push("-D__BLST_NO_ASM__".to_string());
+    }
+    flags
+}
+
 fn main() {
     let root_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("../../");
     let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
 
-    // Ensure libblst exists in `OUT_DIR`
-    // Assuming blst submodule exists
-    Command::new("make")
-        .current_dir(root_dir.join("src"))
-        .arg("blst")
-        .status()
-        .unwrap();
-    move_file(
-        root_dir.join("lib/libblst.a").as_path(),
-        out_dir.join("libblst.a").as_path(),
-    )
-    .unwrap();
-
     let field_elements_per_blob = if cfg!(feature = "minimal-spec") {
         MINIMAL_FIELD_ELEMENTS_PER_BLOB
     } else {
@@ -38,6 +112,78 @@ fn main() {
 
     eprintln!("Using FIELD_ELEMENTS_PER_BLOB={}", field_elements_per_blob);
 
+    // Write the compile time variable to a consts.rs file to be imported to the bindings module.
+    let const_file = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("src/consts.rs");
+    std::fs::write(
+        const_file,
+        format!(
+            "pub const FIELD_ELEMENTS_PER_BLOB: usize = {};",
+            field_elements_per_blob
+        ),
+    )
+    .unwrap();
+
+    // The `mock` feature replaces the C library with Rust code, so there is nothing to build.
+    if cfg!(feature = "mock") {
+        return;
+    }
+
+    if cfg!(feature = "system-libs") {
+        link_system_libs(field_elements_per_blob);
+        return;
+    }
+
+    // When targeting WASI, both C libraries are cross-compiled with clang against the sysroot of
+    // a wasi-sdk installation, which provides the libc headers and `fopen` implementation.
+    let target = env::var("TARGET").unwrap();
+    println!("cargo:rerun-if-env-changed=WASI_SYSROOT");
+    let wasi_flags = if target.starts_with("wasm32-wasi") {
+        let sysroot = env::var("WASI_SYSROOT")
+            .expect("WASI_SYSROOT must point to a wasi-sdk sysroot when building for WASI");
+        Some(format!("--target={} --sysroot={}", target, sysroot))
+    } else {
+        None
+    };
+
+    // Ensure libblst exists in `OUT_DIR`
+    // Assuming blst submodule exists
+    let mut make_blst = Command::new("make");
+    make_blst.current_dir(root_dir.join("src")).arg("blst");
+    let mut blst_flags = Vec::new();
+    let sanitizer_flags = if cfg!(feature = "sanitize") {
+        sanitizer_flags()
+    } else {
+        Vec::new()
+    };
+    blst_flags.extend(sanitizer_flags.iter().cloned());
+    if let Some(wasi_flags) = &wasi_flags {
+        // There is no WebAssembly assembly in blst, so use its portable C implementation.
+        make_blst.env("CC", "clang");
+        blst_flags.push(wasi_flags.clone());
+        blst_flags.push("-D__BLST_NO_ASM__".to_string());
+    }
+    // These mirror the features of the `blst` crate. Without either, blst's build script enables
+    // ADX when the build host supports it.
+    match (cfg!(feature = "portable"), cfg!(feature = "force-adx")) {
+        (true, true) => panic!("Cannot compile with both `portable` and `force-adx` features"),
+        (true, false) => blst_flags.push("-D__BLST_PORTABLE__".to_string()),
+        (false, true) if target.starts_with("x86_64") => blst_flags.push("-D__ADX__".to_string()),
+        (false, true) => println!("cargo:warning=`force-adx` is ignored for non-x86_64 targets"),
+        (false, false) => {}
+    }
+    if !blst_flags.is_empty() {
+        make_blst.arg(format!(
+            "BLST_BUILD_SCRIPT=./build.sh {}",
+            blst_flags.join(" ")
+        ));
+    }
+    make_blst.status().unwrap();
+    move_file(
+        root_dir.join("lib/libblst.a").as_path(),
+        out_dir.join("libblst.a").as_path(),
+    )
+    .unwrap();
+
     // Deleting any existing assembly and object files to ensure that compiling with a different
     // feature flag changes the final linked library file.
     let obj_file = root_dir.join("src/c_kzg_4844.o");
@@ -45,18 +191,55 @@ fn main() {
         std::fs::remove_file(obj_file).unwrap();
     }
 
-    // Ensure libckzg exists in `OUT_DIR`
-    Command::new("make")
+    // With the `lto` feature the C sources are emitted as LLVM bitcode so that the linker can
+    // inline across the FFI boundary. This requires the final link to use `-Clinker-plugin-lto`
+    // with a clang/lld matching the LLVM version of rustc, see the README.
+    let mut make_ckzg = Command::new("make");
+    make_ckzg
         .current_dir(root_dir.join("src"))
         .arg("all")
         .arg(format!(
             "FIELD_ELEMENTS_PER_BLOB={}",
             field_elements_per_blob
-        ))
-        .status()
-        .unwrap();
+        ));
+    if wasi_flags.is_some()
+        || cfg!(feature = "lto")
+        || cfg!(feature = "small-stack")
+        || cfg!(feature = "constant-time")
+        || cfg!(feature = "sanitize")
+    {
+        let mut cflags = match &wasi_flags {
+            Some(wasi_flags) => format!("-O2 {}", wasi_flags),
+            None => "-O2 -fPIC".to_string(),
+        };
+        if cfg!(feature = "lto") {
+            cflags.push_str(" -flto=thin");
+        }
+        // Allocate the blob-sized temporaries of the C library on the heap.
+        if cfg!(feature = "small-stack") {
+            cflags.push_str(" -DC_KZG_SMALL_STACK");
+        }
+        // Keep the time taken by commitments and proofs independent of the blob data.
+        if cfg!(feature = "constant-time") {
+            cflags.push_str(" -DC_KZG_CONSTANT_TIME");
+        }
+        for flag in &sanitizer_flags {
+            cflags.push(' ');
+            cflags.push_str(flag);
+        }
+        make_ckzg.arg(format!("CFLAGS={}", cflags));
+    }
+
+    // Ensure libckzg exists in `OUT_DIR`
+    make_ckzg.status().unwrap();
 
-    Command::new("ar")
+    // The system `ar` cannot index LLVM bitcode or WebAssembly objects.
+    let archiver = if wasi_flags.is_some() || cfg!(feature = "lto") {
+        "llvm-ar"
+    } else {
+        "ar"
+    };
+    Command::new(archiver)
         .current_dir(&root_dir.join("src"))
         .args(["crus", "libckzg.a", "c_kzg_4844.o"])
         .status()
@@ -67,22 +250,22 @@ fn main() {
     )
     .unwrap();
 
+    // The MSVC linker looks for `<name>.lib` instead of `lib<name>.a`.
+    if target.ends_with("-msvc") {
+        for lib in ["ckzg", "blst"] {
+            std::fs::copy(
+                out_dir.join(format!("lib{}.a", lib)),
+                out_dir.join(format!("{}.lib", lib)),
+            )
+            .unwrap();
+        }
+    }
+
     println!("cargo:rustc-link-search={}", out_dir.display());
     println!("cargo:rustc-link-search={}", out_dir.display());
     println!("cargo:rustc-link-lib=static=ckzg");
     println!("cargo:rustc-link-lib=static=blst");
 
-    // Write the compile time variable to a consts.rs file to be imported to the bindings module.
-    let const_file = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("src/consts.rs");
-    std::fs::write(
-        const_file,
-        format!(
-            "pub const FIELD_ELEMENTS_PER_BLOB: usize = {};",
-            field_elements_per_blob
-        ),
-    )
-    .unwrap();
-
     // Cleanup
     let obj_file = root_dir.join("src/c_kzg_4844.o");
     if obj_file.exists() {
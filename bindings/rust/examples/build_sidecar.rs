@@ -0,0 +1,148 @@
+//! Builds and checks a blob sidecar for arbitrary data, as a reference for the most common
+//! integration flow: pack the data into blobs, commit to them, compute the versioned hashes and the
+//! aggregate proof, then verify all of it as a receiving node would.
+//!
+//! ```text
+//! cargo run --release --example build_sidecar -- [<file>]
+//! ```
+//!
+//! Reads the data from standard input without a file. `cargo test` runs the tests at the bottom.
+
+use c_kzg::test_utils::{decode_blobs, encode_blobs};
+use c_kzg::{Blob, KzgCommitment, KzgProof, KzgSettings};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::io::Read;
+
+/// The version byte of versioned hashes of KZG commitments, from EIP-4844.
+const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+struct Sidecar {
+    blobs: Vec<Blob>,
+    commitments: Vec<KzgCommitment>,
+    versioned_hashes: Vec<[u8; 32]>,
+    proof: KzgProof,
+}
+
+fn load_settings() -> Result<KzgSettings, c_kzg::Error> {
+    let text = c_kzg::test_utils::embedded_trusted_setup_text();
+    let (g1_bytes, g2_bytes) = c_kzg::trusted_setup_from_text(text)?;
+    KzgSettings::load_trusted_setup(g1_bytes, g2_bytes)
+}
+
+/// The hash a transaction refers to a blob by: the SHA-256 of its commitment, with the first byte
+/// replaced by the version.
+fn kzg_to_versioned_hash(commitment: &KzgCommitment) -> [u8; 32] {
+    let mut hash: [u8; 32] = Sha256::digest(commitment.to_bytes()).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+fn build_sidecar(data: &[u8], settings: &KzgSettings) -> Result<Sidecar, c_kzg::Error> {
+    let blobs = encode_blobs(data);
+    let commitments = blobs
+        .iter()
+        .map(|blob| KzgCommitment::blob_to_kzg_commitment_ref(blob, settings))
+        .collect::<Result<Vec<_>, _>>()?;
+    let versioned_hashes = commitments.iter().map(kzg_to_versioned_hash).collect();
+    let proof = KzgProof::compute_aggregate_kzg_proof(&blobs, settings)?;
+    Ok(Sidecar {
+        blobs,
+        commitments,
+        versioned_hashes,
+        proof,
+    })
+}
+
+/// Checks a received sidecar against the versioned hashes of the transaction, and returns the data.
+fn verify_sidecar(
+    sidecar: &Sidecar,
+    versioned_hashes: &[[u8; 32]],
+    settings: &KzgSettings,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    if sidecar.commitments.len() != versioned_hashes.len() {
+        return Err("the number of commitments does not match the versioned hashes".into());
+    }
+    for (commitment, versioned_hash) in sidecar.commitments.iter().zip(versioned_hashes) {
+        if kzg_to_versioned_hash(commitment) != *versioned_hash {
+            return Err("a commitment does not match its versioned hash".into());
+        }
+    }
+    if !sidecar
+        .proof
+        .verify_aggregate_kzg_proof(&sidecar.blobs, &sidecar.commitments, settings)?
+    {
+        return Err("the aggregate proof does not verify".into());
+    }
+    Ok(decode_blobs(&sidecar.blobs).ok_or("the blobs do not hold encoded data")?)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut data = Vec::new();
+    match std::env::args_os().nth(1) {
+        Some(path) => data = std::fs::read(path)?,
+        None => {
+            std::io::stdin().read_to_end(&mut data)?;
+        }
+    }
+
+    let settings = load_settings()?;
+    let sidecar = build_sidecar(&data, &settings)?;
+    for (index, (commitment, versioned_hash)) in sidecar
+        .commitments
+        .iter()
+        .zip(&sidecar.versioned_hashes)
+        .enumerate()
+    {
+        println!("blob {}", index);
+        println!("  commitment     0x{}", hex::encode(commitment.to_bytes()));
+        println!("  versioned hash 0x{}", hex::encode(versioned_hash));
+    }
+    println!(
+        "aggregate proof  0x{}",
+        hex::encode(sidecar.proof.to_bytes())
+    );
+
+    let decoded = verify_sidecar(&sidecar, &sidecar.versioned_hashes, &settings)?;
+    assert_eq!(decoded, data);
+    println!(
+        "verified {} bytes in {} blobs",
+        data.len(),
+        sidecar.blobs.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use c_kzg::test_utils::USABLE_BYTES_PER_BLOB;
+
+    #[test]
+    fn test_build_sidecar() {
+        let settings = load_settings().unwrap();
+        let data: Vec<u8> = (0..USABLE_BYTES_PER_BLOB + 1).map(|i| i as u8).collect();
+        for data in [&[][..], b"hello blobs", &data[..]] {
+            let sidecar = build_sidecar(data, &settings).unwrap();
+            assert!(sidecar
+                .versioned_hashes
+                .iter()
+                .all(|hash| hash[0] == VERSIONED_HASH_VERSION_KZG));
+            let decoded = verify_sidecar(&sidecar, &sidecar.versioned_hashes, &settings).unwrap();
+            assert_eq!(decoded, data);
+        }
+    }
+
+    #[cfg(not(feature = "mock"))]
+    #[test]
+    fn test_verify_sidecar_rejects_wrong_data() {
+        let settings = load_settings().unwrap();
+        let mut sidecar = build_sidecar(b"hello blobs", &settings).unwrap();
+        let versioned_hashes = sidecar.versioned_hashes.clone();
+        sidecar.blobs[0][0] ^= 1;
+        assert!(verify_sidecar(&sidecar, &versioned_hashes, &settings).is_err());
+        sidecar.blobs[0][0] ^= 1;
+        sidecar.versioned_hashes[0][31] ^= 1;
+        assert!(verify_sidecar(&sidecar, &sidecar.versioned_hashes, &settings).is_err());
+    }
+}
@@ -0,0 +1,22 @@
+//! Writes valid blobs, commitments, proofs and test vectors into a fuzz corpus directory, so that
+//! fuzzing starts from inputs that reach the pairing code:
+//!
+//! ```text
+//! cargo run --release --example seed_corpus -- fuzz/corpus/deserialize
+//! ```
+
+use c_kzg::test_utils::{embedded_trusted_setup_text, write_corpus_seeds};
+use c_kzg::{trusted_setup_from_text, KzgSettings};
+use std::path::PathBuf;
+
+fn main() {
+    let dir = std::env::args_os()
+        .nth(1)
+        .map(PathBuf::from)
+        .expect("usage: seed_corpus <corpus directory>");
+    let text = embedded_trusted_setup_text();
+    let (g1_bytes, g2_bytes) = trusted_setup_from_text(text).unwrap();
+    let settings = KzgSettings::load_trusted_setup(g1_bytes, g2_bytes).unwrap();
+    let count = write_corpus_seeds(&dir, 3, &settings).unwrap();
+    println!("wrote {} seeds to {}", count, dir.display());
+}
@@ -0,0 +1,119 @@
+//! Commits to, proves and verifies random blobs in a loop, and reloads the trusted setup every
+//! `RELOAD_EVERY` iterations, for hours if needed. Prints the resident set size and the allocations
+//! and bytes held by the C library at every report, so that slow leaks show up as numbers that keep
+//! growing:
+//!
+//! ```text
+//! cargo run --release --features alloc-stats --example soak -- <seconds> [<report seconds>]
+//! ```
+//!
+//! Exits with an error if the C library still holds memory once the last settings are dropped.
+
+use c_kzg::test_utils::{embedded_trusted_setup_text, random_valid_blob};
+use c_kzg::{
+    alloc_stats, track_allocations, trusted_setup_from_bin, trusted_setup_from_text,
+    trusted_setup_to_bin, Blob, Error, KzgCommitment, KzgProof, KzgSettings, BLS_MODULUS_BYTES,
+    BYTES_PER_FIELD_ELEMENT,
+};
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+const RELOAD_EVERY: u64 = 1000;
+const MAX_BLOBS: usize = 4;
+
+fn load(setup: &[u8]) -> KzgSettings {
+    let (g1_bytes, g2_bytes) = trusted_setup_from_bin(setup).unwrap();
+    KzgSettings::load_trusted_setup(g1_bytes, g2_bytes).unwrap()
+}
+
+/// The resident set size in bytes.
+#[cfg(target_os = "linux")]
+fn rss() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    Some(pages * u64::try_from(page_size).ok()?)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn rss() -> Option<u64> {
+    None
+}
+
+fn report(start: Instant, iterations: u64) {
+    let rss = rss().map_or_else(|| String::from("unknown"), |rss| rss.to_string());
+    let stats = alloc_stats();
+    println!(
+        "{:>8}s {:>10} iterations, rss {} bytes, c-kzg holds {} bytes in {} of {} allocations",
+        start.elapsed().as_secs(),
+        iterations,
+        rss,
+        stats.bytes_in_use(),
+        stats.allocations_in_use(),
+        stats.allocations
+    );
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1).map(|arg| {
+        arg.parse()
+            .expect("usage: soak <seconds> [<report seconds>]")
+    });
+    let duration = Duration::from_secs(args.next().unwrap_or(3600));
+    let report_interval = Duration::from_secs(args.next().unwrap_or(60));
+
+    // No settings exist yet, so the counting allocator can be installed.
+    unsafe { track_allocations() };
+    let text = embedded_trusted_setup_text();
+    let (g1_bytes, g2_bytes) = trusted_setup_from_text(text).unwrap();
+    let setup = trusted_setup_to_bin(&g1_bytes, &g2_bytes);
+
+    let mut rng = rand::thread_rng();
+    let mut settings = load(&setup);
+    let start = Instant::now();
+    let mut last_report = start;
+    let mut iterations = 0;
+    while start.elapsed() < duration {
+        let num_blobs = rng.gen_range(1..=MAX_BLOBS);
+        let blobs: Vec<Blob> = (0..num_blobs)
+            .map(|_| random_valid_blob(&mut rng))
+            .collect();
+        let commitments: Vec<KzgCommitment> = blobs
+            .iter()
+            .map(|blob| KzgCommitment::blob_to_kzg_commitment_ref(blob, &settings).unwrap())
+            .collect();
+        let proof = KzgProof::compute_aggregate_kzg_proof(&blobs, &settings).unwrap();
+        assert!(proof
+            .verify_aggregate_kzg_proof(&blobs, &commitments, &settings)
+            .unwrap());
+        // A call that fails in the C library after it has allocated, so that the cleanup of error
+        // paths is soaked as well.
+        let mut invalid_blobs = blobs.clone();
+        invalid_blobs[num_blobs - 1][..BYTES_PER_FIELD_ELEMENT].copy_from_slice(&BLS_MODULUS_BYTES);
+        assert!(matches!(
+            proof.verify_aggregate_kzg_proof(&invalid_blobs, &commitments, &settings),
+            Err(Error::InvalidFieldElement { .. })
+        ));
+
+        iterations += 1;
+        if iterations % RELOAD_EVERY == 0 {
+            settings = load(&setup);
+        }
+        if last_report.elapsed() >= report_interval {
+            report(start, iterations);
+            last_report = Instant::now();
+        }
+    }
+    report(start, iterations);
+
+    drop(settings);
+    let stats = alloc_stats();
+    if stats.bytes_in_use() != 0 || stats.allocations_in_use() != 0 {
+        eprintln!(
+            "c-kzg still holds {} bytes in {} allocations after dropping the settings",
+            stats.bytes_in_use(),
+            stats.allocations_in_use()
+        );
+        std::process::exit(1);
+    }
+}
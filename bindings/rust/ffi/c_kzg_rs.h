@@ -0,0 +1,60 @@
+/*
+ * C interface of the `c-kzg-ffi` shared library, which wraps the Rust bindings.
+ *
+ * All buffers are passed together with their length in bytes. Lists of blobs, commitments and
+ * points are passed as one concatenated buffer. Every function returns one of the CKZG_RS_* codes
+ * and only writes its output on success.
+ */
+
+#ifndef C_KZG_RS_H
+#define C_KZG_RS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define CKZG_RS_OK 0             /**< Success */
+#define CKZG_RS_BADARGS 1        /**< The supplied data is invalid in some way */
+#define CKZG_RS_ERROR 2          /**< Internal error of the C library */
+#define CKZG_RS_MALLOC 3         /**< Could not allocate memory */
+#define CKZG_RS_INVALID_LENGTH 4 /**< A buffer does not have the expected length */
+#define CKZG_RS_NULL_POINTER 5   /**< A required pointer is NULL */
+
+typedef struct ckzg_rs_settings ckzg_rs_settings;
+
+int32_t ckzg_rs_load_trusted_setup(const uint8_t *g1_bytes, size_t g1_len,
+                                   const uint8_t *g2_bytes, size_t g2_len,
+                                   ckzg_rs_settings **out);
+
+void ckzg_rs_free_trusted_setup(ckzg_rs_settings *settings);
+
+int32_t ckzg_rs_blob_to_kzg_commitment(const ckzg_rs_settings *settings,
+                                       const uint8_t *blob, size_t blob_len,
+                                       uint8_t out[48]);
+
+int32_t ckzg_rs_compute_aggregate_kzg_proof(const ckzg_rs_settings *settings,
+                                            const uint8_t *blobs, size_t blobs_len,
+                                            uint8_t out[48]);
+
+int32_t ckzg_rs_verify_aggregate_kzg_proof(const ckzg_rs_settings *settings,
+                                           const uint8_t *blobs, size_t blobs_len,
+                                           const uint8_t *commitments, size_t commitments_len,
+                                           const uint8_t *proof, size_t proof_len,
+                                           bool *out);
+
+int32_t ckzg_rs_verify_kzg_proof(const ckzg_rs_settings *settings,
+                                 const uint8_t *commitment, size_t commitment_len,
+                                 const uint8_t *z, size_t z_len,
+                                 const uint8_t *y, size_t y_len,
+                                 const uint8_t *proof, size_t proof_len,
+                                 bool *out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* C_KZG_RS_H */
@@ -0,0 +1,331 @@
+//! A C ABI over the `c-kzg` Rust crate, built as a shared library.
+//!
+//! Unlike the C library itself, every buffer is passed with its length and validated before use,
+//! and results are reported as `CKZG_RS_*` status codes (see `c_kzg_rs.h`). The symbols carry a
+//! `ckzg_rs_` prefix since the C library is linked into the same shared library.
+#![allow(clippy::missing_safety_doc)]
+
+use c_kzg::{
+    Blob, Error, KzgCommitment, KzgProof, KzgSettings, TrustedSetupError, BYTES_PER_BLOB,
+    BYTES_PER_COMMITMENT, BYTES_PER_FIELD_ELEMENT, BYTES_PER_G1_POINT, BYTES_PER_G2_POINT,
+    BYTES_PER_PROOF,
+};
+use core::slice;
+
+/// Success.
+pub const CKZG_RS_OK: i32 = 0;
+/// The C library rejected the input, e.g. a point not in G1.
+pub const CKZG_RS_BADARGS: i32 = 1;
+/// Internal error of the C library.
+pub const CKZG_RS_ERROR: i32 = 2;
+/// The C library could not allocate memory.
+pub const CKZG_RS_MALLOC: i32 = 3;
+/// A buffer does not have the expected length.
+pub const CKZG_RS_INVALID_LENGTH: i32 = 4;
+/// A required pointer is `NULL`.
+pub const CKZG_RS_NULL_POINTER: i32 = 5;
+
+fn error_code(error: Error) -> i32 {
+    match error {
+        Error::InvalidKzgProof { .. }
+        | Error::InvalidKzgCommitment { .. }
+        | Error::InvalidTrustedSetup(TrustedSetupError::InvalidG1PointCount { .. })
+        | Error::InvalidTrustedSetup(TrustedSetupError::InvalidG2PointCount { .. })
+        | Error::MismatchedLengths { .. } => CKZG_RS_INVALID_LENGTH,
+        Error::InvalidG1Point
+        | Error::InvalidFieldElement { .. }
+        | Error::InvalidHexFormat
+        | Error::MissingG1Points { .. }
+        | Error::IoError { .. }
+        | Error::ReadError { .. }
+        | Error::InvalidTrustedSetup(TrustedSetupError::InvalidFilePath)
+        | Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat) => CKZG_RS_BADARGS,
+        Error::Cancelled => CKZG_RS_ERROR,
+        Error::CError { ret, .. } | Error::InvalidTrustedSetup(TrustedSetupError::CError(ret)) => {
+            ret as i32
+        }
+        _ => CKZG_RS_ERROR,
+    }
+}
+
+/// Views `len` bytes at `ptr` as a slice, allowing `NULL` only for empty buffers.
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], i32> {
+    if len == 0 {
+        Ok(&[])
+    } else if ptr.is_null() {
+        Err(CKZG_RS_NULL_POINTER)
+    } else {
+        Ok(slice::from_raw_parts(ptr, len))
+    }
+}
+
+/// Views a buffer as whole chunks of `N` bytes.
+unsafe fn chunks<'a, const N: usize>(ptr: *const u8, len: usize) -> Result<&'a [[u8; N]], i32> {
+    if !len.is_multiple_of(N) {
+        return Err(CKZG_RS_INVALID_LENGTH);
+    }
+    let bytes = bytes(ptr, len)?;
+    Ok(slice::from_raw_parts(
+        bytes.as_ptr() as *const [u8; N],
+        len / N,
+    ))
+}
+
+unsafe fn settings<'a>(settings: *const KzgSettings) -> Result<&'a KzgSettings, i32> {
+    settings.as_ref().ok_or(CKZG_RS_NULL_POINTER)
+}
+
+unsafe fn write<T>(out: *mut T, value: T) -> Result<(), i32> {
+    if out.is_null() {
+        return Err(CKZG_RS_NULL_POINTER);
+    }
+    out.write(value);
+    Ok(())
+}
+
+fn status(result: Result<(), i32>) -> i32 {
+    result.err().unwrap_or(CKZG_RS_OK)
+}
+
+/// Loads a trusted setup from concatenated compressed G1 (48 byte) and G2 (96 byte) points. On
+/// success `*out` must be released with `ckzg_rs_free_trusted_setup`.
+#[no_mangle]
+pub unsafe extern "C" fn ckzg_rs_load_trusted_setup(
+    g1_bytes: *const u8,
+    g1_len: usize,
+    g2_bytes: *const u8,
+    g2_len: usize,
+    out: *mut *mut KzgSettings,
+) -> i32 {
+    status((|| {
+        let g1_points = chunks::<BYTES_PER_G1_POINT>(g1_bytes, g1_len)?.to_vec();
+        let g2_points = chunks::<BYTES_PER_G2_POINT>(g2_bytes, g2_len)?.to_vec();
+        let settings = KzgSettings::load_trusted_setup(g1_points, g2_points).map_err(error_code)?;
+        write(out, Box::into_raw(Box::new(settings)))
+    })())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ckzg_rs_free_trusted_setup(settings: *mut KzgSettings) {
+    if !settings.is_null() {
+        drop(Box::from_raw(settings));
+    }
+}
+
+/// Writes the 48 byte commitment to a blob to `out`.
+#[no_mangle]
+pub unsafe extern "C" fn ckzg_rs_blob_to_kzg_commitment(
+    kzg_settings: *const KzgSettings,
+    blob: *const u8,
+    blob_len: usize,
+    out: *mut [u8; BYTES_PER_COMMITMENT],
+) -> i32 {
+    status((|| {
+        let kzg_settings = settings(kzg_settings)?;
+        if blob_len != BYTES_PER_BLOB {
+            return Err(CKZG_RS_INVALID_LENGTH);
+        }
+        let blob = &chunks::<BYTES_PER_BLOB>(blob, blob_len)?[0];
+        let commitment =
+            KzgCommitment::blob_to_kzg_commitment_ref(blob, kzg_settings).map_err(error_code)?;
+        write(out, commitment.to_bytes())
+    })())
+}
+
+/// Writes the 48 byte aggregate proof for the concatenated `blobs` to `out`.
+#[no_mangle]
+pub unsafe extern "C" fn ckzg_rs_compute_aggregate_kzg_proof(
+    kzg_settings: *const KzgSettings,
+    blobs: *const u8,
+    blobs_len: usize,
+    out: *mut [u8; BYTES_PER_PROOF],
+) -> i32 {
+    status((|| {
+        let kzg_settings = settings(kzg_settings)?;
+        let blobs: &[Blob] = chunks(blobs, blobs_len)?;
+        let proof =
+            KzgProof::compute_aggregate_kzg_proof(blobs, kzg_settings).map_err(error_code)?;
+        write(out, proof.to_bytes())
+    })())
+}
+
+/// Verifies an aggregate proof for the concatenated `blobs` and their concatenated commitments.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn ckzg_rs_verify_aggregate_kzg_proof(
+    kzg_settings: *const KzgSettings,
+    blobs: *const u8,
+    blobs_len: usize,
+    commitments: *const u8,
+    commitments_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+    out: *mut bool,
+) -> i32 {
+    status((|| {
+        let kzg_settings = settings(kzg_settings)?;
+        let blobs: &[Blob] = chunks(blobs, blobs_len)?;
+        let commitments = chunks::<BYTES_PER_COMMITMENT>(commitments, commitments_len)?
+            .iter()
+            .map(|commitment| KzgCommitment::from_bytes(commitment))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(error_code)?;
+        if commitments.len() != blobs.len() {
+            return Err(CKZG_RS_INVALID_LENGTH);
+        }
+        let proof = KzgProof::from_bytes(bytes(proof, proof_len)?).map_err(error_code)?;
+        let valid = proof
+            .verify_aggregate_kzg_proof(blobs, &commitments, kzg_settings)
+            .map_err(error_code)?;
+        write(out, valid)
+    })())
+}
+
+/// Verifies that the polynomial committed to by `commitment` evaluates to `y` at `z`.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn ckzg_rs_verify_kzg_proof(
+    kzg_settings: *const KzgSettings,
+    commitment: *const u8,
+    commitment_len: usize,
+    z: *const u8,
+    z_len: usize,
+    y: *const u8,
+    y_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+    out: *mut bool,
+) -> i32 {
+    status((|| {
+        let kzg_settings = settings(kzg_settings)?;
+        let commitment =
+            KzgCommitment::from_bytes(bytes(commitment, commitment_len)?).map_err(error_code)?;
+        let z = bytes(z, z_len)?
+            .try_into()
+            .map_err(|_| CKZG_RS_INVALID_LENGTH)?;
+        let y: [u8; BYTES_PER_FIELD_ELEMENT] = bytes(y, y_len)?
+            .try_into()
+            .map_err(|_| CKZG_RS_INVALID_LENGTH)?;
+        let proof = KzgProof::from_bytes(bytes(proof, proof_len)?).map_err(error_code)?;
+        let valid = proof
+            .verify_kzg_proof(commitment, z, y, kzg_settings)
+            .map_err(error_code)?;
+        write(out, valid)
+    })())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    fn load_trusted_setup() -> *mut KzgSettings {
+        let trusted_setup = if cfg!(feature = "minimal-spec") {
+            include_str!("../../../../src/trusted_setup_4.txt")
+        } else {
+            include_str!("../../../../src/trusted_setup.txt")
+        };
+        let (g1, g2) = c_kzg::trusted_setup_from_text(trusted_setup).unwrap();
+        let (g1, g2) = (g1.concat(), g2.concat());
+
+        let mut settings = ptr::null_mut();
+        let ret = unsafe {
+            ckzg_rs_load_trusted_setup(g1.as_ptr(), g1.len(), g2.as_ptr(), g2.len(), &mut settings)
+        };
+        assert_eq!(ret, CKZG_RS_OK);
+        settings
+    }
+
+    #[test]
+    fn test_aggregate_roundtrip() {
+        let settings = load_trusted_setup();
+        let blobs = vec![0u8; 2 * BYTES_PER_BLOB];
+        unsafe {
+            let mut commitments = [[0u8; BYTES_PER_COMMITMENT]; 2];
+            for (i, commitment) in commitments.iter_mut().enumerate() {
+                let blob = blobs[i * BYTES_PER_BLOB..].as_ptr();
+                let ret =
+                    ckzg_rs_blob_to_kzg_commitment(settings, blob, BYTES_PER_BLOB, commitment);
+                assert_eq!(ret, CKZG_RS_OK);
+            }
+
+            let mut proof = [0u8; BYTES_PER_PROOF];
+            let ret = ckzg_rs_compute_aggregate_kzg_proof(
+                settings,
+                blobs.as_ptr(),
+                blobs.len(),
+                &mut proof,
+            );
+            assert_eq!(ret, CKZG_RS_OK);
+
+            let mut valid = false;
+            let ret = ckzg_rs_verify_aggregate_kzg_proof(
+                settings,
+                blobs.as_ptr(),
+                blobs.len(),
+                commitments.as_ptr() as *const u8,
+                2 * BYTES_PER_COMMITMENT,
+                proof.as_ptr(),
+                proof.len(),
+                &mut valid,
+            );
+            assert_eq!(ret, CKZG_RS_OK);
+            assert!(valid);
+
+            ckzg_rs_free_trusted_setup(settings);
+        }
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let settings = load_trusted_setup();
+        let blob = vec![0u8; BYTES_PER_BLOB];
+        let mut commitment = [0u8; BYTES_PER_COMMITMENT];
+        unsafe {
+            assert_eq!(
+                ckzg_rs_blob_to_kzg_commitment(settings, blob.as_ptr(), 1, &mut commitment),
+                CKZG_RS_INVALID_LENGTH
+            );
+            assert_eq!(
+                ckzg_rs_blob_to_kzg_commitment(
+                    ptr::null(),
+                    blob.as_ptr(),
+                    BYTES_PER_BLOB,
+                    &mut commitment
+                ),
+                CKZG_RS_NULL_POINTER
+            );
+            assert_eq!(
+                ckzg_rs_blob_to_kzg_commitment(
+                    settings,
+                    blob.as_ptr(),
+                    BYTES_PER_BLOB,
+                    ptr::null_mut()
+                ),
+                CKZG_RS_NULL_POINTER
+            );
+
+            // The point at infinity, so that only the length of `z` is wrong.
+            let mut infinity = [0u8; BYTES_PER_COMMITMENT];
+            infinity[0] = 0xc0;
+            let mut valid = false;
+            assert_eq!(
+                ckzg_rs_verify_kzg_proof(
+                    settings,
+                    infinity.as_ptr(),
+                    BYTES_PER_COMMITMENT,
+                    [0; BYTES_PER_FIELD_ELEMENT].as_ptr(),
+                    BYTES_PER_FIELD_ELEMENT - 1,
+                    [0; BYTES_PER_FIELD_ELEMENT].as_ptr(),
+                    BYTES_PER_FIELD_ELEMENT,
+                    infinity.as_ptr(),
+                    BYTES_PER_PROOF,
+                    &mut valid,
+                ),
+                CKZG_RS_INVALID_LENGTH
+            );
+
+            ckzg_rs_free_trusted_setup(settings);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Feeds arbitrary bytes to every public constructor, which must return an error instead of
+//! panicking on invalid input.
+
+#![no_main]
+
+use c_kzg::{
+    bytes_to_g1, AlignedBlob, BlsFieldElement, KzgCommitment, KzgProof, BYTES_PER_FIELD_ELEMENT,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = bytes_to_g1(data);
+    let _ = KzgProof::from_bytes(data);
+    let _ = KzgCommitment::from_bytes(data);
+    if let Some(bytes) = data.get(..BYTES_PER_FIELD_ELEMENT) {
+        let _ = BlsFieldElement::bytes_to_bls_field(bytes.try_into().unwrap());
+    }
+    if let Ok(hex_str) = core::str::from_utf8(data) {
+        let _ = KzgProof::from_hex(hex_str);
+        let _ = KzgCommitment::from_hex(hex_str);
+        let _ = AlignedBlob::from_hex(hex_str);
+    }
+});
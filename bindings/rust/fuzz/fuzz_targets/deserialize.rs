@@ -0,0 +1,49 @@
+//! Feeds arbitrary strings to the parsers of serialized data: the `from_hex` constructors, whose
+//! successful parses must round-trip through `as_hex_string`, and the JSON test vector formats of
+//! `spec_tests`, which must report malformed vectors as failures instead of panicking.
+
+#![no_main]
+
+use c_kzg::test_utils::embedded_trusted_setup_text;
+use c_kzg::{
+    spec_tests, trusted_setup_from_text, AlignedBlob, KzgCommitment, KzgProof, KzgSettings,
+};
+use libfuzzer_sys::fuzz_target;
+use std::sync::OnceLock;
+
+static SETTINGS: OnceLock<KzgSettings> = OnceLock::new();
+
+fn settings() -> &'static KzgSettings {
+    SETTINGS.get_or_init(|| {
+        let text = embedded_trusted_setup_text();
+        let (g1_bytes, g2_bytes) = trusted_setup_from_text(text).unwrap();
+        KzgSettings::load_trusted_setup(g1_bytes, g2_bytes).unwrap()
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = core::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(commitment) = KzgCommitment::from_hex(text) {
+        let encoded = commitment.as_hex_string();
+        let decoded = KzgCommitment::from_hex(&encoded).unwrap();
+        assert_eq!(decoded.to_bytes(), commitment.to_bytes());
+        assert_eq!(decoded.as_hex_string(), encoded);
+    }
+    if let Ok(proof) = KzgProof::from_hex(text) {
+        let encoded = proof.as_hex_string();
+        let decoded = KzgProof::from_hex(&encoded).unwrap();
+        assert_eq!(decoded.to_bytes(), proof.to_bytes());
+        assert_eq!(decoded.as_hex_string(), encoded);
+    }
+    if let Ok(blob) = AlignedBlob::from_hex(text) {
+        let decoded = AlignedBlob::from_hex(&blob.as_hex_string()).unwrap();
+        assert_eq!(decoded.0, blob.0);
+    }
+
+    for file_name in ["public_agg_proof.json", "public_verify_kzg_proof.json"] {
+        let _ = spec_tests::run_vectors(file_name, text, settings());
+    }
+});
@@ -0,0 +1,71 @@
+//! Feeds arbitrary bytes to the trusted setup loaders: the text parser of the C library through
+//! `load_trusted_setup_file`, and the text and binary parsers of this crate. Inputs starting with
+//! `0xff` are instead applied as XOR patches to a valid setup, to get past the point counts.
+//!
+//! Loading must fail without panicking, and neither a failed load nor dropping a successful one may
+//! leave settings or memory of the C library behind.
+
+#![no_main]
+
+use c_kzg::test_utils::embedded_trusted_setup_text;
+use c_kzg::{
+    alloc_stats, track_allocations, trusted_setup_from_bin, trusted_setup_from_text,
+    trusted_setup_to_bin, Error, KzgSettings,
+};
+use libfuzzer_sys::fuzz_target;
+use std::sync::{Once, OnceLock};
+
+static TRACK_ALLOCATIONS: Once = Once::new();
+static VALID_SETUP: OnceLock<Vec<u8>> = OnceLock::new();
+
+fn valid_setup() -> &'static [u8] {
+    VALID_SETUP.get_or_init(|| {
+        let text = embedded_trusted_setup_text();
+        let (g1_bytes, g2_bytes) = trusted_setup_from_text(text).unwrap();
+        trusted_setup_to_bin(&g1_bytes, &g2_bytes)
+    })
+}
+
+fn check_load(load: impl FnOnce() -> Result<KzgSettings, Error>) {
+    let before = alloc_stats();
+    match load() {
+        Ok(settings) => drop(settings),
+        Err(_) => assert_eq!(alloc_stats().live_settings, before.live_settings),
+    }
+    let after = alloc_stats();
+    assert_eq!(after.live_settings, before.live_settings);
+    assert_eq!(after.bytes_in_use(), before.bytes_in_use());
+}
+
+fn load_bin(bytes: &[u8]) -> Result<KzgSettings, Error> {
+    let (g1_bytes, g2_bytes) = trusted_setup_from_bin(bytes)?;
+    KzgSettings::load_trusted_setup(g1_bytes, g2_bytes)
+}
+
+fuzz_target!(|data: &[u8]| {
+    // No settings exist yet, so the counting allocator can be installed.
+    TRACK_ALLOCATIONS.call_once(|| unsafe { track_allocations() });
+
+    if let Some((0xff, patches)) = data.split_first() {
+        let mut setup = valid_setup().to_vec();
+        for patch in patches.chunks_exact(5) {
+            let offset = u32::from_le_bytes(patch[..4].try_into().unwrap()) as usize;
+            let len = setup.len();
+            setup[offset % len] ^= patch[4];
+        }
+        check_load(|| load_bin(&setup));
+        return;
+    }
+
+    check_load(|| load_bin(data));
+    if let Ok(text) = core::str::from_utf8(data) {
+        check_load(|| {
+            let (g1_bytes, g2_bytes) = trusted_setup_from_text(text)?;
+            KzgSettings::load_trusted_setup(g1_bytes, g2_bytes)
+        });
+    }
+
+    let path = std::env::temp_dir().join(format!("c-kzg-fuzz-{}.txt", std::process::id()));
+    std::fs::write(&path, data).unwrap();
+    check_load(|| KzgSettings::load_trusted_setup_file(&path));
+});
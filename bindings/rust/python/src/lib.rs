@@ -0,0 +1,192 @@
+//! Python bindings on top of the `c-kzg` Rust crate.
+//!
+//! The module mirrors the functions of the `ckzg` C extension in `bindings/python`, but validates
+//! its inputs in Rust, releases the GIL while computing, and commits to batches of blobs in
+//! parallel.
+
+use c_kzg::{
+    Blob, Error, KzgCommitment, KzgProof, KzgSettings, BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT,
+    BYTES_PER_G1_POINT, BYTES_PER_G2_POINT,
+};
+use core::slice;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use std::path::PathBuf;
+
+fn to_py_err(error: Error) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+/// Views concatenated blobs as a slice of blobs.
+fn as_blobs(bytes: &[u8]) -> PyResult<&[Blob]> {
+    if !bytes.len().is_multiple_of(BYTES_PER_BLOB) {
+        return Err(PyValueError::new_err(format!(
+            "expected a multiple of {} bytes, got {}",
+            BYTES_PER_BLOB,
+            bytes.len()
+        )));
+    }
+    // `Blob` is a byte array, so it has no alignment requirement.
+    Ok(unsafe {
+        slice::from_raw_parts(bytes.as_ptr() as *const Blob, bytes.len() / BYTES_PER_BLOB)
+    })
+}
+
+fn as_field_element(bytes: &[u8]) -> PyResult<[u8; BYTES_PER_FIELD_ELEMENT]> {
+    bytes.try_into().map_err(|_| {
+        PyValueError::new_err(format!(
+            "expected {} bytes, got {}",
+            BYTES_PER_FIELD_ELEMENT,
+            bytes.len()
+        ))
+    })
+}
+
+fn as_points<const N: usize>(points: Vec<&[u8]>) -> PyResult<Vec<[u8; N]>> {
+    points
+        .into_iter()
+        .map(|point| {
+            point.try_into().map_err(|_| {
+                PyValueError::new_err(format!("expected {} bytes, got {}", N, point.len()))
+            })
+        })
+        .collect()
+}
+
+/// A loaded trusted setup.
+#[pyclass(name = "KZGSettings", frozen)]
+struct PyKzgSettings(KzgSettings);
+
+/// Load trusted setup from file path
+#[pyfunction]
+fn load_trusted_setup(py: Python, path: PathBuf) -> PyResult<PyKzgSettings> {
+    py.allow_threads(|| KzgSettings::load_trusted_setup_file(path))
+        .map(PyKzgSettings)
+        .map_err(to_py_err)
+}
+
+/// Load trusted setup from lists of compressed g1 and g2 points
+#[pyfunction]
+fn load_trusted_setup_from_points(
+    py: Python,
+    g1_points: Vec<&[u8]>,
+    g2_points: Vec<&[u8]>,
+) -> PyResult<PyKzgSettings> {
+    let g1_points = as_points::<BYTES_PER_G1_POINT>(g1_points)?;
+    let g2_points = as_points::<BYTES_PER_G2_POINT>(g2_points)?;
+    py.allow_threads(|| KzgSettings::load_trusted_setup(g1_points, g2_points))
+        .map(PyKzgSettings)
+        .map_err(to_py_err)
+}
+
+/// Create a commitment from a blob
+#[pyfunction]
+fn blob_to_kzg_commitment<'py>(
+    py: Python<'py>,
+    blob: &[u8],
+    settings: &PyKzgSettings,
+) -> PyResult<&'py PyBytes> {
+    let blob = as_blobs(blob)?;
+    if blob.len() != 1 {
+        return Err(PyValueError::new_err(format!(
+            "expected {} bytes, got {}",
+            BYTES_PER_BLOB,
+            blob.len() * BYTES_PER_BLOB
+        )));
+    }
+    let commitment = py
+        .allow_threads(|| KzgCommitment::blob_to_kzg_commitment_ref(&blob[0], &settings.0))
+        .map_err(to_py_err)?;
+    Ok(PyBytes::new(py, &commitment.to_bytes()))
+}
+
+/// Create commitments from concatenated blobs in parallel
+#[pyfunction]
+fn blob_to_kzg_commitment_batch<'py>(
+    py: Python<'py>,
+    blobs: &[u8],
+    settings: &PyKzgSettings,
+) -> PyResult<Vec<&'py PyBytes>> {
+    let blobs = as_blobs(blobs)?;
+    let commitments = py
+        .allow_threads(|| KzgCommitment::blob_to_kzg_commitment_batch_parallel(blobs, &settings.0))
+        .map_err(to_py_err)?;
+    Ok(commitments
+        .iter()
+        .map(|commitment| PyBytes::new(py, &commitment.to_bytes()))
+        .collect())
+}
+
+/// Compute aggregate KZG proof
+#[pyfunction]
+fn compute_aggregate_kzg_proof<'py>(
+    py: Python<'py>,
+    blobs: &[u8],
+    settings: &PyKzgSettings,
+) -> PyResult<&'py PyBytes> {
+    let blobs = as_blobs(blobs)?;
+    let proof = py
+        .allow_threads(|| KzgProof::compute_aggregate_kzg_proof(blobs, &settings.0))
+        .map_err(to_py_err)?;
+    Ok(PyBytes::new(py, &proof.to_bytes()))
+}
+
+/// Verify aggregate KZG proof
+#[pyfunction]
+fn verify_aggregate_kzg_proof(
+    py: Python,
+    blobs: &[u8],
+    commitments: Vec<&[u8]>,
+    proof: &[u8],
+    settings: &PyKzgSettings,
+) -> PyResult<bool> {
+    let blobs = as_blobs(blobs)?;
+    if commitments.len() != blobs.len() {
+        return Err(PyValueError::new_err(format!(
+            "expected {} commitments, got {}",
+            blobs.len(),
+            commitments.len()
+        )));
+    }
+    let commitments = commitments
+        .into_iter()
+        .map(KzgCommitment::from_bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(to_py_err)?;
+    let proof = KzgProof::from_bytes(proof).map_err(to_py_err)?;
+    py.allow_threads(|| proof.verify_aggregate_kzg_proof(blobs, &commitments, &settings.0))
+        .map_err(to_py_err)
+}
+
+/// Verify that a committed polynomial evaluates to y at z
+#[pyfunction]
+fn verify_kzg_proof(
+    py: Python,
+    commitment: &[u8],
+    z: &[u8],
+    y: &[u8],
+    proof: &[u8],
+    settings: &PyKzgSettings,
+) -> PyResult<bool> {
+    let commitment = KzgCommitment::from_bytes(commitment).map_err(to_py_err)?;
+    let z = as_field_element(z)?;
+    let y = as_field_element(y)?;
+    let proof = KzgProof::from_bytes(proof).map_err(to_py_err)?;
+    py.allow_threads(|| proof.verify_kzg_proof(commitment, z, y, &settings.0))
+        .map_err(to_py_err)
+}
+
+#[pymodule]
+fn ckzg_rs(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyKzgSettings>()?;
+    m.add_function(wrap_pyfunction!(load_trusted_setup, m)?)?;
+    m.add_function(wrap_pyfunction!(load_trusted_setup_from_points, m)?)?;
+    m.add_function(wrap_pyfunction!(blob_to_kzg_commitment, m)?)?;
+    m.add_function(wrap_pyfunction!(blob_to_kzg_commitment_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_aggregate_kzg_proof, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_aggregate_kzg_proof, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_kzg_proof, m)?)?;
+    m.add("BYTES_PER_BLOB", BYTES_PER_BLOB)?;
+    Ok(())
+}
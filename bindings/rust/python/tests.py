@@ -0,0 +1,47 @@
+import ckzg_rs
+import random
+
+# Commit to a few random blobs
+
+BLOB_SIZE = 4096
+
+blobs = [
+  # use zero final bytes to easily ensure the encodings are valid
+  b''.join([b''.join([random.randbytes(31), bytes(1)]) for _ in range(BLOB_SIZE)])
+  for _ in range(3)
+]
+
+ts = ckzg_rs.load_trusted_setup("../../../src/trusted_setup.txt")
+
+kzg_commitments = [ckzg_rs.blob_to_kzg_commitment(blob, ts) for blob in blobs]
+
+# The parallel batch gives the same commitments
+
+blobs_bytes = b''.join(blobs)
+
+assert ckzg_rs.blob_to_kzg_commitment_batch(blobs_bytes, ts) == kzg_commitments, 'batch mismatch'
+
+# Compute proof for these blobs
+
+proof = ckzg_rs.compute_aggregate_kzg_proof(blobs_bytes, ts)
+
+# Verify proof
+
+assert ckzg_rs.verify_aggregate_kzg_proof(blobs_bytes, kzg_commitments, proof, ts), 'verify failed'
+
+# Verification fails at wrong value
+
+other = b'x' if not blobs_bytes.startswith(b'x') else b'y'
+other_bytes = other + blobs_bytes[1:]
+
+assert not ckzg_rs.verify_aggregate_kzg_proof(other_bytes, kzg_commitments, proof, ts), 'verify succeeded incorrectly'
+
+# Inputs of the wrong length raise instead of reading out of bounds
+
+try:
+  ckzg_rs.blob_to_kzg_commitment(blobs[0][1:], ts)
+  assert False, 'short blob accepted'
+except ValueError:
+  pass
+
+print('tests passed')
@@ -0,0 +1,120 @@
+//! Opt-in accounting of `KzgSettings` instances and of the memory allocated by the C library, to
+//! debug leaks such as settings loaded per request by mistake.
+//!
+//! Settings are always counted. Bytes are only counted once `track_allocations` has installed the
+//! counting allocator.
+
+use core::ffi::c_void;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+static LIVE_SETTINGS: AtomicUsize = AtomicUsize::new(0);
+static BYTES_ALLOCATED: AtomicU64 = AtomicU64::new(0);
+static BYTES_FREED: AtomicU64 = AtomicU64::new(0);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static FREES: AtomicU64 = AtomicU64::new(0);
+
+/// A snapshot of the counters, see `alloc_stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocStats {
+    /// The number of `KzgSettings` owned by Rust code, i.e. created and not yet dropped or
+    /// released with `KzgSettings::into_raw`.
+    pub live_settings: usize,
+    /// The total number of bytes the C library has allocated since `track_allocations`.
+    pub bytes_allocated: u64,
+    /// The total number of bytes the C library has freed since `track_allocations`.
+    pub bytes_freed: u64,
+    /// The number of allocations the C library has made since `track_allocations`.
+    pub allocations: u64,
+    /// The number of allocations the C library has freed since `track_allocations`.
+    pub frees: u64,
+}
+
+impl AllocStats {
+    /// The number of bytes currently held by the C library. The counters are read one after the
+    /// other, so a snapshot taken while another thread frees memory can count the free but not the
+    /// allocation, and this is then clamped to zero.
+    pub fn bytes_in_use(&self) -> u64 {
+        self.bytes_allocated.saturating_sub(self.bytes_freed)
+    }
+
+    /// The number of allocations currently held by the C library, clamped like `bytes_in_use`.
+    pub fn allocations_in_use(&self) -> u64 {
+        self.allocations.saturating_sub(self.frees)
+    }
+}
+
+/// Returns the current counters.
+pub fn alloc_stats() -> AllocStats {
+    AllocStats {
+        live_settings: LIVE_SETTINGS.load(Ordering::Relaxed),
+        bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed),
+        bytes_freed: BYTES_FREED.load(Ordering::Relaxed),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        frees: FREES.load(Ordering::Relaxed),
+    }
+}
+
+/// Installs an allocator for the C library that forwards to libc and counts the allocations and the
+/// bytes allocated and freed, by storing the size of each allocation in front of it.
+///
+/// # Safety
+///
+/// Same as `set_allocator`: this must not be called while any other function of this crate is
+/// running, and every `KzgSettings` created before must have been dropped, since the counting
+/// `free` cannot release memory allocated without the size in front.
+pub unsafe fn track_allocations() {
+    crate::set_allocator(
+        Some(counting_malloc),
+        Some(counting_calloc),
+        Some(counting_free),
+    )
+}
+
+pub(crate) fn settings_created() {
+    LIVE_SETTINGS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn settings_dropped() {
+    LIVE_SETTINGS.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// Large enough to keep the alignment guaranteed by `malloc`.
+const HEADER: usize = 16;
+
+/// Stores `size` in the header at `base` and returns the pointer handed to the C library.
+unsafe fn counted(base: *mut c_void, size: usize) -> *mut c_void {
+    if base.is_null() {
+        return base;
+    }
+    (base as *mut usize).write(size);
+    BYTES_ALLOCATED.fetch_add(size as u64, Ordering::Relaxed);
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    (base as *mut u8).add(HEADER) as *mut c_void
+}
+
+unsafe extern "C" fn counting_malloc(size: usize) -> *mut c_void {
+    match size.checked_add(HEADER) {
+        Some(total) => counted(libc::malloc(total), size),
+        None => core::ptr::null_mut(),
+    }
+}
+
+unsafe extern "C" fn counting_calloc(nmemb: usize, size: usize) -> *mut c_void {
+    match nmemb
+        .checked_mul(size)
+        .and_then(|size| size.checked_add(HEADER))
+    {
+        Some(total) => counted(libc::calloc(1, total), total - HEADER),
+        None => core::ptr::null_mut(),
+    }
+}
+
+unsafe extern "C" fn counting_free(ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+    let base = (ptr as *mut u8).sub(HEADER) as *mut c_void;
+    BYTES_FREED.fetch_add((base as *const usize).read() as u64, Ordering::Relaxed);
+    FREES.fetch_add(1, Ordering::Relaxed);
+    libc::free(base);
+}
@@ -2,6 +2,7 @@
 
 include!("./consts.rs");
 
+#[cfg(all(feature = "std", not(windows), not(feature = "mock")))]
 use libc::FILE;
 
 pub const BYTES_PER_COMMITMENT: usize = 48;
@@ -78,6 +79,12 @@ pub struct blst_p2_affine {
 pub const FIAT_SHAMIR_PROTOCOL_DOMAIN: [u8; 16usize] = [
     70, 83, 66, 76, 79, 66, 86, 69, 82, 73, 70, 89, 95, 86, 49, 95,
 ];
+pub const EQUIVALENCE_PROTOCOL_DOMAIN: [u8; 16usize] = [
+    75, 90, 71, 95, 69, 81, 85, 73, 86, 95, 80, 82, 79, 79, 70, 95,
+];
+pub const DEGREE_BOUND_PROTOCOL_DOMAIN: [u8; 16usize] = [
+    75, 90, 71, 95, 68, 69, 71, 95, 66, 79, 85, 78, 68, 95, 86, 49,
+];
 pub type g1_t = blst_p1;
 pub type g2_t = blst_p2;
 pub type fr_t = blst_fr;
@@ -85,32 +92,29 @@ pub type KZGCommitment = g1_t;
 pub type KZGProof = g1_t;
 pub type BLSFieldElement = fr_t;
 pub type Blob = [u8; BYTES_PER_BLOB];
-#[repr(u32)]
 #[doc = " The common return type for all routines in which something can go wrong."]
 #[doc = ""]
 #[doc = " @warning In the case of @p C_KZG_OK or @p C_KZG_BADARGS, the caller can assume that all memory allocated by the"]
 #[doc = " called routines has been deallocated. However, in the case of @p C_KZG_ERROR or @p C_KZG_MALLOC being returned, these"]
 #[doc = " are unrecoverable and memory may have been leaked."]
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum C_KZG_RET {
-    #[doc = "< Success!"]
-    C_KZG_OK = 0,
-    #[doc = "< The supplied data is invalid in some way"]
-    C_KZG_BADARGS = 1,
-    #[doc = "< Internal error - this should never occur and may indicate a bug in the library"]
-    C_KZG_ERROR = 2,
-    #[doc = "< Could not allocate memory"]
-    C_KZG_MALLOC = 3,
-}
+pub type C_KZG_RET = ::core::ffi::c_uint;
+#[doc = "< Success!"]
+pub const C_KZG_RET_C_KZG_OK: C_KZG_RET = 0;
+#[doc = "< The supplied data is invalid in some way"]
+pub const C_KZG_RET_C_KZG_BADARGS: C_KZG_RET = 1;
+#[doc = "< Internal error - this should never occur and may indicate a bug in the library"]
+pub const C_KZG_RET_C_KZG_ERROR: C_KZG_RET = 2;
+#[doc = "< Could not allocate memory"]
+pub const C_KZG_RET_C_KZG_MALLOC: C_KZG_RET = 3;
 #[doc = " Stores the setup and parameters needed for performing FFTs."]
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct FFTSettings {
     #[doc = "< The maximum size of FFT these settings support, a power of 2."]
     pub max_width: u64,
-    #[doc = "< Ascending powers of the root of unity, size `width + 1`."]
+    #[doc = "< Ascending powers of the root of unity, size `width + 1`. `NULL` once loaded."]
     pub expanded_roots_of_unity: *const fr_t,
-    #[doc = "< Descending powers of the root of unity, size `width + 1`."]
+    #[doc = "< Descending powers of the root of unity, size `width + 1`. `NULL` once loaded."]
     pub reverse_roots_of_unity: *const fr_t,
     #[doc = "< Powers of the root of unity in bit-reversal permutation, size `width`."]
     pub roots_of_unity: *const fr_t,
@@ -120,14 +124,21 @@ pub struct FFTSettings {
 fn bindgen_test_layout_FFTSettings() {
     const UNINIT: ::std::mem::MaybeUninit<FFTSettings> = ::std::mem::MaybeUninit::uninit();
     let ptr = UNINIT.as_ptr();
+    // `max_width` is a `uint64_t`, the remaining fields are pointers.
+    const PTR: usize = ::std::mem::size_of::<usize>();
+    const ALIGN: usize = if ::std::mem::align_of::<u64>() > PTR {
+        ::std::mem::align_of::<u64>()
+    } else {
+        PTR
+    };
     assert_eq!(
         ::std::mem::size_of::<FFTSettings>(),
-        32usize,
+        (8 + 3 * PTR).next_multiple_of(ALIGN),
         concat!("Size of: ", stringify!(FFTSettings))
     );
     assert_eq!(
         ::std::mem::align_of::<FFTSettings>(),
-        8usize,
+        ALIGN,
         concat!("Alignment of ", stringify!(FFTSettings))
     );
     assert_eq!(
@@ -152,7 +163,7 @@ fn bindgen_test_layout_FFTSettings() {
     );
     assert_eq!(
         unsafe { ::std::ptr::addr_of!((*ptr).reverse_roots_of_unity) as usize - ptr as usize },
-        16usize,
+        8 + PTR,
         concat!(
             "Offset of field: ",
             stringify!(FFTSettings),
@@ -162,7 +173,7 @@ fn bindgen_test_layout_FFTSettings() {
     );
     assert_eq!(
         unsafe { ::std::ptr::addr_of!((*ptr).roots_of_unity) as usize - ptr as usize },
-        24usize,
+        8 + 2 * PTR,
         concat!(
             "Offset of field: ",
             stringify!(FFTSettings),
@@ -194,14 +205,15 @@ unsafe impl Send for KZGSettings {}
 fn bindgen_test_layout_KZGSettings() {
     const UNINIT: ::std::mem::MaybeUninit<KZGSettings> = ::std::mem::MaybeUninit::uninit();
     let ptr = UNINIT.as_ptr();
+    const PTR: usize = ::std::mem::size_of::<usize>();
     assert_eq!(
         ::std::mem::size_of::<KZGSettings>(),
-        24usize,
+        3 * PTR,
         concat!("Size of: ", stringify!(KZGSettings))
     );
     assert_eq!(
         ::std::mem::align_of::<KZGSettings>(),
-        8usize,
+        PTR,
         concat!("Alignment of ", stringify!(KZGSettings))
     );
     assert_eq!(
@@ -216,7 +228,7 @@ fn bindgen_test_layout_KZGSettings() {
     );
     assert_eq!(
         unsafe { ::std::ptr::addr_of!((*ptr).g1_values) as usize - ptr as usize },
-        8usize,
+        PTR,
         concat!(
             "Offset of field: ",
             stringify!(KZGSettings),
@@ -226,7 +238,7 @@ fn bindgen_test_layout_KZGSettings() {
     );
     assert_eq!(
         unsafe { ::std::ptr::addr_of!((*ptr).g2_values) as usize - ptr as usize },
-        16usize,
+        2 * PTR,
         concat!(
             "Offset of field: ",
             stringify!(KZGSettings),
@@ -235,19 +247,117 @@ fn bindgen_test_layout_KZGSettings() {
         )
     );
 }
+#[cfg(feature = "mock")]
+pub use crate::mock::*;
+
+#[doc = " Custom allocation functions, with the same semantics as `malloc`, `calloc` and `free`."]
+pub type c_kzg_malloc_fn =
+    ::core::option::Option<unsafe extern "C" fn(size: usize) -> *mut ::core::ffi::c_void>;
+pub type c_kzg_calloc_fn = ::core::option::Option<
+    unsafe extern "C" fn(nmemb: usize, size: usize) -> *mut ::core::ffi::c_void,
+>;
+pub type c_kzg_free_fn =
+    ::core::option::Option<unsafe extern "C" fn(ptr: *mut ::core::ffi::c_void)>;
+#[doc = " Receives the reason a function failed: its return code, the source location and the failing check or a description"]
+#[doc = " of the failure. The strings are static and outlive the call."]
+pub type c_kzg_error_fn = ::core::option::Option<
+    unsafe extern "C" fn(
+        ret: C_KZG_RET,
+        file: *const ::core::ffi::c_char,
+        line: ::core::ffi::c_int,
+        message: *const ::core::ffi::c_char,
+    ),
+>;
+#[cfg(not(feature = "mock"))]
 extern "C" {
     #[doc = " Interface functions"]
+    pub fn c_kzg_set_allocator(
+        malloc_impl: c_kzg_malloc_fn,
+        calloc_impl: c_kzg_calloc_fn,
+        free_impl: c_kzg_free_fn,
+    );
+}
+#[cfg(not(feature = "mock"))]
+extern "C" {
+    pub fn c_kzg_set_error_callback(callback: c_kzg_error_fn);
+}
+#[cfg(not(feature = "mock"))]
+extern "C" {
+    pub fn blst_scalar_from_fr(ret: *mut blst_scalar, a: *const blst_fr);
+    pub fn blst_lendian_from_scalar(out: *mut byte, a: *const blst_scalar);
+}
+#[cfg(all(any(test, feature = "test-utils"), not(feature = "mock")))]
+extern "C" {
+    pub fn blst_p1_generator() -> *const blst_p1;
+    pub fn blst_p2_generator() -> *const blst_p2;
+    pub fn blst_p2_mult(out: *mut blst_p2, p: *const blst_p2, scalar: *const byte, nbits: usize);
+    pub fn blst_p1_compress(out: *mut byte, in_: *const blst_p1);
+    #[cfg(test)]
+    pub fn blst_p1_uncompress(out: *mut blst_p1_affine, in_: *const byte) -> BLST_ERROR;
+    #[cfg(test)]
+    pub fn blst_p1_in_g1(p: *const blst_p1) -> bool;
+}
+#[cfg(all(
+    any(test, feature = "test-utils", feature = "cache"),
+    not(feature = "mock")
+))]
+extern "C" {
+    pub fn blst_p2_compress(out: *mut byte, in_: *const blst_p2);
+}
+#[cfg(all(
+    any(test, feature = "test-utils", feature = "hiding"),
+    not(feature = "mock")
+))]
+extern "C" {
+    pub fn blst_p1_mult(out: *mut blst_p1, p: *const blst_p1, scalar: *const byte, nbits: usize);
+}
+#[cfg(all(feature = "hiding", not(feature = "mock")))]
+extern "C" {
+    pub fn blst_hash_to_g1(
+        out: *mut blst_p1,
+        msg: *const byte,
+        msg_len: usize,
+        DST: *const byte,
+        DST_len: usize,
+        aug: *const byte,
+        aug_len: usize,
+    );
+    pub fn blst_p1_add_or_double(out: *mut blst_p1, a: *const blst_p1, b: *const blst_p1);
+    pub fn blst_p1_cneg(p: *mut blst_p1, cbit: bool);
+}
+#[cfg(all(feature = "blst-interop", not(feature = "mock")))]
+extern "C" {
+    pub fn blst_p1_to_affine(out: *mut blst_p1_affine, in_: *const blst_p1);
+    #[cfg(test)]
+    pub fn blst_fp_from_uint64(ret: *mut blst_fp, a: *const u64);
+}
+pub type BLST_ERROR = ::core::ffi::c_uint;
+pub const BLST_ERROR_BLST_SUCCESS: BLST_ERROR = 0;
+#[cfg(not(feature = "mock"))]
+extern "C" {
+    pub fn blst_p1_from_affine(out: *mut blst_p1, in_: *const blst_p1_affine);
+    pub fn blst_p1_affine_on_curve(p: *const blst_p1_affine) -> bool;
+    pub fn blst_p1_affine_in_g1(p: *const blst_p1_affine) -> bool;
+    pub fn blst_p1_serialize(out: *mut byte, in_: *const blst_p1);
+    pub fn blst_p1_deserialize(out: *mut blst_p1_affine, in_: *const byte) -> BLST_ERROR;
+}
+#[cfg(not(feature = "mock"))]
+extern "C" {
     pub fn bytes_to_g1(out: *mut g1_t, in_: *const u8) -> C_KZG_RET;
 }
+#[cfg(not(feature = "mock"))]
 extern "C" {
     pub fn bytes_from_g1(out: *mut u8, in_: *const g1_t);
 }
+#[cfg(not(feature = "mock"))]
 extern "C" {
     pub fn bytes_to_bls_field(out: *mut BLSFieldElement, in_: *const u8) -> C_KZG_RET;
 }
+#[cfg(all(feature = "std", not(windows), not(feature = "mock")))]
 extern "C" {
     pub fn load_trusted_setup_file(out: *mut KZGSettings, in_: *mut FILE) -> C_KZG_RET;
 }
+#[cfg(not(feature = "mock"))]
 extern "C" {
     pub fn load_trusted_setup(
         out: *mut KZGSettings,
@@ -257,9 +367,33 @@ extern "C" {
         n2: usize,
     ) -> C_KZG_RET;
 }
+#[cfg(all(feature = "std", not(feature = "mock")))]
+extern "C" {
+    pub fn save_trusted_setup_lagrange(
+        out: *mut u8, /* (FIELD_ELEMENTS_PER_BLOB + 65) * 96 + 32 bytes */
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+}
+#[cfg(not(feature = "mock"))]
+extern "C" {
+    pub fn load_trusted_setup_lagrange(
+        out: *mut KZGSettings,
+        bytes: *const u8, /* (FIELD_ELEMENTS_PER_BLOB + 65) * 96 + 32 bytes */
+    ) -> C_KZG_RET;
+}
+#[cfg(not(feature = "mock"))]
+extern "C" {
+    pub fn load_trusted_setup_verify_only(
+        out: *mut KZGSettings,
+        g2_bytes: *const u8, /* n2 * 96 bytes */
+        n2: usize,
+    ) -> C_KZG_RET;
+}
+#[cfg(not(feature = "mock"))]
 extern "C" {
     pub fn free_trusted_setup(s: *mut KZGSettings);
 }
+#[cfg(not(feature = "mock"))]
 extern "C" {
     pub fn compute_aggregate_kzg_proof(
         out: *mut KZGProof,
@@ -268,6 +402,7 @@ extern "C" {
         s: *const KZGSettings,
     ) -> C_KZG_RET;
 }
+#[cfg(not(feature = "mock"))]
 extern "C" {
     pub fn verify_aggregate_kzg_proof(
         out: *mut bool,
@@ -278,9 +413,53 @@ extern "C" {
         s: *const KZGSettings,
     ) -> C_KZG_RET;
 }
+#[cfg(not(feature = "mock"))]
+extern "C" {
+    pub fn blob_to_kzg_commitment(
+        out: *mut KZGCommitment,
+        blob: *const u8,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+}
+#[cfg(not(feature = "mock"))]
+extern "C" {
+    pub fn blob_prefix_to_kzg_commitment(
+        out: *mut KZGCommitment,
+        field_elements: *const u8,
+        n: usize,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+}
+#[cfg(not(feature = "mock"))]
+extern "C" {
+    pub fn commit_coefficients(
+        out: *mut KZGCommitment,
+        coefficients: *const u8,
+        n: usize,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+}
+#[cfg(not(feature = "mock"))]
 extern "C" {
-    pub fn blob_to_kzg_commitment(out: *mut KZGCommitment, blob: *mut u8, s: *const KZGSettings);
+    pub fn compute_proof_monomial(
+        proof_out: *mut KZGProof,
+        y_out: *mut u8,
+        coefficients: *const u8,
+        n: usize,
+        z: *const u8,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
 }
+#[cfg(not(feature = "mock"))]
+extern "C" {
+    pub fn g1_linear_combination(
+        out: *mut g1_t,
+        points: *const g1_t,
+        scalars: *const BLSFieldElement,
+        n: usize,
+    ) -> C_KZG_RET;
+}
+#[cfg(not(feature = "mock"))]
 extern "C" {
     pub fn verify_kzg_proof(
         out: *mut bool,
@@ -291,3 +470,52 @@ extern "C" {
         s: *const KZGSettings,
     ) -> C_KZG_RET;
 }
+#[cfg(all(feature = "low-level", not(feature = "mock")))]
+extern "C" {
+    pub fn verify_kzg_proof_field_elements(
+        out: *mut bool,
+        commitment: *const KZGCommitment,
+        z: *const BLSFieldElement,
+        y: *const BLSFieldElement,
+        kzg_proof: *const KZGProof,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+}
+#[cfg(not(feature = "mock"))]
+extern "C" {
+    pub fn compute_equivalence_proof(
+        proof_out: *mut KZGProof,
+        y_out: *mut u8,
+        blob: *const u8,
+        commitment: *const KZGCommitment,
+        data_hash: *const u8,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+    pub fn verify_equivalence_proof(
+        out: *mut bool,
+        commitment: *const KZGCommitment,
+        data_hash: *const u8,
+        y: *const u8,
+        proof: *const KZGProof,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+}
+#[cfg(not(feature = "mock"))]
+extern "C" {
+    pub fn degree_bound_proof_length(degree_bound: usize) -> usize;
+    pub fn compute_degree_bound_proof(
+        proofs_out: *mut KZGProof,
+        coefficients: *const u8,
+        n: usize,
+        degree_bound: usize,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+    pub fn verify_degree_bound_proof(
+        out: *mut bool,
+        commitment: *const KZGCommitment,
+        degree_bound: usize,
+        proofs: *const KZGProof,
+        n: usize,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+}
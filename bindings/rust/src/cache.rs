@@ -0,0 +1,207 @@
+//! A bounded cache of validated commitments and verified aggregate proofs, so that a node which
+//! sees the same sidecar in its mempool and again at block import only checks it once.
+//!
+//! Commitments are keyed by their versioned hash, the hash transactions refer to blobs by. Proofs
+//! are keyed by a SHA-256 digest of the settings, blobs, commitments and proof they were verified
+//! with, and only successful verifications are cached. Both are evicted least recently used first.
+//!
+//! Enabled by the `cache` feature.
+
+use crate::{bindings, Blob, Error, KzgCommitment, KzgProof, KzgSettings, BYTES_PER_G2_POINT};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+
+/// The version byte of versioned hashes of KZG commitments, from EIP-4844.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+impl KzgCommitment {
+    /// The SHA-256 of the compressed commitment, with the first byte replaced by
+    /// `VERSIONED_HASH_VERSION_KZG`.
+    pub fn to_versioned_hash(&self) -> [u8; 32] {
+        versioned_hash(&self.to_bytes())
+    }
+}
+
+fn versioned_hash(commitment_bytes: &[u8]) -> [u8; 32] {
+    let mut hash: [u8; 32] = Sha256::digest(commitment_bytes).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// Identifies the settings a proof is verified with. Verification reads no other point of the setup
+/// than `[tau]_2`, so settings with the same one give the same verdicts.
+fn settings_identity(kzg_settings: &KzgSettings) -> [u8; BYTES_PER_G2_POINT] {
+    let mut bytes = [0; BYTES_PER_G2_POINT];
+    // The mock settings hold no points.
+    if !kzg_settings.0.g2_values.is_null() {
+        unsafe { bindings::blst_p2_compress(bytes.as_mut_ptr(), kzg_settings.0.g2_values.add(1)) };
+    }
+    bytes
+}
+
+/// The hits and misses of a `CommitmentCache` since it was created or last cleared.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub commitment_hits: u64,
+    pub commitment_misses: u64,
+    pub proof_hits: u64,
+    pub proof_misses: u64,
+}
+
+/// A map of at most `capacity` entries that evicts the least recently used one.
+struct Lru<V> {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<[u8; 32], (V, u64)>,
+    /// The keys by the tick they were last used at, oldest first.
+    order: BTreeMap<u64, [u8; 32]>,
+}
+
+impl<V> Lru<V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &[u8; 32]) -> Option<&V> {
+        let (value, last_used) = self.entries.get_mut(key)?;
+        self.order.remove(last_used);
+        self.tick += 1;
+        *last_used = self.tick;
+        self.order.insert(self.tick, *key);
+        Some(value)
+    }
+
+    fn insert(&mut self, key: [u8; 32], value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, last_used)) = self.entries.insert(key, (value, self.tick)) {
+            self.order.remove(&last_used);
+        } else if self.entries.len() > self.capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.insert(self.tick, key);
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+/// Remembers up to `capacity` validated commitments and as many verified aggregate proofs.
+///
+/// The cache takes `&mut self` even for lookups, which update the recency of entries; share it
+/// between threads behind a `Mutex`.
+pub struct CommitmentCache {
+    commitments: Lru<KzgCommitment>,
+    proofs: Lru<()>,
+    stats: CacheStats,
+}
+
+impl CommitmentCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            commitments: Lru::new(capacity),
+            proofs: Lru::new(capacity),
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Parses and validates a compressed commitment like `KzgCommitment::from_bytes`, unless a
+    /// commitment with the same versioned hash was validated before.
+    pub fn commitment(&mut self, bytes: &[u8]) -> Result<KzgCommitment, Error> {
+        let key = versioned_hash(bytes);
+        if let Some(commitment) = self.commitments.get(&key) {
+            self.stats.commitment_hits += 1;
+            return Ok(KzgCommitment(commitment.0));
+        }
+        self.stats.commitment_misses += 1;
+        let commitment = KzgCommitment::from_bytes(bytes)?;
+        self.commitments.insert(key, KzgCommitment(commitment.0));
+        Ok(commitment)
+    }
+
+    /// The validated commitment with the given versioned hash, if it is cached.
+    pub fn get(&mut self, versioned_hash: &[u8; 32]) -> Option<KzgCommitment> {
+        match self.commitments.get(versioned_hash) {
+            Some(commitment) => {
+                self.stats.commitment_hits += 1;
+                Some(KzgCommitment(commitment.0))
+            }
+            None => {
+                self.stats.commitment_misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Verifies `proof` like `KzgProof::verify_aggregate_kzg_proof`, unless the same blobs,
+    /// commitments and proof were verified successfully before, with the same trusted setup.
+    pub fn verify_aggregate_kzg_proof(
+        &mut self,
+        proof: &KzgProof,
+        blobs: &[Blob],
+        expected_kzg_commitments: &[KzgCommitment],
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, Error> {
+        if blobs.len() != expected_kzg_commitments.len() {
+            return proof.verify_aggregate_kzg_proof(blobs, expected_kzg_commitments, kzg_settings);
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(settings_identity(kzg_settings));
+        hasher.update((blobs.len() as u64).to_le_bytes());
+        for commitment in expected_kzg_commitments {
+            hasher.update(commitment.to_bytes());
+        }
+        hasher.update(proof.to_bytes());
+        for blob in blobs {
+            hasher.update(blob);
+        }
+        let key: [u8; 32] = hasher.finalize().into();
+
+        if self.proofs.get(&key).is_some() {
+            self.stats.proof_hits += 1;
+            return Ok(true);
+        }
+        self.stats.proof_misses += 1;
+        let verified =
+            proof.verify_aggregate_kzg_proof(blobs, expected_kzg_commitments, kzg_settings)?;
+        if verified {
+            self.proofs.insert(key, ());
+        }
+        Ok(verified)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// The number of cached commitments and proofs.
+    pub fn len(&self) -> usize {
+        self.commitments.len() + self.proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every entry and resets the statistics.
+    pub fn clear(&mut self) {
+        self.commitments.clear();
+        self.proofs.clear();
+        self.stats = CacheStats::default();
+    }
+}
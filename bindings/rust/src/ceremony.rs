@@ -0,0 +1,50 @@
+//! Loading of trusted setups in the JSON format of the KZG ceremony output, e.g.
+//! `trusted_setup_4096.json`, which lists the points as `0x` prefixed hex strings under
+//! `g1_monomial`, `g1_lagrange` and `g2_monomial`.
+//!
+//! Enabled by the `serde` feature.
+
+use crate::{
+    check_point_counts, decode_hex, Error, KzgSettings, TrustedSetupError, TrustedSetupPoints,
+};
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::Deserialize;
+
+/// The fields of the transcript that `load_trusted_setup` needs. The settings compute the Lagrange
+/// form of the g1 points themselves, so `g1_lagrange` is not read.
+#[derive(Deserialize)]
+struct CeremonySetup {
+    g1_monomial: Vec<String>,
+    g2_monomial: Vec<String>,
+}
+
+/// Parses the JSON output of the KZG ceremony into g1 and g2 points, in the form taken by
+/// `KzgSettings::load_trusted_setup`. The points are not checked to be on the curve.
+pub fn trusted_setup_from_json(json: &str) -> Result<TrustedSetupPoints, Error> {
+    let invalid_format = Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat);
+    let setup: CeremonySetup = serde_json::from_str(json).map_err(|_| invalid_format)?;
+    check_point_counts(setup.g1_monomial.len(), setup.g2_monomial.len())?;
+    let g1_bytes = setup
+        .g1_monomial
+        .iter()
+        .map(|point| decode_hex(point))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid_format)?;
+    let g2_bytes = setup
+        .g2_monomial
+        .iter()
+        .map(|point| decode_hex(point))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid_format)?;
+    Ok((g1_bytes, g2_bytes))
+}
+
+impl KzgSettings {
+    /// Loads a trusted setup from the JSON output of the KZG ceremony, without converting it to the
+    /// text format first.
+    pub fn load_trusted_setup_json(json: &str) -> Result<Self, Error> {
+        let (g1_bytes, g2_bytes) = trusted_setup_from_json(json)?;
+        Self::load_trusted_setup(g1_bytes, g2_bytes)
+    }
+}
@@ -0,0 +1,78 @@
+//! Packing of arbitrary bytes into blobs, to build test blobs from data rather than polynomials.
+//! Exported through `test_utils`, since the packing is not a standard one.
+//!
+//! Each field element carries 31 bytes of data in its low bytes and a zero top byte, which keeps
+//! it below `BLS_MODULUS`. The data is prefixed with its length as a little-endian `u64` and
+//! padded with zeros to fill the last blob.
+
+use crate::{Blob, BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT, FIELD_ELEMENTS_PER_BLOB};
+use alloc::vec::Vec;
+
+/// The number of data bytes carried by each field element.
+const DATA_BYTES_PER_FIELD_ELEMENT: usize = BYTES_PER_FIELD_ELEMENT - 1;
+
+/// The number of bytes `encode_blobs` stores in each blob, including the length prefix.
+pub const USABLE_BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * DATA_BYTES_PER_FIELD_ELEMENT;
+
+const LENGTH_PREFIX: usize = core::mem::size_of::<u64>();
+
+fn data_bytes(blobs: &[Blob]) -> impl Iterator<Item = &u8> {
+    blobs
+        .iter()
+        .flat_map(|blob| blob.chunks_exact(BYTES_PER_FIELD_ELEMENT))
+        .flat_map(|field_element| &field_element[..DATA_BYTES_PER_FIELD_ELEMENT])
+}
+
+/// Encodes `data` into as few blobs as possible, at least one. Every field element of the blobs is
+/// canonical, so they can be committed to.
+pub fn encode_blobs(data: &[u8]) -> Vec<Blob> {
+    let len = LENGTH_PREFIX + data.len();
+    let num_blobs = len.div_ceil(USABLE_BYTES_PER_BLOB).max(1);
+    let mut blobs: Vec<Blob> = Vec::with_capacity(num_blobs);
+    blobs.resize(num_blobs, [0; BYTES_PER_BLOB]);
+    let payload = (data.len() as u64)
+        .to_le_bytes()
+        .into_iter()
+        .chain(data.iter().copied());
+    let slots = blobs
+        .iter_mut()
+        .flat_map(|blob| blob.chunks_exact_mut(BYTES_PER_FIELD_ELEMENT))
+        .flat_map(|field_element| &mut field_element[..DATA_BYTES_PER_FIELD_ELEMENT]);
+    for (slot, byte) in slots.zip(payload) {
+        *slot = byte;
+    }
+    blobs
+}
+
+/// Decodes the data encoded by `encode_blobs`. Returns `None` unless the blobs are exactly the
+/// ones `encode_blobs` would return for the data, e.g. if the length prefix does not fit the number
+/// of blobs or a top or padding byte is not zero.
+pub fn decode_blobs(blobs: &[Blob]) -> Option<Vec<u8>> {
+    if blobs
+        .iter()
+        .flat_map(|blob| blob.chunks_exact(BYTES_PER_FIELD_ELEMENT))
+        .any(|field_element| field_element[DATA_BYTES_PER_FIELD_ELEMENT] != 0)
+    {
+        return None;
+    }
+
+    let mut bytes = data_bytes(blobs);
+    let mut prefix = [0; LENGTH_PREFIX];
+    for (byte, encoded) in prefix.iter_mut().zip(&mut bytes) {
+        *byte = *encoded;
+    }
+    let len = usize::try_from(u64::from_le_bytes(prefix)).ok()?;
+    let expected_blobs = len
+        .checked_add(LENGTH_PREFIX)?
+        .div_ceil(USABLE_BYTES_PER_BLOB)
+        .max(1);
+    if blobs.len() != expected_blobs {
+        return None;
+    }
+
+    let data: Vec<u8> = (&mut bytes).take(len).copied().collect();
+    if bytes.any(|byte| *byte != 0) {
+        return None;
+    }
+    Some(data)
+}
@@ -0,0 +1,92 @@
+//! Pedersen-blinded ("hiding") commitments, for schemes that publish a commitment to data before
+//! revealing the data.
+//!
+//! A hiding commitment is the KZG commitment to a blob plus `r * H`, where `r` is a secret blinding
+//! factor and `H` is a G1 generator derived by hashing to the curve, so that nobody knows its
+//! discrete logarithm. Until `r` is revealed the commitment says nothing about the blob. Revealing
+//! `r` opens it: `unblind` recovers the ordinary commitment, against which the proofs of this crate
+//! verify as usual.
+//!
+//! Enabled by the `hiding` feature.
+
+use crate::bindings::{self, g1_t};
+use crate::{Blob, BlsFieldElement, Error, KzgCommitment, KzgSettings};
+use core::mem::MaybeUninit;
+use core::ptr;
+
+/// The domain separation tag of the hash to the curve that derives `H`.
+const BLINDING_GENERATOR_DST: &[u8] =
+    b"C_KZG_4844_HIDING_GENERATOR_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// Returns `blinding_factor * H`.
+fn blinding_term(blinding_factor: &BlsFieldElement) -> g1_t {
+    let mut generator = MaybeUninit::<g1_t>::uninit();
+    let mut term = MaybeUninit::<g1_t>::uninit();
+    unsafe {
+        bindings::blst_hash_to_g1(
+            generator.as_mut_ptr(),
+            ptr::null(),
+            0,
+            BLINDING_GENERATOR_DST.as_ptr(),
+            BLINDING_GENERATOR_DST.len(),
+            ptr::null(),
+            0,
+        );
+        bindings::blst_p1_mult(
+            term.as_mut_ptr(),
+            generator.as_ptr(),
+            blinding_factor.to_bytes().as_ptr(),
+            255,
+        );
+        term.assume_init()
+    }
+}
+
+impl KzgCommitment {
+    /// Commits to `blob` hiding it behind `blinding_factor`, which must be drawn uniformly at random
+    /// for every commitment and kept secret until the commitment is opened. A zero blinding factor
+    /// gives the ordinary commitment, which hides nothing.
+    pub fn commit_hiding(
+        blob: &Blob,
+        blinding_factor: &BlsFieldElement,
+        kzg_settings: &KzgSettings,
+    ) -> Result<Self, Error> {
+        let commitment = Self::blob_to_kzg_commitment_ref(blob, kzg_settings)?;
+        let term = blinding_term(blinding_factor);
+        let mut hiding = MaybeUninit::<g1_t>::uninit();
+        unsafe {
+            bindings::blst_p1_add_or_double(hiding.as_mut_ptr(), &commitment.0, &term);
+            Ok(Self(hiding.assume_init()))
+        }
+    }
+
+    /// Removes the blinding of a commitment returned by `commit_hiding`, giving the ordinary
+    /// commitment to the blob if `blinding_factor` is the one it was created with.
+    pub fn unblind(&self, blinding_factor: &BlsFieldElement) -> Self {
+        let mut term = blinding_term(blinding_factor);
+        let mut commitment = MaybeUninit::<g1_t>::uninit();
+        unsafe {
+            bindings::blst_p1_cneg(&mut term, true);
+            bindings::blst_p1_add_or_double(commitment.as_mut_ptr(), &self.0, &term);
+            Self(commitment.assume_init())
+        }
+    }
+
+    /// Checks that this hiding commitment opens to `blob` with `blinding_factor`. With the `subtle`
+    /// feature the unblinded commitment, which depends on the secret blinding factor, is compared
+    /// in constant time.
+    pub fn verify_hiding_opening(
+        &self,
+        blob: &Blob,
+        blinding_factor: &BlsFieldElement,
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, Error> {
+        let commitment = Self::blob_to_kzg_commitment_ref(blob, kzg_settings)?;
+        let unblinded = self.unblind(blinding_factor);
+        #[cfg(feature = "subtle")]
+        let opens = bool::from(subtle::ConstantTimeEq::ct_eq(&unblinded, &commitment));
+        #[cfg(not(feature = "subtle"))]
+        let opens = unblinded.to_bytes() == commitment.to_bytes();
+        Ok(opens)
+    }
+}
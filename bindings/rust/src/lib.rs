@@ -1,48 +1,585 @@
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
+extern crate alloc;
+
+// blst and the layout of the bindings (`size_t` as `usize`) only hold for 32-bit and 64-bit targets.
+#[cfg(not(any(target_pointer_width = "32", target_pointer_width = "64")))]
+compile_error!("c-kzg only supports targets with 32-bit or 64-bit pointers");
+
+#[cfg(feature = "alloc-stats")]
+mod alloc_stats;
 mod bindings;
-use bindings::{g1_t, C_KZG_RET};
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "serde")]
+mod ceremony;
+#[cfg(any(test, feature = "test-utils"))]
+mod codec;
+#[cfg(feature = "hiding")]
+mod hiding;
+#[cfg(feature = "low-level")]
+mod low_level;
+pub mod metrics;
+#[cfg(feature = "mock")]
+mod mock;
+#[cfg(any(test, feature = "spec-tests"))]
+pub mod spec_tests;
+mod ssz;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+/// With the `tracing` feature, enters a debug span around the rest of the enclosing block, so
+/// subscribers see the name, input sizes and duration of each call into the C library.
+macro_rules! ffi_span {
+    ($name:literal $(, $field:ident = $value:expr)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name $(, $field = $value)*).entered();
+    };
+}
+
+use alloc::alloc::{alloc_zeroed, handle_alloc_error, Layout};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use bindings::g1_t;
+use core::ffi::{c_char, c_int, CStr};
+use core::fmt;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicPtr, Ordering};
+#[cfg(all(feature = "std", not(windows)))]
 use libc::fopen;
+use metrics::KzgOperation;
+#[cfg(all(feature = "std", not(windows)))]
 use std::ffi::CString;
-use std::mem::MaybeUninit;
+#[cfg(all(feature = "std", unix))]
 use std::os::unix::prelude::OsStrExt;
-use std::path::PathBuf;
+#[cfg(all(feature = "std", target_os = "wasi"))]
+use std::os::wasi::ffi::OsStrExt;
+#[cfg(feature = "std")]
+use std::path::Path;
 
 pub use bindings::{
     Blob, BYTES_PER_BLOB, BYTES_PER_COMMITMENT, BYTES_PER_FIELD_ELEMENT, BYTES_PER_PROOF,
-    FIAT_SHAMIR_PROTOCOL_DOMAIN, FIELD_ELEMENTS_PER_BLOB,
+    DEGREE_BOUND_PROTOCOL_DOMAIN, EQUIVALENCE_PROTOCOL_DOMAIN, FIAT_SHAMIR_PROTOCOL_DOMAIN,
+    FIELD_ELEMENTS_PER_BLOB,
 };
 
 pub const BYTES_PER_G1_POINT: usize = 48;
+/// The length of the uncompressed encoding of a G1 point, the big-endian `x` and `y` coordinates.
+pub const BYTES_PER_G1_POINT_UNCOMPRESSED: usize = 96;
 pub const BYTES_PER_G2_POINT: usize = 96;
 
 /// Number of G2 points required for the kzg trusted setup.
 /// 65 is fixed and is used for providing multiproofs up to 64 field elements.
 const NUM_G2_POINTS: usize = 65;
 
-#[derive(Debug)]
+/// The bytes `blob_to_kzg_commitment_from_reader` reads at a time, a multiple of
+/// `BYTES_PER_FIELD_ELEMENT`.
+#[cfg(feature = "std")]
+const READ_CHUNK_SIZE: usize = 4096;
+
+/// The order of the scalar field of BLS12-381, as little-endian 64-bit limbs.
+pub const BLS_MODULUS: [u64; 4] = [
+    0xffffffff00000001,
+    0x53bda402fffe5bfe,
+    0x3339d80809a1d805,
+    0x73eda753299d7d48,
+];
+
+/// `BLS_MODULUS` in the little-endian byte order of field elements. It is not a field element
+/// itself: `BlsFieldElement::bytes_to_bls_field` only accepts values below it.
+pub const BLS_MODULUS_BYTES: [u8; BYTES_PER_FIELD_ELEMENT] = limbs_to_bytes(BLS_MODULUS);
+
+/// The generator of the multiplicative group of the scalar field used to derive the roots of unity.
+pub const PRIMITIVE_ROOT: u64 = 7;
+
+/// The first 32 roots of unity, as little-endian 64-bit limbs. Entry `i` is the primitive `2**i`-th
+/// root of unity `pow(PRIMITIVE_ROOT, (BLS_MODULUS - 1) // 2**i, BLS_MODULUS)`.
+#[rustfmt::skip]
+pub const SCALE2_ROOT_OF_UNITY: [[u64; 4]; 32] = [
+    [0x0000000000000001, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xffffffff00000000, 0x53bda402fffe5bfe, 0x3339d80809a1d805, 0x73eda753299d7d48],
+    [0x0001000000000000, 0xec03000276030000, 0x8d51ccce760304d0, 0x0000000000000000],
+    [0x7228fd3397743f7a, 0xb38b21c28713b700, 0x8c0625cd70d77ce2, 0x345766f603fa66e7],
+    [0x53ea61d87742bcce, 0x17beb312f20b6f76, 0xdd1c0af834cec32c, 0x20b1ce9140267af9],
+    [0x360c60997369df4e, 0xbf6e88fb4c38fb8a, 0xb4bcd40e22f55448, 0x50e0903a157988ba],
+    [0x8140d032f0a9ee53, 0x2d967f4be2f95155, 0x14a1e27164d8fdbd, 0x45af6345ec055e4d],
+    [0x5130c2c1660125be, 0x98d0caac87f5713c, 0xb7c68b4d7fdd60d0, 0x6898111413588742],
+    [0x4935bd2f817f694b, 0x0a0865a899e8deff, 0x6b368121ac0cf4ad, 0x4f9b4098e2e9f12e],
+    [0x4541b8ff2ee0434e, 0xd697168a3a6000fe, 0x39feec240d80689f, 0x095166525526a654],
+    [0x3c28d666a5c2d854, 0xea437f9626fc085e, 0x8f4de02c0f776af3, 0x325db5c3debf77a1],
+    [0x4a838b5d59cd79e5, 0x55ea6811be9c622d, 0x09f1ca610a08f166, 0x6d031f1b5c49c834],
+    [0xe206da11a5d36306, 0x0ad1347b378fbf96, 0xfc3e8acfe0f8245f, 0x564c0a11a0f704f4],
+    [0x6fdd00bfc78c8967, 0x146b58bc434906ac, 0x2ccddea2972e89ed, 0x485d512737b1da3d],
+    [0x034d2ff22a5ad9e1, 0xae4622f6a9152435, 0xdc86b01c0d477fa6, 0x56624634b500a166],
+    [0xfbd047e11279bb6e, 0xc8d5f51db3f32699, 0x483405417a0cbe39, 0x3291357ee558b50d],
+    [0xd7118f85cd96b8ad, 0x67a665ae1fcadc91, 0x88f39a78f1aeb578, 0x2155379d12180caa],
+    [0x08692405f3b70f10, 0xcd7f2bd6d0711b7d, 0x473a2eef772c33d6, 0x224262332d8acbf4],
+    [0x6f421a7d8ef674fb, 0xbb97a3bf30ce40fd, 0x652f717ae1c34bb0, 0x2d3056a530794f01],
+    [0x194e8c62ecb38d9d, 0xad8e16e84419c750, 0xdf625e80d0adef90, 0x520e587a724a6955],
+    [0xfece7e0e39898d4b, 0x2f69e02d265e09d9, 0xa57a6e07cb98de4a, 0x03e1c54bcb947035],
+    [0xcd3979122d3ea03a, 0x46b3105f04db5844, 0xc70d0874b0691d4e, 0x47c8b5817018af4f],
+    [0xc6e7a6ffb08e3363, 0xe08fec7c86389bee, 0xf2d38f10fbb8d1bb, 0x0abe6a5e5abcaa32],
+    [0x5616c57de0ec9eae, 0xc631ffb2585a72db, 0x5121af06a3b51e3c, 0x73560252aa0655b2],
+    [0x92cf4deb77bd779c, 0x72cf6a8029b7d7bc, 0x6e0bcd91ee762730, 0x291cf6d68823e687],
+    [0xce32ef844e11a51e, 0xc0ba12bb3da64ca5, 0x0454dc1edc61a1a3, 0x019fe632fd328739],
+    [0x531a11a0d2d75182, 0x02c8118402867ddc, 0x116168bffbedc11d, 0x0a0a77a3b1980c0d],
+    [0xe2d0a7869f0319ed, 0xb94f1101b1d7a628, 0xece8ea224f31d25d, 0x23397a9300f8f98b],
+    [0xd7b688830a4f2089, 0x6558e9e3f6ac7b41, 0x99e276b571905a7d, 0x52dd465e2f094256],
+    [0x474650359d8e211b, 0x84d37b826214abc6, 0x8da40c1ef2bb4598, 0x0c83ea7744bf1bee],
+    [0x694341f608c9dd56, 0xed3a181fabb30adc, 0x1339a815da8b398f, 0x2c6d4e4511657e1e],
+    [0x63e7cb4906ffc93f, 0xf070bb00e28a193d, 0xad1715b02e5713b5, 0x4b5371495990693f],
+];
+
+/// `SCALE2_ROOT_OF_UNITY` in the little-endian byte order of field elements.
+pub const SCALE2_ROOT_OF_UNITY_BYTES: [[u8; BYTES_PER_FIELD_ELEMENT]; 32] = {
+    let mut bytes = [[0; BYTES_PER_FIELD_ELEMENT]; 32];
+    let mut i = 0;
+    while i < 32 {
+        bytes[i] = limbs_to_bytes(SCALE2_ROOT_OF_UNITY[i]);
+        i += 1;
+    }
+    bytes
+};
+
+const fn limbs_to_bytes(limbs: [u64; 4]) -> [u8; BYTES_PER_FIELD_ELEMENT] {
+    let mut bytes = [0; BYTES_PER_FIELD_ELEMENT];
+    let mut i = 0;
+    while i < BYTES_PER_FIELD_ELEMENT {
+        bytes[i] = (limbs[i / 8] >> (8 * (i % 8))) as u8;
+        i += 1;
+    }
+    bytes
+}
+
+/// The errors of this crate. More variants may be added without a major version bump, so matches
+/// on it need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Error {
-    /// The KZG proof is invalid.
-    InvalidKzgProof(String),
-    /// The KZG commitment is invalid.
-    InvalidKzgCommitment(String),
+    /// The KZG proof does not have the expected byte length.
+    InvalidKzgProof { expected: usize, actual: usize },
+    /// The KZG commitment does not have the expected byte length.
+    InvalidKzgCommitment { expected: usize, actual: usize },
     /// The provided trusted setup is invalid.
-    InvalidTrustedSetup(String),
+    InvalidTrustedSetup(TrustedSetupError),
+    /// The point is not a valid G1 point, e.g. not on the curve or not 48 bytes long.
+    InvalidG1Point,
+    /// A field element is not canonical, i.e. not smaller than `BLS_MODULUS`. The position
+    /// counts field elements across all blobs passed to the operation. With the `constant-time`
+    /// feature the operations that commit to or prove blobs and field elements hide the position,
+    /// and fail with `Error::CError` for the argument `"field element"` instead.
+    InvalidFieldElement { position: usize },
+    /// A string passed to a `from_hex` constructor is not valid hex of the expected length.
+    InvalidHexFormat,
+    /// A `_cancellable` operation was abandoned because `should_cancel` returned `true`.
+    Cancelled,
+    /// The settings were loaded with `KzgSettings::load_trusted_setup_verify_only` and have no g1
+    /// points to compute a commitment or proof with.
+    MissingG1Points { operation: &'static str },
+    /// The trusted setup file could not be opened or read. Holds the OS error code, whose message
+    /// is part of the `Display` output.
+    #[cfg(feature = "std")]
+    IoError { code: i32 },
+    /// Reading from an `std::io::Read` failed, with `std::io::ErrorKind::UnexpectedEof` if it ended
+    /// early.
+    #[cfg(feature = "std")]
+    ReadError { kind: std::io::ErrorKind },
+    /// Two arguments that must have the same length do not.
+    MismatchedLengths {
+        operation: &'static str,
+        first: &'static str,
+        first_len: usize,
+        second: &'static str,
+        second_len: usize,
+    },
     /// The underlying c-kzg library returned an error.
+    CError {
+        /// The operation that failed, e.g. `"verify_kzg_proof"`.
+        operation: &'static str,
+        /// The argument that was rejected, if it is known.
+        argument: Option<&'static str>,
+        ret: C_KZG_RET,
+    },
+}
+
+impl Error {
+    #[cfg(all(feature = "std", not(windows)))]
+    fn last_os_error() -> Self {
+        Self::IoError {
+            code: std::io::Error::last_os_error()
+                .raw_os_error()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Names the operation and argument of an `Error::CError` converted from a `C_KZG_RET`.
+    fn in_operation(self, operation: &'static str, argument: Option<&'static str>) -> Self {
+        match self {
+            Self::CError { ret, .. } => Self::CError {
+                operation,
+                argument,
+                ret,
+            },
+            error => error,
+        }
+    }
+}
+
+/// Converts a return code of the C library into `Error::CError`, with `"c-kzg"` as the operation.
+/// `C_KZG_OK` is not an error and should not be converted.
+impl From<C_KZG_RET> for Error {
+    fn from(ret: C_KZG_RET) -> Self {
+        Self::CError {
+            operation: "c-kzg",
+            argument: None,
+            ret,
+        }
+    }
+}
+
+/// The reason a trusted setup was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustedSetupError {
+    /// The setup does not contain `FIELD_ELEMENTS_PER_BLOB` g1 points.
+    InvalidG1PointCount { expected: usize, actual: usize },
+    /// The setup does not contain 65 g2 points.
+    InvalidG2PointCount { expected: usize, actual: usize },
+    /// The path to the trusted setup file contains a nul byte.
+    InvalidFilePath,
+    /// The setup is not in the text or binary trusted setup format.
+    InvalidFormat,
+    /// The underlying c-kzg library failed to load the setup.
     CError(C_KZG_RET),
 }
 
+impl C_KZG_RET {
+    /// Converts a code returned by the C library. The code is read as a plain integer, since a
+    /// value without a variant would be undefined behavior in an enum, and codes this crate does
+    /// not know are reported as `C_KZG_ERROR`.
+    fn from_raw(ret: bindings::C_KZG_RET) -> Self {
+        match ret {
+            bindings::C_KZG_RET_C_KZG_OK => Self::C_KZG_OK,
+            bindings::C_KZG_RET_C_KZG_BADARGS => Self::C_KZG_BADARGS,
+            bindings::C_KZG_RET_C_KZG_ERROR => Self::C_KZG_ERROR,
+            bindings::C_KZG_RET_C_KZG_MALLOC => Self::C_KZG_MALLOC,
+            _ => Self::C_KZG_ERROR,
+        }
+    }
+
+    /// Whether the inputs were rejected, i.e. `C_KZG_BADARGS`.
+    pub fn is_bad_args(&self) -> bool {
+        matches!(self, Self::C_KZG_BADARGS)
+    }
+
+    /// Whether the library failed for a reason other than its inputs, i.e. `C_KZG_ERROR` or
+    /// `C_KZG_MALLOC`. Memory may have been leaked in that case.
+    pub fn is_internal(&self) -> bool {
+        matches!(self, Self::C_KZG_ERROR | Self::C_KZG_MALLOC)
+    }
+}
+
+impl fmt::Display for C_KZG_RET {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::C_KZG_OK => "success",
+            Self::C_KZG_BADARGS => "the supplied data is invalid in some way",
+            Self::C_KZG_ERROR => {
+                "internal error, this should never occur and may indicate a bug in the library"
+            }
+            Self::C_KZG_MALLOC => "could not allocate memory",
+        })
+    }
+}
+
+impl fmt::Display for TrustedSetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidG1PointCount { expected, actual } => {
+                write!(f, "expected {} g1 points, got {}", expected, actual)
+            }
+            Self::InvalidG2PointCount { expected, actual } => {
+                write!(f, "expected {} g2 points, got {}", expected, actual)
+            }
+            Self::InvalidFilePath => f.write_str("the trusted setup file path contains a nul byte"),
+            Self::InvalidFormat => f.write_str("the trusted setup is not in the expected format"),
+            Self::CError(_) => f.write_str("the c-kzg library could not load the trusted setup"),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidKzgProof { expected, actual } => {
+                write!(
+                    f,
+                    "expected a {} byte KZG proof, got {} bytes",
+                    expected, actual
+                )
+            }
+            Self::InvalidKzgCommitment { expected, actual } => write!(
+                f,
+                "expected a {} byte KZG commitment, got {} bytes",
+                expected, actual
+            ),
+            Self::InvalidTrustedSetup(_) => f.write_str("invalid trusted setup"),
+            Self::InvalidG1Point => f.write_str("invalid G1 point"),
+            Self::InvalidFieldElement { position } => {
+                write!(f, "field element {} is not canonical", position)
+            }
+            Self::InvalidHexFormat => f.write_str("invalid hex string"),
+            Self::Cancelled => f.write_str("the operation was cancelled"),
+            Self::MissingG1Points { operation } => write!(
+                f,
+                "{}: the settings have no g1 points, they were loaded to verify only",
+                operation
+            ),
+            #[cfg(feature = "std")]
+            Self::IoError { code } => write!(
+                f,
+                "could not read the trusted setup file: {}",
+                std::io::Error::from_raw_os_error(*code)
+            ),
+            #[cfg(feature = "std")]
+            Self::ReadError { kind } => write!(f, "could not read the input: {}", kind),
+            Self::MismatchedLengths {
+                operation,
+                first,
+                first_len,
+                second,
+                second_len,
+            } => write!(
+                f,
+                "{}: {} length {} does not match {} length {}",
+                operation, first, first_len, second, second_len
+            ),
+            Self::CError {
+                operation,
+                argument: Some(argument),
+                ..
+            } => write!(f, "{}: invalid {}", operation, argument),
+            Self::CError {
+                operation,
+                argument: None,
+                ..
+            } => write!(f, "{}: the c-kzg library returned an error", operation),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for C_KZG_RET {}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TrustedSetupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::CError(ret) => Some(ret),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidTrustedSetup(error) => Some(error),
+            Self::CError { ret, .. } => Some(ret),
+            _ => None,
+        }
+    }
+}
+
+pub use bindings::{c_kzg_calloc_fn, c_kzg_free_fn, c_kzg_malloc_fn};
+
+#[cfg(feature = "alloc-stats")]
+pub use alloc_stats::{alloc_stats, track_allocations, AllocStats};
+#[cfg(feature = "cache")]
+pub use cache::{CacheStats, CommitmentCache, VERSIONED_HASH_VERSION_KZG};
+#[cfg(feature = "serde")]
+pub use ceremony::trusted_setup_from_json;
+#[cfg(feature = "low-level")]
+pub use low_level::blob_to_polynomial;
+pub use ssz::{trusted_setup_from_ssz, trusted_setup_to_ssz};
+
+/// The return code of the C library. It is non-exhaustive, so that new codes can be added without
+/// breaking matches downstream.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum C_KZG_RET {
+    /// Success!
+    C_KZG_OK,
+    /// The supplied data is invalid in some way.
+    C_KZG_BADARGS,
+    /// Internal error, this should never occur and may indicate a bug in the library.
+    C_KZG_ERROR,
+    /// Could not allocate memory.
+    C_KZG_MALLOC,
+}
+
+/// The C representation of the settings, see `KzgSettings::from_raw` and `KzgSettings::into_raw`.
+pub use bindings::{FFTSettings, KZGSettings};
+
+/// Affine G1 points and their coordinates, with the same layout as the types of the same name in
+/// the `blst` crate.
+#[cfg(feature = "blst-interop")]
+pub use bindings::{blst_fp, blst_p1_affine};
+
+/// The versions of the KZG code this crate runs, see `version`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionInfo {
+    /// The version of this crate.
+    pub crate_version: &'static str,
+    /// The version of the C library. It has no version of its own and is built from the sources
+    /// shipped with this crate, so this is the crate version, or `mock` with the `mock` feature.
+    pub c_library_version: &'static str,
+    /// The spec milestones whose KZG functions are implemented.
+    pub spec_milestones: &'static [&'static str],
+}
+
+impl fmt::Display for VersionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "c-kzg {} (C library {}",
+            self.crate_version, self.c_library_version
+        )?;
+        for milestone in self.spec_milestones {
+            write!(f, ", {}", milestone)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Returns the versions of the KZG code, e.g. for nodes to log at startup.
+pub fn version() -> VersionInfo {
+    VersionInfo {
+        crate_version: env!("CARGO_PKG_VERSION"),
+        c_library_version: if cfg!(feature = "mock") {
+            "mock"
+        } else {
+            env!("CARGO_PKG_VERSION")
+        },
+        spec_milestones: &["eip4844"],
+    }
+}
+
+/// Replaces the functions the C library uses to allocate and free memory, e.g. to serve the
+/// scratch buffers of proving and verification from an arena. Passing `None` restores the libc
+/// default for that function.
+///
+/// # Safety
+///
+/// The functions must behave like `malloc`, `calloc` and `free`. This must not be called while any
+/// other function of this crate is running on another thread, and memory is always released with
+/// the current `free_impl`, so every `KzgSettings` created with a previous allocator must have been
+/// dropped before switching.
+pub unsafe fn set_allocator(
+    malloc_impl: c_kzg_malloc_fn,
+    calloc_impl: c_kzg_calloc_fn,
+    free_impl: c_kzg_free_fn,
+) {
+    bindings::c_kzg_set_allocator(malloc_impl, calloc_impl, free_impl)
+}
+
+/// Why the C library returned an error, as passed to the callback of `set_error_callback`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CErrorReport {
+    pub ret: C_KZG_RET,
+    /// The source file of the C library that returned the error.
+    pub file: &'static str,
+    pub line: u32,
+    /// The failing check, e.g. `blst_scalar_fr_check(&tmp)`, or a description of the failure.
+    pub message: &'static str,
+}
+
+static ERROR_CALLBACK: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+unsafe extern "C" fn report_error(
+    ret: bindings::C_KZG_RET,
+    file: *const c_char,
+    line: c_int,
+    message: *const c_char,
+) {
+    let callback = ERROR_CALLBACK.load(Ordering::Acquire);
+    if callback.is_null() {
+        return;
+    }
+    let callback = core::mem::transmute::<*mut (), fn(&CErrorReport)>(callback);
+    callback(&CErrorReport {
+        ret: C_KZG_RET::from_raw(ret),
+        file: CStr::from_ptr(file).to_str().unwrap_or_default(),
+        line: line as u32,
+        message: CStr::from_ptr(message).to_str().unwrap_or_default(),
+    });
+}
+
+/// Registers a callback that the C library calls with the reason of every error it returns, e.g.
+/// the check that rejected a `C_KZG_BADARGS` input, so that failures can be logged with detail.
+/// Passing `None` disables reporting.
+///
+/// # Safety
+///
+/// The C library reads the callback without synchronization, so set it once at startup, before
+/// any other function of this crate is called on any thread. The callback runs on the thread that
+/// made the failing call and must not panic.
+pub unsafe fn set_error_callback(callback: Option<fn(&CErrorReport)>) {
+    match callback {
+        Some(callback) => {
+            ERROR_CALLBACK.store(callback as *mut (), Ordering::Release);
+            bindings::c_kzg_set_error_callback(Some(report_error));
+        }
+        None => {
+            bindings::c_kzg_set_error_callback(None);
+            ERROR_CALLBACK.store(core::ptr::null_mut(), Ordering::Release);
+        }
+    }
+}
+
+/// Decompresses a G1 point. Returns `Error::InvalidG1Point` unless `bytes` is a 48 byte compressed
+/// point in the G1 subgroup.
 pub fn bytes_to_g1(bytes: &[u8]) -> Result<g1_t, Error> {
+    // The C library reads exactly `BYTES_PER_G1_POINT` bytes.
+    if bytes.len() != BYTES_PER_G1_POINT {
+        return Err(Error::InvalidG1Point);
+    }
     let mut g1_point = MaybeUninit::<g1_t>::uninit();
     unsafe {
-        let res = bindings::bytes_to_g1(g1_point.as_mut_ptr(), bytes.as_ptr());
-        if let C_KZG_RET::C_KZG_OK = res {
-            Ok(g1_point.assume_init())
-        } else {
-            Err(Error::CError(res))
+        let res = C_KZG_RET::from_raw(bindings::bytes_to_g1(g1_point.as_mut_ptr(), bytes.as_ptr()));
+        match res {
+            C_KZG_RET::C_KZG_OK => Ok(g1_point.assume_init()),
+            C_KZG_RET::C_KZG_BADARGS => Err(Error::InvalidG1Point),
+            _ => Err(Error::from(res).in_operation("bytes_to_g1", None)),
+        }
+    }
+}
+
+#[cfg(feature = "blst-interop")]
+fn g1_to_affine(g1_point: &g1_t) -> blst_p1_affine {
+    let mut affine = MaybeUninit::<blst_p1_affine>::uninit();
+    unsafe {
+        bindings::blst_p1_to_affine(affine.as_mut_ptr(), g1_point);
+        affine.assume_init()
+    }
+}
+
+/// Checks that an affine point, which unlike a compressed one may not even be on the curve, is on
+/// the curve and in the G1 subgroup. The C `bytes_to_g1` checks the same for compressed points.
+fn g1_from_affine(affine: &bindings::blst_p1_affine) -> Result<g1_t, Error> {
+    let mut g1_point = MaybeUninit::<g1_t>::uninit();
+    unsafe {
+        if !bindings::blst_p1_affine_on_curve(affine) || !bindings::blst_p1_affine_in_g1(affine) {
+            return Err(Error::InvalidG1Point);
         }
+        bindings::blst_p1_from_affine(g1_point.as_mut_ptr(), affine);
+        Ok(g1_point.assume_init())
     }
 }
 
@@ -52,6 +589,77 @@ pub fn bytes_from_g1(g1_point: g1_t) -> [u8; BYTES_PER_G1_POINT] {
     bytes
 }
 
+/// Decodes an uncompressed G1 point. Unlike blst, rejects compressed encodings, which have the most
+/// significant bit set.
+fn g1_from_uncompressed(bytes: &[u8; BYTES_PER_G1_POINT_UNCOMPRESSED]) -> Result<g1_t, Error> {
+    if bytes[0] & 0x80 != 0 {
+        return Err(Error::InvalidG1Point);
+    }
+    let mut affine = MaybeUninit::<bindings::blst_p1_affine>::uninit();
+    unsafe {
+        if bindings::blst_p1_deserialize(affine.as_mut_ptr(), bytes.as_ptr())
+            != bindings::BLST_ERROR_BLST_SUCCESS
+        {
+            return Err(Error::InvalidG1Point);
+        }
+        g1_from_affine(&affine.assume_init())
+    }
+}
+
+fn g1_to_uncompressed(g1_point: &g1_t) -> [u8; BYTES_PER_G1_POINT_UNCOMPRESSED] {
+    let mut bytes = [0; BYTES_PER_G1_POINT_UNCOMPRESSED];
+    unsafe { bindings::blst_p1_serialize(bytes.as_mut_ptr(), g1_point) }
+    bytes
+}
+
+/// Computes `sum(scalars[i] * points[i])` over compressed G1 points and canonical little-endian
+/// field elements, with the multi-scalar multiplication the library uses for commitments, so that
+/// protocols on the same curve do not need another implementation. The sum of no points is the
+/// point at infinity.
+///
+/// Fails with `Error::InvalidG1Point` if a point is not on the curve or not in the G1 subgroup,
+/// which `bytes_to_g1` checks, and with `Error::InvalidFieldElement` if a scalar is not canonical.
+pub fn g1_lincomb(
+    points: &[[u8; BYTES_PER_G1_POINT]],
+    scalars: &[[u8; BYTES_PER_FIELD_ELEMENT]],
+) -> Result<[u8; BYTES_PER_G1_POINT], Error> {
+    if points.len() != scalars.len() {
+        return Err(Error::MismatchedLengths {
+            operation: "g1_lincomb",
+            first: "points",
+            first_len: points.len(),
+            second: "scalars",
+            second_len: scalars.len(),
+        });
+    }
+    let points = points
+        .iter()
+        .map(|bytes| bytes_to_g1(bytes))
+        .collect::<Result<Vec<_>, _>>()?;
+    let scalars = scalars
+        .iter()
+        .enumerate()
+        .map(|(position, bytes)| {
+            BlsFieldElement::bytes_to_bls_field(*bytes)
+                .map(|element| element.0)
+                .map_err(|_| Error::InvalidFieldElement { position })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut sum = MaybeUninit::<g1_t>::uninit();
+    unsafe {
+        let res = C_KZG_RET::from_raw(bindings::g1_linear_combination(
+            sum.as_mut_ptr(),
+            points.as_ptr(),
+            scalars.as_ptr(),
+            points.len(),
+        ));
+        match res {
+            C_KZG_RET::C_KZG_OK => Ok(bytes_from_g1(sum.assume_init())),
+            _ => Err(Error::from(res).in_operation("g1_lincomb", None)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct BlsFieldElement(bindings::BLSFieldElement);
 
@@ -59,14 +667,117 @@ impl BlsFieldElement {
     pub fn bytes_to_bls_field(bytes: [u8; BYTES_PER_FIELD_ELEMENT]) -> Result<Self, Error> {
         let mut bls_field_element = MaybeUninit::<bindings::BLSFieldElement>::uninit();
         unsafe {
-            let res = bindings::bytes_to_bls_field(bls_field_element.as_mut_ptr(), bytes.as_ptr());
-            if let C_KZG_RET::C_KZG_OK = res {
-                Ok(Self(bls_field_element.assume_init()))
-            } else {
-                Err(Error::CError(res))
+            let res = C_KZG_RET::from_raw(bindings::bytes_to_bls_field(
+                bls_field_element.as_mut_ptr(),
+                bytes.as_ptr(),
+            ));
+            match res {
+                C_KZG_RET::C_KZG_OK => Ok(Self(bls_field_element.assume_init())),
+                C_KZG_RET::C_KZG_BADARGS => Err(Error::InvalidFieldElement { position: 0 }),
+                _ => Err(Error::from(res).in_operation("bytes_to_bls_field", None)),
+            }
+        }
+    }
+
+    /// The canonical little-endian encoding, as accepted by `bytes_to_bls_field`.
+    pub fn to_bytes(&self) -> [u8; BYTES_PER_FIELD_ELEMENT] {
+        let mut scalar = MaybeUninit::<bindings::blst_scalar>::uninit();
+        let mut bytes = [0; BYTES_PER_FIELD_ELEMENT];
+        unsafe {
+            bindings::blst_scalar_from_fr(scalar.as_mut_ptr(), &self.0);
+            bindings::blst_lendian_from_scalar(bytes.as_mut_ptr(), scalar.as_ptr());
+        }
+        bytes
+    }
+
+    /// Decodes a little-endian field element from hex, with or without a `0x` prefix.
+    #[cfg(feature = "hex")]
+    pub fn from_hex(hex_str: &str) -> Result<Self, Error> {
+        Self::bytes_to_bls_field(decode_hex::<BYTES_PER_FIELD_ELEMENT>(hex_str)?)
+    }
+
+    #[cfg(feature = "hex")]
+    pub fn as_hex_string(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+}
+
+#[cfg(feature = "hex")]
+fn strip_hex_prefix(hex_str: &str) -> &str {
+    hex_str.strip_prefix("0x").unwrap_or(hex_str)
+}
+
+#[cfg(feature = "hex")]
+fn decode_hex<const N: usize>(hex_str: &str) -> Result<[u8; N], Error> {
+    let mut bytes = [0; N];
+    hex::decode_to_slice(strip_hex_prefix(hex_str), &mut bytes)
+        .map_err(|_| Error::InvalidHexFormat)?;
+    Ok(bytes)
+}
+
+/// A `Blob` stored at a 64-byte aligned address.
+///
+/// `Blob` is a plain byte array, so it has no alignment guarantees and building one by value
+/// places `BYTES_PER_BLOB` bytes on the stack. An `AlignedBlob` is allocated directly on the heap
+/// and derefs to a `Blob`, so it can be passed anywhere a `&Blob` is expected.
+#[repr(C, align(64))]
+pub struct AlignedBlob(pub Blob);
+
+// `as_blobs` relies on `AlignedBlob` having no trailing padding.
+const _: () = assert!(core::mem::size_of::<AlignedBlob>() == BYTES_PER_BLOB);
+
+impl AlignedBlob {
+    /// Allocates a zeroed, 64-byte aligned blob on the heap.
+    pub fn new_boxed_aligned() -> Box<Self> {
+        let layout = Layout::new::<Self>();
+        unsafe {
+            let ptr = alloc_zeroed(layout) as *mut Self;
+            if ptr.is_null() {
+                handle_alloc_error(layout);
             }
+            Box::from_raw(ptr)
         }
     }
+
+    /// Copies `blob` into a new 64-byte aligned heap allocation.
+    pub fn from_blob(blob: &Blob) -> Box<Self> {
+        let mut aligned = Self::new_boxed_aligned();
+        aligned.0.copy_from_slice(blob);
+        aligned
+    }
+
+    /// Decodes a blob from hex, with or without a `0x` prefix.
+    #[cfg(feature = "hex")]
+    pub fn from_hex(hex_str: &str) -> Result<Box<Self>, Error> {
+        let mut aligned = Self::new_boxed_aligned();
+        hex::decode_to_slice(strip_hex_prefix(hex_str), &mut aligned.0[..])
+            .map_err(|_| Error::InvalidHexFormat)?;
+        Ok(aligned)
+    }
+
+    #[cfg(feature = "hex")]
+    pub fn as_hex_string(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// Views a slice of aligned blobs as a slice of `Blob`s without copying.
+    pub fn as_blobs(blobs: &[AlignedBlob]) -> &[Blob] {
+        unsafe { core::slice::from_raw_parts(blobs.as_ptr() as *const Blob, blobs.len()) }
+    }
+}
+
+impl Deref for AlignedBlob {
+    type Target = Blob;
+
+    fn deref(&self) -> &Blob {
+        &self.0
+    }
+}
+
+impl DerefMut for AlignedBlob {
+    fn deref_mut(&mut self) -> &mut Blob {
+        &mut self.0
+    }
 }
 
 /// Holds the parameters of a kzg trusted setup ceremony.
@@ -78,39 +789,54 @@ impl KzgSettings {
         g1_bytes: Vec<[u8; BYTES_PER_G1_POINT]>,
         g2_bytes: Vec<[u8; BYTES_PER_G2_POINT]>,
     ) -> Result<Self, Error> {
-        if g1_bytes.len() != FIELD_ELEMENTS_PER_BLOB {
-            return Err(Error::InvalidTrustedSetup(format!(
-                "Invalid number of g1 points in trusted setup. Expected {} got {}",
-                FIELD_ELEMENTS_PER_BLOB,
-                g1_bytes.len()
-            )));
-        }
-        if g2_bytes.len() != NUM_G2_POINTS {
-            return Err(Error::InvalidTrustedSetup(format!(
-                "Invalid number of g2 points in trusted setup. Expected {} got {}",
-                NUM_G2_POINTS,
-                g2_bytes.len()
-            )));
-        }
+        ffi_span!(
+            "load_trusted_setup",
+            g1_points = g1_bytes.len(),
+            g2_points = g2_bytes.len()
+        );
+        check_point_counts(g1_bytes.len(), g2_bytes.len())?;
         let mut kzg_settings = MaybeUninit::<bindings::KZGSettings>::uninit();
         unsafe {
             let n1 = g1_bytes.len();
             let n2 = g2_bytes.len();
 
-            let res = bindings::load_trusted_setup(
+            let res = C_KZG_RET::from_raw(bindings::load_trusted_setup(
                 kzg_settings.as_mut_ptr(),
                 g1_bytes.as_ptr() as *const u8,
                 n1,
                 g2_bytes.as_ptr() as *const u8,
                 n2,
-            );
+            ));
+            if let C_KZG_RET::C_KZG_OK = res {
+                Ok(Self::from_c(kzg_settings.assume_init()))
+            } else {
+                Err(Error::InvalidTrustedSetup(TrustedSetupError::CError(res)))
+            }
+        }
+    }
+
+    /// Initializes settings for verification only from the 65 g2 points of a trusted setup, for
+    /// nodes that never compute commitments or proofs. The g1 points are not needed, which saves
+    /// the time of converting them and the memory they take.
+    ///
+    /// Every `verify_*` function works with the returned settings, while computing a commitment or
+    /// a proof with them fails with `Error::MissingG1Points`.
+    pub fn load_trusted_setup_verify_only(
+        g2_bytes: Vec<[u8; BYTES_PER_G2_POINT]>,
+    ) -> Result<Self, Error> {
+        ffi_span!("load_trusted_setup_verify_only", g2_points = g2_bytes.len());
+        check_point_counts(FIELD_ELEMENTS_PER_BLOB, g2_bytes.len())?;
+        let mut kzg_settings = MaybeUninit::<bindings::KZGSettings>::uninit();
+        unsafe {
+            let res = C_KZG_RET::from_raw(bindings::load_trusted_setup_verify_only(
+                kzg_settings.as_mut_ptr(),
+                g2_bytes.as_ptr() as *const u8,
+                g2_bytes.len(),
+            ));
             if let C_KZG_RET::C_KZG_OK = res {
-                Ok(Self(kzg_settings.assume_init()))
+                Ok(Self::from_c(kzg_settings.assume_init()))
             } else {
-                Err(Error::InvalidTrustedSetup(format!(
-                    "Invalid trusted setup: {:?}",
-                    res
-                )))
+                Err(Error::InvalidTrustedSetup(TrustedSetupError::CError(res)))
             }
         }
     }
@@ -121,308 +847,3539 @@ impl KzgSettings {
     /// 65 # This is fixed and is used for providing multiproofs up to 64 field elements.
     /// FIELD_ELEMENT_PER_BLOB g1 byte values
     /// 65 g2 byte values
-    pub fn load_trusted_setup_file(file_path: PathBuf) -> Result<Self, Error> {
-        let file_path = CString::new(file_path.as_os_str().as_bytes()).map_err(|e| {
-            Error::InvalidTrustedSetup(format!("Invalid trusted setup file: {:?}", e))
-        })?;
+    ///
+    /// The path is passed to `fopen` as the bytes of the `OsStr`, so it does not need to be UTF-8.
+    #[cfg(all(feature = "std", not(windows)))]
+    pub fn load_trusted_setup_file<P: AsRef<Path>>(file_path: P) -> Result<Self, Error> {
+        ffi_span!("load_trusted_setup_file");
+        let file_path = CString::new(file_path.as_ref().as_os_str().as_bytes())
+            .map_err(|_| Error::InvalidTrustedSetup(TrustedSetupError::InvalidFilePath))?;
         let mut kzg_settings = MaybeUninit::<bindings::KZGSettings>::uninit();
         unsafe {
-            let file_ptr = fopen(file_path.as_ptr(), &('r' as libc::c_char));
-            let res = bindings::load_trusted_setup_file(kzg_settings.as_mut_ptr(), file_ptr);
+            let file_ptr = fopen(file_path.as_ptr(), c"r".as_ptr());
+            if file_ptr.is_null() {
+                return Err(Error::last_os_error());
+            }
+            let res = C_KZG_RET::from_raw(bindings::load_trusted_setup_file(
+                kzg_settings.as_mut_ptr(),
+                file_ptr,
+            ));
+            libc::fclose(file_ptr);
             if let C_KZG_RET::C_KZG_OK = res {
-                Ok(Self(kzg_settings.assume_init()))
+                Ok(Self::from_c(kzg_settings.assume_init()))
             } else {
-                Err(Error::InvalidTrustedSetup(format!(
-                    "Invalid trusted setup: {:?}",
-                    res
-                )))
+                Err(Error::InvalidTrustedSetup(TrustedSetupError::CError(res)))
             }
         }
     }
-}
 
-impl Drop for KzgSettings {
-    fn drop(&mut self) {
-        unsafe { bindings::free_trusted_setup(&mut self.0) }
+    /// Loads the trusted setup parameters from a file. The file format is as follows:
+    ///
+    /// FIELD_ELEMENTS_PER_BLOB
+    /// 65 # This is fixed and is used for providing multiproofs up to 64 field elements.
+    /// FIELD_ELEMENT_PER_BLOB g1 byte values
+    /// 65 g2 byte values
+    ///
+    /// On Windows the file is read and parsed in Rust, since paths are not byte strings there and
+    /// the C runtime of the C library may differ from the one of the Rust standard library.
+    #[cfg(all(feature = "std", windows))]
+    pub fn load_trusted_setup_file<P: AsRef<Path>>(file_path: P) -> Result<Self, Error> {
+        ffi_span!("load_trusted_setup_file");
+        let text =
+            std::fs::read_to_string(file_path).map_err(|error| match error.raw_os_error() {
+                Some(code) => Error::IoError { code },
+                // The file is not valid UTF-8.
+                None => Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat),
+            })?;
+        let (g1_bytes, g2_bytes) = trusted_setup_from_text(&text)?;
+        Self::load_trusted_setup(g1_bytes, g2_bytes)
+    }
+
+    /// Reads a trusted setup in the format of `load_trusted_setup_file` from `reader`, e.g. an entry
+    /// of an archive or a network buffer, and parses it in Rust, without going through the file
+    /// system or libc.
+    ///
+    /// Fails with `Error::ReadError` if the reader fails, and with `TrustedSetupError::InvalidFormat`
+    /// if the input is not UTF-8 text.
+    #[cfg(feature = "std")]
+    pub fn load_trusted_setup_reader<R: std::io::Read>(mut reader: R) -> Result<Self, Error> {
+        ffi_span!("load_trusted_setup_reader");
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|error| Error::ReadError { kind: error.kind() })?;
+        let text = core::str::from_utf8(&bytes)
+            .map_err(|_| Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat))?;
+        text.parse()
     }
 }
 
-pub struct KzgProof(bindings::KZGProof);
+/// Parses and loads a trusted setup in the format of `trusted_setup.txt`, e.g. one embedded with
+/// `include_str!`.
+impl core::str::FromStr for KzgSettings {
+    type Err = Error;
 
-impl KzgProof {
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
-        if bytes.len() != BYTES_PER_PROOF {
-            return Err(Error::InvalidKzgProof(format!(
-                "Invalid byte length. Expected {} got {}",
-                BYTES_PER_PROOF,
-                bytes.len(),
-            )));
-        }
-        let mut proof_bytes = [0; BYTES_PER_PROOF];
-        proof_bytes.copy_from_slice(bytes);
-        Ok(Self(bytes_to_g1(bytes)?))
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let (g1_bytes, g2_bytes) = trusted_setup_from_text(text)?;
+        Self::load_trusted_setup(g1_bytes, g2_bytes)
     }
+}
 
-    pub fn to_bytes(&self) -> [u8; BYTES_PER_G1_POINT] {
-        bytes_from_g1(self.0)
+fn check_point_counts(n1: usize, n2: usize) -> Result<(), Error> {
+    if n1 != FIELD_ELEMENTS_PER_BLOB {
+        return Err(Error::InvalidTrustedSetup(
+            TrustedSetupError::InvalidG1PointCount {
+                expected: FIELD_ELEMENTS_PER_BLOB,
+                actual: n1,
+            },
+        ));
     }
-
-    pub fn as_hex_string(&self) -> String {
-        hex::encode(self.to_bytes())
+    if n2 != NUM_G2_POINTS {
+        return Err(Error::InvalidTrustedSetup(
+            TrustedSetupError::InvalidG2PointCount {
+                expected: NUM_G2_POINTS,
+                actual: n2,
+            },
+        ));
     }
+    Ok(())
+}
 
-    pub fn compute_aggregate_kzg_proof(
-        blobs: &[Blob],
-        kzg_settings: &KzgSettings,
-    ) -> Result<Self, Error> {
-        let mut kzg_proof = MaybeUninit::<bindings::KZGProof>::uninit();
-        unsafe {
-            let res = bindings::compute_aggregate_kzg_proof(
-                kzg_proof.as_mut_ptr(),
-                blobs.as_ptr() as *const u8,
-                blobs.len(),
-                &kzg_settings.0,
-            );
-            if let C_KZG_RET::C_KZG_OK = res {
-                Ok(Self(kzg_proof.assume_init()))
-            } else {
-                Err(Error::CError(res))
-            }
+/// The g1 and g2 points of a trusted setup, in the form taken by `KzgSettings::load_trusted_setup`.
+pub type TrustedSetupPoints = (Vec<[u8; BYTES_PER_G1_POINT]>, Vec<[u8; BYTES_PER_G2_POINT]>);
+
+/// Parses the text format of `trusted_setup.txt` into g1 and g2 points, accepting the same input as
+/// the `fscanf` based loader of the C library. The points are not checked to be on the curve.
+pub fn trusted_setup_from_text(text: &str) -> Result<TrustedSetupPoints, Error> {
+    fn parse_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+        if hex.len() != 2 * N {
+            return None;
         }
+        let mut out = [0; N];
+        for (byte, pair) in out.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            let high = (pair[0] as char).to_digit(16)?;
+            let low = (pair[1] as char).to_digit(16)?;
+            *byte = (high << 4 | low) as u8;
+        }
+        Some(out)
     }
 
-    pub fn verify_aggregate_kzg_proof(
-        &self,
-        blobs: &[Blob],
-        expected_kzg_commitments: &[KzgCommitment],
-        kzg_settings: &KzgSettings,
-    ) -> Result<bool, Error> {
-        let mut verified: MaybeUninit<bool> = MaybeUninit::uninit();
-        unsafe {
-            // TODO: pass without allocating a vec
-            let res = bindings::verify_aggregate_kzg_proof(
-                verified.as_mut_ptr(),
-                blobs.as_ptr() as *const u8,
-                expected_kzg_commitments
-                    .iter()
-                    .map(|c| c.0)
-                    .collect::<Vec<_>>()
-                    .as_ptr(),
-                blobs.len(),
-                &self.0,
-                &kzg_settings.0,
-            );
-            if let C_KZG_RET::C_KZG_OK = res {
-                Ok(verified.assume_init())
-            } else {
-                Err(Error::CError(res))
-            }
-        }
+    let invalid_format = Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat);
+    let mut tokens = text.split_ascii_whitespace();
+    let mut count = || -> Result<usize, Error> {
+        let token = tokens.next().ok_or(invalid_format)?;
+        token.parse().map_err(|_| invalid_format)
+    };
+    let n1 = count()?;
+    let n2 = count()?;
+    check_point_counts(n1, n2)?;
+    let g1_bytes = (&mut tokens)
+        .take(n1)
+        .map(parse_hex)
+        .collect::<Option<Vec<_>>>()
+        .ok_or(invalid_format)?;
+    let g2_bytes = (&mut tokens)
+        .take(n2)
+        .map(parse_hex)
+        .collect::<Option<Vec<_>>>()
+        .ok_or(invalid_format)?;
+    if g1_bytes.len() != n1 || g2_bytes.len() != n2 {
+        return Err(invalid_format);
     }
+    Ok((g1_bytes, g2_bytes))
+}
 
-    pub fn verify_kzg_proof(
+/// Formats g1 and g2 points in the text format of `trusted_setup.txt`, the inverse of
+/// `trusted_setup_from_text`.
+pub fn trusted_setup_to_text(
+    g1_bytes: &[[u8; BYTES_PER_G1_POINT]],
+    g2_bytes: &[[u8; BYTES_PER_G2_POINT]],
+) -> String {
+    use core::fmt::Write;
+
+    let mut text = String::with_capacity(
+        16 + (2 * BYTES_PER_G1_POINT + 1) * g1_bytes.len()
+            + (2 * BYTES_PER_G2_POINT + 1) * g2_bytes.len(),
+    );
+    write!(text, "{}\n{}", g1_bytes.len(), g2_bytes.len()).unwrap();
+    let points = g1_bytes
+        .iter()
+        .map(|point| &point[..])
+        .chain(g2_bytes.iter().map(|point| &point[..]));
+    for point in points {
+        text.push('\n');
+        for byte in point {
+            write!(text, "{:02x}", byte).unwrap();
+        }
+    }
+    text
+}
+
+/// Splits the binary format of a trusted setup into g1 and g2 points. The binary format is the
+/// `FIELD_ELEMENTS_PER_BLOB` compressed g1 points followed by the 65 compressed g2 points, without
+/// any header or padding.
+pub fn trusted_setup_from_bin(bytes: &[u8]) -> Result<TrustedSetupPoints, Error> {
+    let g1_len = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_G1_POINT;
+    if bytes.len() != g1_len + NUM_G2_POINTS * BYTES_PER_G2_POINT {
+        return Err(Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat));
+    }
+    let (g1, g2) = bytes.split_at(g1_len);
+    let g1_bytes = g1
+        .chunks_exact(BYTES_PER_G1_POINT)
+        .map(|point| point.try_into().unwrap())
+        .collect();
+    let g2_bytes = g2
+        .chunks_exact(BYTES_PER_G2_POINT)
+        .map(|point| point.try_into().unwrap())
+        .collect();
+    Ok((g1_bytes, g2_bytes))
+}
+
+/// Concatenates g1 and g2 points into the binary format read by `trusted_setup_from_bin`.
+pub fn trusted_setup_to_bin(
+    g1_bytes: &[[u8; BYTES_PER_G1_POINT]],
+    g2_bytes: &[[u8; BYTES_PER_G2_POINT]],
+) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(
+        BYTES_PER_G1_POINT * g1_bytes.len() + BYTES_PER_G2_POINT * g2_bytes.len(),
+    );
+    for point in g1_bytes {
+        bytes.extend_from_slice(point);
+    }
+    for point in g2_bytes {
+        bytes.extend_from_slice(point);
+    }
+    bytes
+}
+
+impl KzgSettings {
+    /// Wraps settings that were initialized by the C library, e.g. through another language
+    /// binding in the same process, without loading the setup again.
+    ///
+    /// The returned value takes ownership of the arrays referenced by `*raw` and frees them with
+    /// `free_trusted_setup` when dropped. The struct at `raw` itself is only read and stays owned
+    /// by the caller, who must not free or use the settings through it afterwards.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must point to settings successfully initialized by `load_trusted_setup` or
+    /// `load_trusted_setup_file` of a C library built with the same `FIELD_ELEMENTS_PER_BLOB`, and
+    /// that have not been freed.
+    pub unsafe fn from_raw(raw: *mut KZGSettings) -> Self {
+        Self::from_c(core::ptr::read(raw))
+    }
+
+    /// Takes ownership of settings loaded by the C library.
+    fn from_c(settings: bindings::KZGSettings) -> Self {
+        #[cfg(feature = "alloc-stats")]
+        alloc_stats::settings_created();
+        Self(settings)
+    }
+
+    /// Releases ownership of the settings to the caller, who becomes responsible for freeing
+    /// them with `free_trusted_setup` of the C library (or for passing them to `from_raw`).
+    pub fn into_raw(self) -> KZGSettings {
+        #[cfg(feature = "alloc-stats")]
+        alloc_stats::settings_dropped();
+        let settings = ManuallyDrop::new(self);
+        unsafe { core::ptr::read(&settings.0) }
+    }
+}
+
+/// The first bytes of the output of `KzgSettings::serialize_precomputed`, with the format version
+/// as the last one.
+const PRECOMPUTED_MAGIC: [u8; 8] = *b"CKZGLAG\x02";
+
+/// The length of the output of `KzgSettings::serialize_precomputed`: the magic, the number of field
+/// elements per blob as a little-endian `u64`, 96 bytes per point, and the SHA-256 digest of the
+/// points.
+const PRECOMPUTED_LEN: usize = 16 + (FIELD_ELEMENTS_PER_BLOB + NUM_G2_POINTS) * 96 + 32;
+
+impl KzgSettings {
+    /// Writes the settings in the form the library uses them in, with the g1 points already
+    /// converted to Lagrange form, so that `deserialize_precomputed` restores them without the FFT
+    /// that dominates `load_trusted_setup`, e.g. from a cache file written on the first start.
+    ///
+    /// Fails with `std::io::ErrorKind::InvalidInput` for settings loaded with
+    /// `load_trusted_setup_verify_only`, which have no g1 points to write.
+    #[cfg(feature = "std")]
+    pub fn serialize_precomputed<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        ffi_span!("serialize_precomputed");
+        let mut bytes = alloc::vec![0; PRECOMPUTED_LEN];
+        bytes[..8].copy_from_slice(&PRECOMPUTED_MAGIC);
+        bytes[8..16].copy_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u64).to_le_bytes());
+        let res = unsafe {
+            C_KZG_RET::from_raw(bindings::save_trusted_setup_lagrange(
+                bytes[16..].as_mut_ptr(),
+                &self.0,
+            ))
+        };
+        if res != C_KZG_RET::C_KZG_OK {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "the settings were loaded without g1 points",
+            ));
+        }
+        writer.write_all(&bytes)
+    }
+
+    /// Loads settings written by `serialize_precomputed` with the same `FIELD_ELEMENTS_PER_BLOB`.
+    ///
+    /// The digest of the points is checked, which catches corrupted input, and the g2 points are
+    /// checked to be in the G2 subgroup. The g1 points are only checked to be on the curve, since
+    /// checking them to be in the G1 subgroup would take as long as loading the setup again, so the
+    /// bytes are trusted like a trusted setup file. Only pass bytes that were serialized by this
+    /// node, not ones received from others.
+    pub fn deserialize_precomputed(bytes: &[u8]) -> Result<Self, Error> {
+        ffi_span!("deserialize_precomputed");
+        let invalid_format = Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat);
+        if bytes.len() < 16 || bytes[..8] != PRECOMPUTED_MAGIC {
+            return Err(invalid_format);
+        }
+        let mut n1 = [0; 8];
+        n1.copy_from_slice(&bytes[8..16]);
+        let n1 = u64::from_le_bytes(n1);
+        if n1 != FIELD_ELEMENTS_PER_BLOB as u64 {
+            return Err(Error::InvalidTrustedSetup(
+                TrustedSetupError::InvalidG1PointCount {
+                    expected: FIELD_ELEMENTS_PER_BLOB,
+                    actual: usize::try_from(n1).unwrap_or(usize::MAX),
+                },
+            ));
+        }
+        if bytes.len() != PRECOMPUTED_LEN {
+            return Err(invalid_format);
+        }
+        let mut kzg_settings = MaybeUninit::<bindings::KZGSettings>::uninit();
+        unsafe {
+            let res = C_KZG_RET::from_raw(bindings::load_trusted_setup_lagrange(
+                kzg_settings.as_mut_ptr(),
+                bytes[16..].as_ptr(),
+            ));
+            if let C_KZG_RET::C_KZG_OK = res {
+                Ok(Self::from_c(kzg_settings.assume_init()))
+            } else {
+                Err(Error::InvalidTrustedSetup(TrustedSetupError::CError(res)))
+            }
+        }
+    }
+}
+
+/// A defect found by `KzgSettings::is_well_formed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsProblem {
+    /// A pointer that the C library dereferences is null, e.g. `"g1_values"`.
+    NullPointer(&'static str),
+    /// The FFT settings are not sized for `FIELD_ELEMENTS_PER_BLOB` field elements.
+    InvalidMaxWidth { expected: u64, actual: u64 },
+    /// The root of unity at `index` of `roots_of_unity` is not the one `load_trusted_setup` computes.
+    InvalidRootOfUnity { index: usize },
+}
+
+impl fmt::Display for SettingsProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NullPointer(name) => write!(f, "{} is null", name),
+            Self::InvalidMaxWidth { expected, actual } => {
+                write!(f, "expected a max width of {}, got {}", expected, actual)
+            }
+            Self::InvalidRootOfUnity { index } => write!(f, "root of unity {} is invalid", index),
+        }
+    }
+}
+
+impl KzgSettings {
+    /// Fails with `Error::MissingG1Points` for settings loaded with
+    /// `load_trusted_setup_verify_only`, before `operation` hands them to the C library.
+    fn check_g1_points(&self, operation: &'static str) -> Result<(), Error> {
+        // The mock computes without any points, whichever way the settings were loaded.
+        if !cfg!(feature = "mock") && self.0.g1_values.is_null() {
+            return Err(Error::MissingG1Points { operation });
+        }
+        Ok(())
+    }
+
+    /// Checks the invariants the C library relies on, e.g. before using settings restored with
+    /// `from_raw`, and returns every problem found. Only the pointers are checked for the g1 and g2
+    /// points, so corrupted points are not detected. Settings loaded with
+    /// `load_trusted_setup_verify_only` report their null `g1_values`.
+    pub fn is_well_formed(&self) -> Result<(), Vec<SettingsProblem>> {
+        let mut problems = Vec::new();
+        if self.0.g1_values.is_null() {
+            problems.push(SettingsProblem::NullPointer("g1_values"));
+        }
+        if self.0.g2_values.is_null() {
+            problems.push(SettingsProblem::NullPointer("g2_values"));
+        }
+        match unsafe { self.0.fs.as_ref() } {
+            None => problems.push(SettingsProblem::NullPointer("fs")),
+            Some(fs) if fs.max_width != FIELD_ELEMENTS_PER_BLOB as u64 => {
+                problems.push(SettingsProblem::InvalidMaxWidth {
+                    expected: FIELD_ELEMENTS_PER_BLOB as u64,
+                    actual: fs.max_width,
+                })
+            }
+            Some(fs) if fs.roots_of_unity.is_null() => {
+                problems.push(SettingsProblem::NullPointer("fs.roots_of_unity"))
+            }
+            Some(fs) => {
+                // In bit-reversal order, 1 comes first, followed by -1 and the root itself at
+                // half the width.
+                let width = FIELD_ELEMENTS_PER_BLOB;
+                let mut minus_one = BLS_MODULUS_BYTES;
+                minus_one[0] -= 1;
+                let mut one = [0; BYTES_PER_FIELD_ELEMENT];
+                one[0] = 1;
+                let expected = [
+                    (0, one),
+                    (1, minus_one),
+                    (
+                        width / 2,
+                        SCALE2_ROOT_OF_UNITY_BYTES[width.trailing_zeros() as usize],
+                    ),
+                ];
+                for (index, bytes) in expected {
+                    let root = BlsFieldElement(unsafe { *fs.roots_of_unity.add(index) });
+                    if root.to_bytes() != bytes {
+                        problems.push(SettingsProblem::InvalidRootOfUnity { index });
+                    }
+                }
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+impl Drop for KzgSettings {
+    fn drop(&mut self) {
+        #[cfg(feature = "alloc-stats")]
+        alloc_stats::settings_dropped();
+        unsafe { bindings::free_trusted_setup(&mut self.0) }
+    }
+}
+
+pub struct KzgProof(bindings::KZGProof);
+
+impl KzgProof {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != BYTES_PER_PROOF {
+            return Err(Error::InvalidKzgProof {
+                expected: BYTES_PER_PROOF,
+                actual: bytes.len(),
+            });
+        }
+        let mut proof_bytes = [0; BYTES_PER_PROOF];
+        proof_bytes.copy_from_slice(bytes);
+        Ok(Self(bytes_to_g1(bytes)?))
+    }
+
+    pub fn to_bytes(&self) -> [u8; BYTES_PER_G1_POINT] {
+        bytes_from_g1(self.0)
+    }
+
+    /// Decodes an uncompressed point, as returned by `to_uncompressed_bytes`. Fails with
+    /// `Error::InvalidG1Point` unless it is a point of G1.
+    pub fn from_uncompressed_bytes(
+        bytes: &[u8; BYTES_PER_G1_POINT_UNCOMPRESSED],
+    ) -> Result<Self, Error> {
+        Ok(Self(g1_from_uncompressed(bytes)?))
+    }
+
+    /// The uncompressed encoding: the big-endian `x` and `y` coordinates, with the infinity flag
+    /// of the compressed encoding in the first byte.
+    pub fn to_uncompressed_bytes(&self) -> [u8; BYTES_PER_G1_POINT_UNCOMPRESSED] {
+        g1_to_uncompressed(&self.0)
+    }
+
+    /// Decodes a compressed point from hex, with or without a `0x` prefix.
+    #[cfg(feature = "hex")]
+    pub fn from_hex(hex_str: &str) -> Result<Self, Error> {
+        Self::from_bytes(&decode_hex::<BYTES_PER_G1_POINT>(hex_str)?)
+    }
+
+    #[cfg(feature = "hex")]
+    pub fn as_hex_string(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    pub fn compute_aggregate_kzg_proof(
+        blobs: &[Blob],
+        kzg_settings: &KzgSettings,
+    ) -> Result<Self, Error> {
+        ffi_span!("compute_aggregate_kzg_proof", num_blobs = blobs.len());
+        kzg_settings.check_g1_points("compute_aggregate_kzg_proof")?;
+        let mut kzg_proof = MaybeUninit::<bindings::KZGProof>::uninit();
+        metrics::observe(KzgOperation::ComputeAggregateKzgProof, || unsafe {
+            let res = C_KZG_RET::from_raw(bindings::compute_aggregate_kzg_proof(
+                kzg_proof.as_mut_ptr(),
+                blobs.as_ptr() as *const u8,
+                blobs.len(),
+                &kzg_settings.0,
+            ));
+            if let C_KZG_RET::C_KZG_OK = res {
+                Ok(Self(kzg_proof.assume_init()))
+            } else {
+                Err(blob_error("compute_aggregate_kzg_proof", blobs, res))
+            }
+        })
+    }
+
+    pub fn verify_aggregate_kzg_proof(
+        &self,
+        blobs: &[Blob],
+        expected_kzg_commitments: &[KzgCommitment],
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, Error> {
+        ffi_span!("verify_aggregate_kzg_proof", num_blobs = blobs.len());
+        // The C library reads one commitment per blob.
+        if blobs.len() != expected_kzg_commitments.len() {
+            return Err(Error::MismatchedLengths {
+                operation: "verify_aggregate_kzg_proof",
+                first: "blobs",
+                first_len: blobs.len(),
+                second: "commitments",
+                second_len: expected_kzg_commitments.len(),
+            });
+        }
+        let mut verified: MaybeUninit<bool> = MaybeUninit::uninit();
+        metrics::observe_verification(KzgOperation::VerifyAggregateKzgProof, || unsafe {
+            // TODO: pass without allocating a vec
+            let res = C_KZG_RET::from_raw(bindings::verify_aggregate_kzg_proof(
+                verified.as_mut_ptr(),
+                blobs.as_ptr() as *const u8,
+                expected_kzg_commitments
+                    .iter()
+                    .map(|c| c.0)
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
+                blobs.len(),
+                &self.0,
+                &kzg_settings.0,
+            ));
+            if let C_KZG_RET::C_KZG_OK = res {
+                Ok(verified.assume_init())
+            } else {
+                Err(blob_error("verify_aggregate_kzg_proof", blobs, res))
+            }
+        })
+    }
+
+    /// Verifies several aggregate proofs one after the other, e.g. those of the blocks of a range
+    /// sync, calling `should_cancel` before each proof and returning `Error::Cancelled` as soon as
+    /// it returns `true`. Otherwise returns the verdict of every proof, in order.
+    pub fn verify_aggregate_kzg_proof_batch_cancellable(
+        proofs: &[KzgProof],
+        blobs: &[&[Blob]],
+        expected_kzg_commitments: &[&[KzgCommitment]],
+        kzg_settings: &KzgSettings,
+        should_cancel: &dyn Fn() -> bool,
+    ) -> Result<Vec<bool>, Error> {
+        for (second, second_len) in [
+            ("blobs", blobs.len()),
+            ("commitments", expected_kzg_commitments.len()),
+        ] {
+            if proofs.len() != second_len {
+                return Err(Error::MismatchedLengths {
+                    operation: "verify_aggregate_kzg_proof_batch_cancellable",
+                    first: "proofs",
+                    first_len: proofs.len(),
+                    second,
+                    second_len,
+                });
+            }
+        }
+        proofs
+            .iter()
+            .zip(blobs)
+            .zip(expected_kzg_commitments)
+            .map(|((proof, blobs), expected_kzg_commitments)| {
+                if should_cancel() {
+                    return Err(Error::Cancelled);
+                }
+                proof.verify_aggregate_kzg_proof(blobs, expected_kzg_commitments, kzg_settings)
+            })
+            .collect()
+    }
+
+    pub fn verify_kzg_proof(
         &self,
         kzg_commitment: KzgCommitment,
         z: [u8; BYTES_PER_FIELD_ELEMENT],
         y: [u8; BYTES_PER_FIELD_ELEMENT],
         kzg_settings: &KzgSettings,
     ) -> Result<bool, Error> {
+        ffi_span!("verify_kzg_proof");
         let mut verified: MaybeUninit<bool> = MaybeUninit::uninit();
-        unsafe {
-            let res = bindings::verify_kzg_proof(
+        metrics::observe_verification(KzgOperation::VerifyKzgProof, || unsafe {
+            let res = C_KZG_RET::from_raw(bindings::verify_kzg_proof(
                 verified.as_mut_ptr(),
                 &kzg_commitment.0,
                 z.as_ptr(),
                 y.as_ptr(),
                 &self.0,
                 &kzg_settings.0,
-            );
+            ));
+            if let C_KZG_RET::C_KZG_OK = res {
+                Ok(verified.assume_init())
+            } else if let C_KZG_RET::C_KZG_BADARGS = res {
+                // Only the field elements can be rejected, find out which one.
+                let argument = match BlsFieldElement::bytes_to_bls_field(z) {
+                    Ok(_) => "y",
+                    Err(_) => "z",
+                };
+                Err(Error::from(res).in_operation("verify_kzg_proof", Some(argument)))
+            } else {
+                Err(Error::from(res).in_operation("verify_kzg_proof", None))
+            }
+        })
+    }
+
+    /// Proves the value at `z` of the polynomial with the given coefficients, lowest degree
+    /// first, against its `KzgCommitment::commit_coefficients`. Returns the proof and the value
+    /// `y`, which `verify_kzg_proof` checks like any other opening.
+    pub fn compute_proof_monomial(
+        coefficients: &[[u8; BYTES_PER_FIELD_ELEMENT]],
+        z: [u8; BYTES_PER_FIELD_ELEMENT],
+        kzg_settings: &KzgSettings,
+    ) -> Result<(Self, [u8; BYTES_PER_FIELD_ELEMENT]), Error> {
+        ffi_span!(
+            "compute_proof_monomial",
+            num_coefficients = coefficients.len()
+        );
+        kzg_settings.check_g1_points("compute_proof_monomial")?;
+        if coefficients.len() > FIELD_ELEMENTS_PER_BLOB {
+            return Err(Error::from(C_KZG_RET::C_KZG_BADARGS)
+                .in_operation("compute_proof_monomial", Some("coefficients")));
+        }
+        let mut kzg_proof = MaybeUninit::<bindings::KZGProof>::uninit();
+        let mut y = [0; BYTES_PER_FIELD_ELEMENT];
+        unsafe {
+            let res = C_KZG_RET::from_raw(bindings::compute_proof_monomial(
+                kzg_proof.as_mut_ptr(),
+                y.as_mut_ptr(),
+                coefficients.as_ptr() as *const u8,
+                coefficients.len(),
+                z.as_ptr(),
+                &kzg_settings.0,
+            ));
+            match res {
+                C_KZG_RET::C_KZG_OK => Ok((Self(kzg_proof.assume_init()), y)),
+                C_KZG_RET::C_KZG_BADARGS => {
+                    // Either `z` or a coefficient was rejected, find out which one.
+                    if BlsFieldElement::bytes_to_bls_field(z).is_err() {
+                        return Err(
+                            Error::from(res).in_operation("compute_proof_monomial", Some("z"))
+                        );
+                    }
+                    Err(field_elements_error(
+                        "compute_proof_monomial",
+                        coefficients,
+                        res,
+                    ))
+                }
+                _ => Err(Error::from(res).in_operation("compute_proof_monomial", None)),
+            }
+        }
+    }
+
+    /// Proves that the polynomial with the given coefficients, lowest degree first, has a degree
+    /// less than `degree_bound`, e.g. that data committed to with
+    /// `KzgCommitment::commit_coefficients` is at most `degree_bound` field elements long. At most
+    /// `degree_bound` coefficients, and a `degree_bound` of at most `FIELD_ELEMENTS_PER_BLOB`, are
+    /// accepted.
+    ///
+    /// The proof holds the commitments to the polynomial multiplied by growing powers of `X`, up to
+    /// `X^(FIELD_ELEMENTS_PER_BLOB - degree_bound)`, which the setup can only commit to for a
+    /// polynomial below the bound. The setup has 64 powers of tau in G2 to check each shift with,
+    /// so there is one point per 64 of the shift, up to 64 points for short data.
+    pub fn compute_degree_bound_proof(
+        coefficients: &[[u8; BYTES_PER_FIELD_ELEMENT]],
+        degree_bound: usize,
+        kzg_settings: &KzgSettings,
+    ) -> Result<Vec<Self>, Error> {
+        ffi_span!(
+            "compute_degree_bound_proof",
+            num_coefficients = coefficients.len(),
+            degree_bound = degree_bound
+        );
+        kzg_settings.check_g1_points("compute_degree_bound_proof")?;
+        if degree_bound > FIELD_ELEMENTS_PER_BLOB {
+            return Err(Error::from(C_KZG_RET::C_KZG_BADARGS)
+                .in_operation("compute_degree_bound_proof", Some("degree_bound")));
+        }
+        if coefficients.len() > degree_bound {
+            return Err(Error::from(C_KZG_RET::C_KZG_BADARGS)
+                .in_operation("compute_degree_bound_proof", Some("coefficients")));
+        }
+        unsafe {
+            let len = bindings::degree_bound_proof_length(degree_bound);
+            let mut proofs = Vec::<bindings::KZGProof>::with_capacity(len);
+            let res = C_KZG_RET::from_raw(bindings::compute_degree_bound_proof(
+                proofs.as_mut_ptr(),
+                coefficients.as_ptr() as *const u8,
+                coefficients.len(),
+                degree_bound,
+                &kzg_settings.0,
+            ));
+            match res {
+                C_KZG_RET::C_KZG_OK => {
+                    proofs.set_len(len);
+                    Ok(proofs.into_iter().map(Self).collect())
+                }
+                _ => Err(field_elements_error(
+                    "compute_degree_bound_proof",
+                    coefficients,
+                    res,
+                )),
+            }
+        }
+    }
+
+    /// Checks a proof returned by `compute_degree_bound_proof`: that `commitment` is to a
+    /// polynomial of degree less than `degree_bound`. Fails if `proofs` does not have the length
+    /// of a proof for `degree_bound`, which is empty for `FIELD_ELEMENTS_PER_BLOB`, the bound of
+    /// every commitment.
+    pub fn verify_degree_bound_proof(
+        proofs: &[Self],
+        commitment: &KzgCommitment,
+        degree_bound: usize,
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, Error> {
+        ffi_span!(
+            "verify_degree_bound_proof",
+            num_proofs = proofs.len(),
+            degree_bound = degree_bound
+        );
+        if degree_bound > FIELD_ELEMENTS_PER_BLOB {
+            return Err(Error::from(C_KZG_RET::C_KZG_BADARGS)
+                .in_operation("verify_degree_bound_proof", Some("degree_bound")));
+        }
+        if proofs.len() != unsafe { bindings::degree_bound_proof_length(degree_bound) } {
+            return Err(Error::from(C_KZG_RET::C_KZG_BADARGS)
+                .in_operation("verify_degree_bound_proof", Some("proofs")));
+        }
+        let mut verified: MaybeUninit<bool> = MaybeUninit::uninit();
+        metrics::observe_verification(KzgOperation::VerifyDegreeBoundProof, || unsafe {
+            let res = C_KZG_RET::from_raw(bindings::verify_degree_bound_proof(
+                verified.as_mut_ptr(),
+                &commitment.0,
+                degree_bound,
+                proofs.iter().map(|p| p.0).collect::<Vec<_>>().as_ptr(),
+                proofs.len(),
+                &kzg_settings.0,
+            ));
             if let C_KZG_RET::C_KZG_OK = res {
                 Ok(verified.assume_init())
             } else {
-                Err(Error::CError(res))
+                Err(Error::from(res).in_operation("verify_degree_bound_proof", None))
+            }
+        })
+    }
+
+    /// Proves that `blob` is the data behind both `commitment` and `data_hash`, a hash of the same
+    /// data under another commitment scheme, e.g. that of a rollup (a proof of equivalence).
+    ///
+    /// Opens the commitment at the point `z` whose little-endian bytes, reduced modulo
+    /// `BLS_MODULUS`, are the SHA-256 hash of `EQUIVALENCE_PROTOCOL_DOMAIN`, the compressed
+    /// commitment and `data_hash`. Returns the proof and the evaluation `y` of the blob at `z`, which
+    /// the holder of the data compares with its own evaluation before checking the proof with
+    /// `verify_equivalence_proof`.
+    pub fn compute_equivalence_proof(
+        blob: &Blob,
+        commitment: &KzgCommitment,
+        data_hash: &[u8; 32],
+        kzg_settings: &KzgSettings,
+    ) -> Result<(Self, [u8; BYTES_PER_FIELD_ELEMENT]), Error> {
+        ffi_span!("compute_equivalence_proof");
+        kzg_settings.check_g1_points("compute_equivalence_proof")?;
+        let mut kzg_proof = MaybeUninit::<bindings::KZGProof>::uninit();
+        let mut y = [0; BYTES_PER_FIELD_ELEMENT];
+        unsafe {
+            let res = C_KZG_RET::from_raw(bindings::compute_equivalence_proof(
+                kzg_proof.as_mut_ptr(),
+                y.as_mut_ptr(),
+                blob.as_ptr(),
+                &commitment.0,
+                data_hash.as_ptr(),
+                &kzg_settings.0,
+            ));
+            if let C_KZG_RET::C_KZG_OK = res {
+                Ok((Self(kzg_proof.assume_init()), y))
+            } else {
+                Err(blob_error(
+                    "compute_equivalence_proof",
+                    core::slice::from_ref(blob),
+                    res,
+                ))
+            }
+        }
+    }
+
+    /// Checks a proof returned by `compute_equivalence_proof`: that `commitment` evaluates to `y`
+    /// at the point derived from it and `data_hash`.
+    pub fn verify_equivalence_proof(
+        &self,
+        commitment: &KzgCommitment,
+        data_hash: &[u8; 32],
+        y: &[u8; BYTES_PER_FIELD_ELEMENT],
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, Error> {
+        ffi_span!("verify_equivalence_proof");
+        let mut verified: MaybeUninit<bool> = MaybeUninit::uninit();
+        metrics::observe_verification(KzgOperation::VerifyEquivalenceProof, || unsafe {
+            let res = C_KZG_RET::from_raw(bindings::verify_equivalence_proof(
+                verified.as_mut_ptr(),
+                &commitment.0,
+                data_hash.as_ptr(),
+                y.as_ptr(),
+                &self.0,
+                &kzg_settings.0,
+            ));
+            match res {
+                C_KZG_RET::C_KZG_OK => Ok(verified.assume_init()),
+                C_KZG_RET::C_KZG_BADARGS => {
+                    Err(Error::from(res).in_operation("verify_equivalence_proof", Some("y")))
+                }
+                _ => Err(Error::from(res).in_operation("verify_equivalence_proof", None)),
+            }
+        })
+    }
+}
+
+/// The outcome of a verification as reported by the `diagnose_` methods of `KzgProof`, telling a
+/// failed pairing check apart from malformed inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationDiagnosis {
+    /// The proof is valid.
+    Valid,
+    /// All inputs are well formed, but the pairing check failed.
+    PairingCheckFailed,
+    /// The commitment at `index` is not a compressed point of the G1 subgroup, e.g. it is not on
+    /// the curve or it is on the curve but outside the subgroup.
+    InvalidCommitment { index: usize },
+    /// The proof is not a compressed point of the G1 subgroup.
+    InvalidProof,
+    /// A field element is not canonical. For blobs the position counts field elements across all
+    /// blobs, for `diagnose_kzg_proof` position 0 is `z` and 1 is `y`.
+    InvalidFieldElement { position: usize },
+}
+
+/// Slower variants of the verification functions for triaging interop failures. They take the
+/// commitments and proof as received and decode them one by one before verifying, so that a
+/// `false` verdict or a decoding failure can be attributed to an input.
+impl KzgProof {
+    pub fn diagnose_aggregate_kzg_proof(
+        proof_bytes: [u8; BYTES_PER_PROOF],
+        blobs: &[Blob],
+        expected_kzg_commitment_bytes: &[[u8; BYTES_PER_COMMITMENT]],
+        kzg_settings: &KzgSettings,
+    ) -> Result<VerificationDiagnosis, Error> {
+        let mut commitments = Vec::with_capacity(expected_kzg_commitment_bytes.len());
+        for (index, bytes) in expected_kzg_commitment_bytes.iter().enumerate() {
+            match KzgCommitment::from_bytes(bytes) {
+                Ok(commitment) => commitments.push(commitment),
+                Err(_) => return Ok(VerificationDiagnosis::InvalidCommitment { index }),
+            }
+        }
+        let proof = match Self::from_bytes(&proof_bytes) {
+            Ok(proof) => proof,
+            Err(_) => return Ok(VerificationDiagnosis::InvalidProof),
+        };
+        diagnosis(proof.verify_aggregate_kzg_proof(blobs, &commitments, kzg_settings))
+    }
+
+    pub fn diagnose_kzg_proof(
+        proof_bytes: [u8; BYTES_PER_PROOF],
+        kzg_commitment_bytes: [u8; BYTES_PER_COMMITMENT],
+        z: [u8; BYTES_PER_FIELD_ELEMENT],
+        y: [u8; BYTES_PER_FIELD_ELEMENT],
+        kzg_settings: &KzgSettings,
+    ) -> Result<VerificationDiagnosis, Error> {
+        let kzg_commitment = match KzgCommitment::from_bytes(&kzg_commitment_bytes) {
+            Ok(kzg_commitment) => kzg_commitment,
+            Err(_) => return Ok(VerificationDiagnosis::InvalidCommitment { index: 0 }),
+        };
+        let proof = match Self::from_bytes(&proof_bytes) {
+            Ok(proof) => proof,
+            Err(_) => return Ok(VerificationDiagnosis::InvalidProof),
+        };
+        for (position, bytes) in [z, y].into_iter().enumerate() {
+            if BlsFieldElement::bytes_to_bls_field(bytes).is_err() {
+                return Ok(VerificationDiagnosis::InvalidFieldElement { position });
             }
         }
+        diagnosis(proof.verify_kzg_proof(kzg_commitment, z, y, kzg_settings))
+    }
+}
+
+fn diagnosis(verified: Result<bool, Error>) -> Result<VerificationDiagnosis, Error> {
+    match verified {
+        Ok(true) => Ok(VerificationDiagnosis::Valid),
+        Ok(false) => Ok(VerificationDiagnosis::PairingCheckFailed),
+        Err(Error::InvalidFieldElement { position }) => {
+            Ok(VerificationDiagnosis::InvalidFieldElement { position })
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// The C library only returns `C_KZG_BADARGS` from the blob operations if a blob contains a field
+/// element that is not canonical, so look for the first one.
+fn blob_error(operation: &'static str, blobs: &[Blob], ret: C_KZG_RET) -> Error {
+    if let C_KZG_RET::C_KZG_BADARGS = ret {
+        let field_elements = blobs
+            .iter()
+            .flat_map(|blob| blob.chunks_exact(BYTES_PER_FIELD_ELEMENT));
+        if let Some(error) = non_canonical_error(operation, field_elements) {
+            return error;
+        }
+    }
+    Error::from(ret).in_operation(operation, None)
+}
+
+/// Like `blob_error`, for the operations on a slice of field elements.
+fn field_elements_error(
+    operation: &'static str,
+    field_elements: &[[u8; BYTES_PER_FIELD_ELEMENT]],
+    ret: C_KZG_RET,
+) -> Error {
+    if let C_KZG_RET::C_KZG_BADARGS = ret {
+        let field_elements = field_elements.iter().map(|bytes| &bytes[..]);
+        if let Some(error) = non_canonical_error(operation, field_elements) {
+            return error;
+        }
+    }
+    Error::from(ret).in_operation(operation, None)
+}
+
+/// `Error::InvalidFieldElement` at the first of `field_elements` that is not canonical, if any.
+///
+/// With the `constant-time` feature the C library checks every field element so that the time
+/// taken does not reveal which one was rejected. All of them are checked here too, and the error
+/// names the operation but no position.
+fn non_canonical_error<'a>(
+    operation: &'static str,
+    mut field_elements: impl Iterator<Item = &'a [u8]>,
+) -> Option<Error> {
+    let is_non_canonical = |bytes: &[u8]| {
+        let mut field_element = [0; BYTES_PER_FIELD_ELEMENT];
+        field_element.copy_from_slice(bytes);
+        BlsFieldElement::bytes_to_bls_field(field_element).is_err()
+    };
+    if cfg!(feature = "constant-time") {
+        let non_canonical =
+            field_elements.fold(false, |found, bytes| found | is_non_canonical(bytes));
+        return non_canonical.then(|| {
+            Error::from(C_KZG_RET::C_KZG_BADARGS).in_operation(operation, Some("field element"))
+        });
+    }
+    field_elements
+        .position(is_non_canonical)
+        .map(|position| Error::InvalidFieldElement { position })
+}
+
+#[cfg(feature = "blst-interop")]
+impl KzgProof {
+    pub fn to_blst_p1_affine(&self) -> blst_p1_affine {
+        g1_to_affine(&self.0)
+    }
+
+    /// Fails with `Error::InvalidG1Point` if the point is not in the G1 subgroup.
+    pub fn from_blst_p1_affine(point: &blst_p1_affine) -> Result<Self, Error> {
+        Ok(Self(g1_from_affine(point)?))
+    }
+}
+
+pub struct KzgCommitment(bindings::KZGCommitment);
+
+impl KzgCommitment {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != BYTES_PER_COMMITMENT {
+            return Err(Error::InvalidKzgCommitment {
+                expected: BYTES_PER_COMMITMENT,
+                actual: bytes.len(),
+            });
+        }
+        let mut proof_bytes = [0; BYTES_PER_COMMITMENT];
+        proof_bytes.copy_from_slice(bytes);
+        Ok(Self(bytes_to_g1(bytes)?))
+    }
+
+    pub fn to_bytes(&self) -> [u8; BYTES_PER_G1_POINT] {
+        bytes_from_g1(self.0)
+    }
+
+    /// Decodes an uncompressed point, as returned by `to_uncompressed_bytes`. Fails with
+    /// `Error::InvalidG1Point` unless it is a point of G1.
+    pub fn from_uncompressed_bytes(
+        bytes: &[u8; BYTES_PER_G1_POINT_UNCOMPRESSED],
+    ) -> Result<Self, Error> {
+        Ok(Self(g1_from_uncompressed(bytes)?))
+    }
+
+    /// The uncompressed encoding: the big-endian `x` and `y` coordinates, with the infinity flag
+    /// of the compressed encoding in the first byte.
+    pub fn to_uncompressed_bytes(&self) -> [u8; BYTES_PER_G1_POINT_UNCOMPRESSED] {
+        g1_to_uncompressed(&self.0)
+    }
+
+    /// Decodes a compressed point from hex, with or without a `0x` prefix.
+    #[cfg(feature = "hex")]
+    pub fn from_hex(hex_str: &str) -> Result<Self, Error> {
+        Self::from_bytes(&decode_hex::<BYTES_PER_G1_POINT>(hex_str)?)
+    }
+
+    #[cfg(feature = "hex")]
+    pub fn as_hex_string(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    pub fn blob_to_kzg_commitment(blob: Blob, kzg_settings: &KzgSettings) -> Result<Self, Error> {
+        Self::blob_to_kzg_commitment_ref(&blob, kzg_settings)
+    }
+
+    /// Like `blob_to_kzg_commitment`, but borrows the blob so that it is not copied onto the stack.
+    /// Pair it with `AlignedBlob::new_boxed_aligned` and the `small-stack` feature on threads with
+    /// small stacks.
+    pub fn blob_to_kzg_commitment_ref(
+        blob: &Blob,
+        kzg_settings: &KzgSettings,
+    ) -> Result<Self, Error> {
+        ffi_span!("blob_to_kzg_commitment");
+        kzg_settings.check_g1_points("blob_to_kzg_commitment")?;
+        let mut kzg_commitment: MaybeUninit<bindings::KZGCommitment> = MaybeUninit::uninit();
+        metrics::observe(KzgOperation::BlobToKzgCommitment, || unsafe {
+            let res = C_KZG_RET::from_raw(bindings::blob_to_kzg_commitment(
+                kzg_commitment.as_mut_ptr(),
+                blob.as_ptr(),
+                &kzg_settings.0,
+            ));
+            if let C_KZG_RET::C_KZG_OK = res {
+                Ok(Self(kzg_commitment.assume_init()))
+            } else {
+                Err(blob_error(
+                    "blob_to_kzg_commitment",
+                    core::slice::from_ref(blob),
+                    res,
+                ))
+            }
+        })
+    }
+
+    /// Computes the commitment to the blob made of `field_elements` followed by zeros, without
+    /// building the padded blob. The commitment is the same as the one of the padded blob, and is
+    /// cheaper to compute for short data, since only the first `field_elements.len()` points of
+    /// the setup are used.
+    pub fn commit_partial(
+        field_elements: &[[u8; BYTES_PER_FIELD_ELEMENT]],
+        kzg_settings: &KzgSettings,
+    ) -> Result<Self, Error> {
+        ffi_span!(
+            "blob_prefix_to_kzg_commitment",
+            num_field_elements = field_elements.len()
+        );
+        kzg_settings.check_g1_points("commit_partial")?;
+        if field_elements.len() > FIELD_ELEMENTS_PER_BLOB {
+            return Err(Error::from(C_KZG_RET::C_KZG_BADARGS)
+                .in_operation("commit_partial", Some("field_elements")));
+        }
+        let mut kzg_commitment: MaybeUninit<bindings::KZGCommitment> = MaybeUninit::uninit();
+        unsafe {
+            let res = C_KZG_RET::from_raw(bindings::blob_prefix_to_kzg_commitment(
+                kzg_commitment.as_mut_ptr(),
+                field_elements.as_ptr() as *const u8,
+                field_elements.len(),
+                &kzg_settings.0,
+            ));
+            match res {
+                C_KZG_RET::C_KZG_OK => Ok(Self(kzg_commitment.assume_init())),
+                _ => Err(field_elements_error("commit_partial", field_elements, res)),
+            }
+        }
+    }
+
+    /// Commits to the polynomial with the given coefficients, lowest degree first, rather than to
+    /// its evaluations like the other commitments. At most `FIELD_ELEMENTS_PER_BLOB` coefficients
+    /// are accepted, and the commitment equals the one of the blob of evaluations of the
+    /// polynomial, so `KzgProof::compute_proof_monomial` and `KzgProof::verify_kzg_proof` open it.
+    pub fn commit_coefficients(
+        coefficients: &[[u8; BYTES_PER_FIELD_ELEMENT]],
+        kzg_settings: &KzgSettings,
+    ) -> Result<Self, Error> {
+        ffi_span!("commit_coefficients", num_coefficients = coefficients.len());
+        kzg_settings.check_g1_points("commit_coefficients")?;
+        if coefficients.len() > FIELD_ELEMENTS_PER_BLOB {
+            return Err(Error::from(C_KZG_RET::C_KZG_BADARGS)
+                .in_operation("commit_coefficients", Some("coefficients")));
+        }
+        let mut kzg_commitment: MaybeUninit<bindings::KZGCommitment> = MaybeUninit::uninit();
+        unsafe {
+            let res = C_KZG_RET::from_raw(bindings::commit_coefficients(
+                kzg_commitment.as_mut_ptr(),
+                coefficients.as_ptr() as *const u8,
+                coefficients.len(),
+                &kzg_settings.0,
+            ));
+            match res {
+                C_KZG_RET::C_KZG_OK => Ok(Self(kzg_commitment.assume_init())),
+                _ => Err(field_elements_error(
+                    "commit_coefficients",
+                    coefficients,
+                    res,
+                )),
+            }
+        }
+    }
+
+    /// Reads a blob from `reader` and commits to it, without the caller holding a copy of it. The
+    /// blob is read into a heap buffer 4 KiB at a time, and each chunk is
+    /// checked as it arrives, so that a non-canonical field element stops the read. With the
+    /// `constant-time` feature the field elements are only checked once the whole blob is read.
+    ///
+    /// Fails with `Error::ReadError` if the reader fails or ends before `BYTES_PER_BLOB` bytes.
+    #[cfg(feature = "std")]
+    pub fn blob_to_kzg_commitment_from_reader<R: std::io::Read>(
+        mut reader: R,
+        kzg_settings: &KzgSettings,
+    ) -> Result<Self, Error> {
+        let mut blob = AlignedBlob::new_boxed_aligned();
+        for (index, chunk) in blob.chunks_mut(READ_CHUNK_SIZE).enumerate() {
+            reader
+                .read_exact(chunk)
+                .map_err(|error| Error::ReadError { kind: error.kind() })?;
+            if cfg!(feature = "constant-time") {
+                continue;
+            }
+            for (offset, field_element) in chunk.chunks_exact(BYTES_PER_FIELD_ELEMENT).enumerate() {
+                let mut bytes = [0; BYTES_PER_FIELD_ELEMENT];
+                bytes.copy_from_slice(field_element);
+                if BlsFieldElement::bytes_to_bls_field(bytes).is_err() {
+                    return Err(Error::InvalidFieldElement {
+                        position: index * READ_CHUNK_SIZE / BYTES_PER_FIELD_ELEMENT + offset,
+                    });
+                }
+            }
+        }
+        Self::blob_to_kzg_commitment_ref(&blob, kzg_settings)
+    }
+
+    /// Computes the commitments to `blobs`, spreading the work over the rayon thread pool.
+    #[cfg(feature = "parallel")]
+    pub fn blob_to_kzg_commitment_batch_parallel(
+        blobs: &[Blob],
+        kzg_settings: &KzgSettings,
+    ) -> Result<Vec<Self>, Error> {
+        use rayon::prelude::*;
+
+        blobs
+            .par_iter()
+            .map(|blob| Self::blob_to_kzg_commitment_ref(blob, kzg_settings))
+            .collect()
+    }
+
+    /// Computes the commitments to `blobs` one after the other, calling `should_cancel` before
+    /// each blob and returning `Error::Cancelled` as soon as it returns `true`.
+    pub fn blob_to_kzg_commitment_batch_cancellable(
+        blobs: &[Blob],
+        kzg_settings: &KzgSettings,
+        should_cancel: &dyn Fn() -> bool,
+    ) -> Result<Vec<Self>, Error> {
+        blobs
+            .iter()
+            .map(|blob| {
+                if should_cancel() {
+                    return Err(Error::Cancelled);
+                }
+                Self::blob_to_kzg_commitment_ref(blob, kzg_settings)
+            })
+            .collect()
+    }
+
+    /// Like `blob_to_kzg_commitment_batch_cancellable`, but spreads the work over the rayon thread
+    /// pool. Blobs that are already being committed to when `should_cancel` returns `true` are
+    /// finished first.
+    #[cfg(feature = "parallel")]
+    pub fn blob_to_kzg_commitment_batch_parallel_cancellable(
+        blobs: &[Blob],
+        kzg_settings: &KzgSettings,
+        should_cancel: &(dyn Fn() -> bool + Sync),
+    ) -> Result<Vec<Self>, Error> {
+        use rayon::prelude::*;
+
+        blobs
+            .par_iter()
+            .map(|blob| {
+                if should_cancel() {
+                    return Err(Error::Cancelled);
+                }
+                Self::blob_to_kzg_commitment_ref(blob, kzg_settings)
+            })
+            .collect()
+    }
+}
+
+/// The EIP-4844 operations, so that code can be written generically over KZG implementations.
+/// Implemented by `KzgSettings` with the types of this crate.
+pub trait KzgBackend {
+    type Commitment;
+    type Proof;
+    type Error;
+
+    fn blob_to_kzg_commitment(&self, blob: &Blob) -> Result<Self::Commitment, Self::Error>;
+
+    fn compute_aggregate_kzg_proof(&self, blobs: &[Blob]) -> Result<Self::Proof, Self::Error>;
+
+    fn verify_aggregate_kzg_proof(
+        &self,
+        blobs: &[Blob],
+        commitments: &[Self::Commitment],
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error>;
+
+    fn verify_kzg_proof(
+        &self,
+        commitment: &Self::Commitment,
+        z: [u8; BYTES_PER_FIELD_ELEMENT],
+        y: [u8; BYTES_PER_FIELD_ELEMENT],
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error>;
+}
+
+impl KzgBackend for KzgSettings {
+    type Commitment = KzgCommitment;
+    type Proof = KzgProof;
+    type Error = Error;
+
+    fn blob_to_kzg_commitment(&self, blob: &Blob) -> Result<KzgCommitment, Error> {
+        KzgCommitment::blob_to_kzg_commitment_ref(blob, self)
+    }
+
+    fn compute_aggregate_kzg_proof(&self, blobs: &[Blob]) -> Result<KzgProof, Error> {
+        KzgProof::compute_aggregate_kzg_proof(blobs, self)
+    }
+
+    fn verify_aggregate_kzg_proof(
+        &self,
+        blobs: &[Blob],
+        commitments: &[KzgCommitment],
+        proof: &KzgProof,
+    ) -> Result<bool, Error> {
+        proof.verify_aggregate_kzg_proof(blobs, commitments, self)
+    }
+
+    fn verify_kzg_proof(
+        &self,
+        commitment: &KzgCommitment,
+        z: [u8; BYTES_PER_FIELD_ELEMENT],
+        y: [u8; BYTES_PER_FIELD_ELEMENT],
+        proof: &KzgProof,
+    ) -> Result<bool, Error> {
+        proof.verify_kzg_proof(KzgCommitment(commitment.0), z, y, self)
+    }
+}
+
+#[cfg(feature = "blst-interop")]
+impl KzgCommitment {
+    pub fn to_blst_p1_affine(&self) -> blst_p1_affine {
+        g1_to_affine(&self.0)
+    }
+
+    /// Fails with `Error::InvalidG1Point` if the point is not in the G1 subgroup.
+    pub fn from_blst_p1_affine(point: &blst_p1_affine) -> Result<Self, Error> {
+        Ok(Self(g1_from_affine(point)?))
+    }
+}
+
+/// Converts a compressed G1 point, which arkworks encodes the same way as blst.
+#[cfg(feature = "ark")]
+fn g1_to_ark(bytes: &[u8; BYTES_PER_G1_POINT]) -> Result<ark_bls12_381::G1Affine, Error> {
+    use ark_serialize::CanonicalDeserialize;
+
+    ark_bls12_381::G1Affine::deserialize_compressed(&bytes[..]).map_err(|_| Error::InvalidG1Point)
+}
+
+#[cfg(feature = "ark")]
+fn g1_from_ark(point: &ark_bls12_381::G1Affine) -> Result<g1_t, Error> {
+    use ark_serialize::CanonicalSerialize;
+
+    let mut bytes = [0; BYTES_PER_G1_POINT];
+    point.serialize_compressed(&mut bytes[..]).map_err(|_| {
+        Error::from(C_KZG_RET::C_KZG_BADARGS).in_operation("g1_from_ark", Some("point"))
+    })?;
+    bytes_to_g1(&bytes)
+}
+
+#[cfg(feature = "ark")]
+impl TryFrom<&KzgCommitment> for ark_bls12_381::G1Affine {
+    type Error = Error;
+
+    fn try_from(commitment: &KzgCommitment) -> Result<Self, Error> {
+        g1_to_ark(&commitment.to_bytes())
+    }
+}
+
+#[cfg(feature = "ark")]
+impl TryFrom<&ark_bls12_381::G1Affine> for KzgCommitment {
+    type Error = Error;
+
+    fn try_from(point: &ark_bls12_381::G1Affine) -> Result<Self, Error> {
+        Ok(Self(g1_from_ark(point)?))
+    }
+}
+
+#[cfg(feature = "ark")]
+impl TryFrom<&KzgProof> for ark_bls12_381::G1Affine {
+    type Error = Error;
+
+    fn try_from(proof: &KzgProof) -> Result<Self, Error> {
+        g1_to_ark(&proof.to_bytes())
+    }
+}
+
+#[cfg(feature = "ark")]
+impl TryFrom<&ark_bls12_381::G1Affine> for KzgProof {
+    type Error = Error;
+
+    fn try_from(point: &ark_bls12_381::G1Affine) -> Result<Self, Error> {
+        Ok(Self(g1_from_ark(point)?))
+    }
+}
+
+#[cfg(feature = "ark")]
+impl TryFrom<&BlsFieldElement> for ark_bls12_381::Fr {
+    type Error = Error;
+
+    fn try_from(element: &BlsFieldElement) -> Result<Self, Error> {
+        use ark_serialize::CanonicalDeserialize;
+
+        Self::deserialize_compressed(&element.to_bytes()[..])
+            .map_err(|_| Error::InvalidFieldElement { position: 0 })
+    }
+}
+
+#[cfg(feature = "ark")]
+impl TryFrom<&ark_bls12_381::Fr> for BlsFieldElement {
+    type Error = Error;
+
+    fn try_from(element: &ark_bls12_381::Fr) -> Result<Self, Error> {
+        use ark_serialize::CanonicalSerialize;
+
+        let mut bytes = [0; BYTES_PER_FIELD_ELEMENT];
+        element.serialize_compressed(&mut bytes[..]).map_err(|_| {
+            Error::from(C_KZG_RET::C_KZG_BADARGS).in_operation("fr_from_ark", Some("element"))
+        })?;
+        Self::bytes_to_bls_field(bytes)
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for KzgCommitment {
+    /// Compares the compressed encodings without branching on their contents.
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        subtle::ConstantTimeEq::ct_eq(&self.to_bytes()[..], &other.to_bytes()[..])
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for KzgProof {
+    /// Compares the compressed encodings without branching on their contents.
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        subtle::ConstantTimeEq::ct_eq(&self.to_bytes()[..], &other.to_bytes()[..])
+    }
+}
+
+/// Proof harnesses for the byte and hex conversions, run with `cargo kani --features mock` since
+/// Kani cannot reason about the C library.
+#[cfg(all(kani, feature = "mock"))]
+mod verification {
+    use super::*;
+
+    #[kani::proof]
+    #[kani::unwind(97)]
+    fn proof_from_bytes_checks_length() {
+        let bytes: [u8; 2 * BYTES_PER_PROOF] = kani::any();
+        let len: usize = kani::any();
+        kani::assume(len <= bytes.len());
+        match KzgProof::from_bytes(&bytes[..len]) {
+            Ok(proof) => {
+                assert_eq!(len, BYTES_PER_PROOF);
+                assert_eq!(proof.to_bytes(), bytes[..BYTES_PER_PROOF]);
+            }
+            Err(error) => assert_eq!(
+                error,
+                Error::InvalidKzgProof {
+                    expected: BYTES_PER_PROOF,
+                    actual: len
+                }
+            ),
+        }
+    }
+
+    #[kani::proof]
+    #[kani::unwind(97)]
+    fn commitment_from_bytes_checks_length() {
+        let bytes: [u8; 2 * BYTES_PER_COMMITMENT] = kani::any();
+        let len: usize = kani::any();
+        kani::assume(len <= bytes.len());
+        match KzgCommitment::from_bytes(&bytes[..len]) {
+            Ok(commitment) => {
+                assert_eq!(len, BYTES_PER_COMMITMENT);
+                assert_eq!(commitment.to_bytes(), bytes[..BYTES_PER_COMMITMENT]);
+            }
+            Err(error) => assert_eq!(
+                error,
+                Error::InvalidKzgCommitment {
+                    expected: BYTES_PER_COMMITMENT,
+                    actual: len
+                }
+            ),
+        }
+    }
+
+    #[cfg(feature = "hex")]
+    #[kani::proof]
+    #[kani::unwind(101)]
+    fn decode_hex_never_panics() {
+        let input: [u8; 2 + 2 * BYTES_PER_G1_POINT + 2] = kani::any();
+        let len: usize = kani::any();
+        kani::assume(len <= input.len());
+        if let Ok(hex_str) = core::str::from_utf8(&input[..len]) {
+            if decode_hex::<BYTES_PER_G1_POINT>(hex_str).is_ok() {
+                assert_eq!(strip_hex_prefix(hex_str).len(), 2 * BYTES_PER_G1_POINT);
+            }
+        }
+    }
+
+    #[cfg(feature = "hex")]
+    #[kani::proof]
+    #[kani::unwind(99)]
+    fn hex_round_trip() {
+        let bytes: [u8; BYTES_PER_G1_POINT] = kani::any();
+        let proof = KzgProof::from_bytes(&bytes).unwrap();
+        let hex_str = proof.as_hex_string();
+        assert_eq!(hex_str.len(), 2 * BYTES_PER_G1_POINT);
+        assert_eq!(KzgProof::from_hex(&hex_str).unwrap().to_bytes(), bytes);
+        let prefixed = alloc::format!("0x{}", hex_str);
+        assert_eq!(
+            KzgCommitment::from_hex(&prefixed).unwrap().to_bytes(),
+            bytes
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    #[cfg(feature = "std")]
+    use std::path::PathBuf;
+    use test_utils::{
+        decode_blobs, embedded_trusted_setup_text, encode_blobs, random_valid_blob, valid_sidecar,
+        USABLE_BYTES_PER_BLOB,
+    };
+
+    /// Loads the trusted setup embedded in the test binary, so that tests do not need `std::fs` or
+    /// the file loader and also run without the `std` feature.
+    fn load_embedded_trusted_setup() -> KzgSettings {
+        let trusted_setup = embedded_trusted_setup_text();
+        let (g1_bytes, g2_bytes) = trusted_setup_from_text(trusted_setup).unwrap();
+        KzgSettings::load_trusted_setup(g1_bytes, g2_bytes).unwrap()
+    }
+
+    #[test]
+    fn test_version() {
+        let version = version();
+        assert_eq!(version.crate_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(version.spec_milestones, ["eip4844"]);
+        let c_library_version = if cfg!(feature = "mock") {
+            "mock"
+        } else {
+            env!("CARGO_PKG_VERSION")
+        };
+        assert_eq!(
+            version.to_string(),
+            format!(
+                "c-kzg {} (C library {}, eip4844)",
+                env!("CARGO_PKG_VERSION"),
+                c_library_version
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_trusted_setup() {
+        let g1 = "ab".repeat(BYTES_PER_G1_POINT);
+        let g2 = "CD".repeat(BYTES_PER_G2_POINT);
+        let text = |n1: usize, g1: &str| {
+            let mut text = format!("{}\n{}\n", n1, NUM_G2_POINTS);
+            for _ in 0..n1 {
+                text += g1;
+                text += "\n";
+            }
+            for _ in 0..NUM_G2_POINTS {
+                text += &g2;
+                text += "\n";
+            }
+            text
+        };
+
+        let (g1_bytes, g2_bytes) =
+            trusted_setup_from_text(&text(FIELD_ELEMENTS_PER_BLOB, &g1)).unwrap();
+        assert_eq!(
+            g1_bytes,
+            vec![[0xab; BYTES_PER_G1_POINT]; FIELD_ELEMENTS_PER_BLOB]
+        );
+        assert_eq!(g2_bytes, vec![[0xcd; BYTES_PER_G2_POINT]; NUM_G2_POINTS]);
+
+        assert_eq!(
+            trusted_setup_from_text(&text(FIELD_ELEMENTS_PER_BLOB + 1, &g1)).err(),
+            Some(Error::InvalidTrustedSetup(
+                TrustedSetupError::InvalidG1PointCount {
+                    expected: FIELD_ELEMENTS_PER_BLOB,
+                    actual: FIELD_ELEMENTS_PER_BLOB + 1
+                }
+            ))
+        );
+        let invalid_format = Some(Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat));
+        assert_eq!(
+            trusted_setup_from_text(&text(FIELD_ELEMENTS_PER_BLOB, &g1[2..])).err(),
+            invalid_format
+        );
+        assert_eq!(
+            trusted_setup_from_text(&text(FIELD_ELEMENTS_PER_BLOB, &g1.replace('b', "g"))).err(),
+            invalid_format
+        );
+        let truncated = text(FIELD_ELEMENTS_PER_BLOB, &g1);
+        assert_eq!(
+            trusted_setup_from_text(&truncated[..truncated.len() - 10]).err(),
+            invalid_format
+        );
+    }
+
+    #[test]
+    fn test_trusted_setup_conversions() {
+        let text = embedded_trusted_setup_text();
+        let (g1_bytes, g2_bytes) = trusted_setup_from_text(text).unwrap();
+        assert_eq!(trusted_setup_to_text(&g1_bytes, &g2_bytes), text.trim_end());
+
+        let bin = trusted_setup_to_bin(&g1_bytes, &g2_bytes);
+        assert_eq!(
+            bin.len(),
+            FIELD_ELEMENTS_PER_BLOB * BYTES_PER_G1_POINT + NUM_G2_POINTS * BYTES_PER_G2_POINT
+        );
+        assert_eq!(trusted_setup_from_bin(&bin).unwrap(), (g1_bytes, g2_bytes));
+        assert_eq!(
+            trusted_setup_from_bin(&bin[1..]).err(),
+            Some(Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat))
+        );
+    }
+
+    #[test]
+    fn test_load_trusted_setup_verify_only() {
+        let kzg_settings = load_embedded_trusted_setup();
+        let trusted_setup = embedded_trusted_setup_text();
+        let (_, mut g2_bytes) = trusted_setup_from_text(trusted_setup).unwrap();
+        let verify_only = KzgSettings::load_trusted_setup_verify_only(g2_bytes.clone()).unwrap();
+
+        let sidecar = valid_sidecar(2, &kzg_settings).unwrap();
+        assert!(sidecar
+            .proof
+            .verify_aggregate_kzg_proof(&sidecar.blobs, &sidecar.commitments, &verify_only)
+            .unwrap());
+        #[cfg(not(feature = "mock"))]
+        {
+            assert!(!sidecar
+                .proof
+                .verify_aggregate_kzg_proof(
+                    &sidecar.blobs[..1],
+                    &sidecar.commitments[..1],
+                    &verify_only
+                )
+                .unwrap());
+            assert_eq!(
+                KzgCommitment::blob_to_kzg_commitment_ref(&sidecar.blobs[0], &verify_only).err(),
+                Some(Error::MissingG1Points {
+                    operation: "blob_to_kzg_commitment"
+                })
+            );
+            assert_eq!(
+                KzgProof::compute_aggregate_kzg_proof(&sidecar.blobs, &verify_only).err(),
+                Some(Error::MissingG1Points {
+                    operation: "compute_aggregate_kzg_proof"
+                })
+            );
+            assert_eq!(
+                KzgProof::compute_equivalence_proof(
+                    &sidecar.blobs[0],
+                    &sidecar.commitments[0],
+                    &[0; 32],
+                    &verify_only
+                )
+                .err(),
+                Some(Error::MissingG1Points {
+                    operation: "compute_equivalence_proof"
+                })
+            );
+            let field_elements = [[0; BYTES_PER_FIELD_ELEMENT]; 2];
+            assert_eq!(
+                KzgCommitment::commit_partial(&field_elements, &verify_only).err(),
+                Some(Error::MissingG1Points {
+                    operation: "commit_partial"
+                })
+            );
+            assert_eq!(
+                KzgCommitment::commit_coefficients(&field_elements, &verify_only).err(),
+                Some(Error::MissingG1Points {
+                    operation: "commit_coefficients"
+                })
+            );
+            assert_eq!(
+                KzgProof::compute_proof_monomial(
+                    &field_elements,
+                    [0; BYTES_PER_FIELD_ELEMENT],
+                    &verify_only
+                )
+                .err(),
+                Some(Error::MissingG1Points {
+                    operation: "compute_proof_monomial"
+                })
+            );
+            assert_eq!(
+                KzgProof::compute_degree_bound_proof(&field_elements, 2, &verify_only).err(),
+                Some(Error::MissingG1Points {
+                    operation: "compute_degree_bound_proof"
+                })
+            );
+            #[cfg(feature = "std")]
+            assert_eq!(
+                verify_only
+                    .serialize_precomputed(&mut Vec::new())
+                    .unwrap_err()
+                    .kind(),
+                std::io::ErrorKind::InvalidInput
+            );
+        }
+
+        g2_bytes.pop();
+        assert_eq!(
+            KzgSettings::load_trusted_setup_verify_only(g2_bytes).err(),
+            Some(Error::InvalidTrustedSetup(
+                TrustedSetupError::InvalidG2PointCount {
+                    expected: NUM_G2_POINTS,
+                    actual: NUM_G2_POINTS - 1,
+                }
+            ))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_precomputed_settings() {
+        let kzg_settings = load_embedded_trusted_setup();
+        let mut bytes = Vec::new();
+        kzg_settings.serialize_precomputed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), PRECOMPUTED_LEN);
+        let restored = KzgSettings::deserialize_precomputed(&bytes).unwrap();
+
+        let sidecar = valid_sidecar(2, &kzg_settings).unwrap();
+        for (blob, commitment) in sidecar.blobs.iter().zip(&sidecar.commitments) {
+            assert_eq!(
+                KzgCommitment::blob_to_kzg_commitment_ref(blob, &restored)
+                    .unwrap()
+                    .to_bytes(),
+                commitment.to_bytes()
+            );
+        }
+        assert_eq!(
+            KzgProof::compute_aggregate_kzg_proof(&sidecar.blobs, &restored)
+                .unwrap()
+                .to_bytes(),
+            sidecar.proof.to_bytes()
+        );
+        assert!(sidecar
+            .proof
+            .verify_aggregate_kzg_proof(&sidecar.blobs, &sidecar.commitments, &restored)
+            .unwrap());
+
+        let invalid_format = Some(Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat));
+        assert_eq!(
+            KzgSettings::deserialize_precomputed(&bytes[..bytes.len() - 1]).err(),
+            invalid_format
+        );
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[7] = 1;
+        assert_eq!(
+            KzgSettings::deserialize_precomputed(&wrong_magic).err(),
+            invalid_format
+        );
+        let mut wrong_count = bytes.clone();
+        wrong_count[8] ^= 1;
+        assert!(matches!(
+            KzgSettings::deserialize_precomputed(&wrong_count),
+            Err(Error::InvalidTrustedSetup(
+                TrustedSetupError::InvalidG1PointCount { .. }
+            ))
+        ));
+        #[cfg(not(feature = "mock"))]
+        {
+            let badargs = Some(Error::InvalidTrustedSetup(TrustedSetupError::CError(
+                C_KZG_RET::C_KZG_BADARGS,
+            )));
+            // The digest no longer matches the first g1 point.
+            let mut corrupted = bytes.clone();
+            corrupted[16 + 95] ^= 1;
+            assert_eq!(
+                KzgSettings::deserialize_precomputed(&corrupted).err(),
+                badargs
+            );
+            // The digest no longer matches the last g2 point.
+            let mut corrupted = bytes;
+            corrupted[PRECOMPUTED_LEN - 33] ^= 1;
+            assert_eq!(
+                KzgSettings::deserialize_precomputed(&corrupted).err(),
+                badargs
+            );
+        }
+    }
+
+    #[test]
+    fn test_trusted_setup_ssz() {
+        let text = embedded_trusted_setup_text();
+        let (g1_bytes, g2_bytes) = trusted_setup_from_text(text).unwrap();
+        let ssz = trusted_setup_to_ssz(&g1_bytes, &g2_bytes);
+        assert_eq!(&ssz[..4], &8u32.to_le_bytes());
+        assert_eq!(
+            &ssz[4..8],
+            &((8 + FIELD_ELEMENTS_PER_BLOB * BYTES_PER_G1_POINT) as u32).to_le_bytes()
+        );
+        let (ssz_g1_bytes, ssz_g2_bytes) = trusted_setup_from_ssz(&ssz).unwrap();
+        assert_eq!(
+            trusted_setup_to_text(&ssz_g1_bytes, &ssz_g2_bytes),
+            text.trim_end()
+        );
+
+        let blob = random_valid_blob(&mut rand::thread_rng());
+        assert_eq!(
+            KzgCommitment::blob_to_kzg_commitment_ref(
+                &blob,
+                &KzgSettings::load_trusted_setup_ssz(&ssz).unwrap()
+            )
+            .unwrap()
+            .to_bytes(),
+            KzgCommitment::blob_to_kzg_commitment_ref(&blob, &load_embedded_trusted_setup())
+                .unwrap()
+                .to_bytes()
+        );
+
+        let invalid_format = Some(Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat));
+        assert_eq!(trusted_setup_from_ssz(&ssz[..7]).err(), invalid_format);
+        assert_eq!(
+            trusted_setup_from_ssz(&ssz[..ssz.len() - 1]).err(),
+            invalid_format
+        );
+        let mut bad_offset = ssz.clone();
+        bad_offset[4] ^= 1;
+        assert_eq!(trusted_setup_from_ssz(&bad_offset).err(), invalid_format);
+        assert!(matches!(
+            trusted_setup_from_ssz(&trusted_setup_to_ssz(&g1_bytes[1..], &g2_bytes)),
+            Err(Error::InvalidTrustedSetup(
+                TrustedSetupError::InvalidG1PointCount { .. }
+            ))
+        ));
+    }
+
+    #[cfg(not(feature = "mock"))]
+    fn test_simple(kzg_settings: KzgSettings) {
+        let mut rng = rand::thread_rng();
+
+        // At least one blob, which is replaced with an incorrect one below.
+        let num_blobs: usize = rng.gen_range(1..16);
+        let mut blobs: Vec<Blob> = (0..num_blobs)
+            .map(|_| random_valid_blob(&mut rng))
+            .collect();
+
+        let kzg_commitments: Vec<KzgCommitment> = blobs
+            .clone()
+            .into_iter()
+            .map(|blob| KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings).unwrap())
+            .collect();
+
+        let kzg_proof = KzgProof::compute_aggregate_kzg_proof(&blobs, &kzg_settings).unwrap();
+
+        assert!(kzg_proof
+            .verify_aggregate_kzg_proof(&blobs, &kzg_commitments, &kzg_settings)
+            .unwrap());
+
+        let incorrect_blob = random_valid_blob(&mut rng);
+        blobs.pop();
+        blobs.push(incorrect_blob);
+
+        assert!(!kzg_proof
+            .verify_aggregate_kzg_proof(&blobs, &kzg_commitments, &kzg_settings)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_valid_sidecar() {
+        let kzg_settings = load_embedded_trusted_setup();
+        let sidecar = valid_sidecar(3, &kzg_settings).unwrap();
+        assert_eq!(sidecar.blobs.len(), 3);
+        assert!(sidecar
+            .proof
+            .verify_aggregate_kzg_proof(&sidecar.blobs, &sidecar.commitments, &kzg_settings)
+            .unwrap());
+        // The sidecar only depends on its size.
+        let again = valid_sidecar(3, &kzg_settings).unwrap();
+        assert_eq!(again.blobs, sidecar.blobs);
+        assert_eq!(again.proof.to_bytes(), sidecar.proof.to_bytes());
+    }
+
+    #[cfg(not(feature = "mock"))]
+    #[test]
+    fn test_bit_flips_rejected() {
+        let kzg_settings = load_embedded_trusted_setup();
+        let sidecar = valid_sidecar(1, &kzg_settings).unwrap();
+        // About 32 flips spread over the blob.
+        let blob_stride = 8 * BYTES_PER_BLOB / 32 + 1;
+        test_utils::assert_bit_flips_rejected(
+            &sidecar.blobs[0],
+            &sidecar.commitments[0],
+            &sidecar.proof,
+            &kzg_settings,
+            blob_stride,
+        );
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_corpus_seeds() {
+        let kzg_settings = load_embedded_trusted_setup();
+        let seeds = test_utils::corpus_seeds(2, &kzg_settings).unwrap();
+        // Three files per blob and three per sidecar.
+        assert_eq!(seeds.len(), 3 * 3 + 2 * 3);
+        for (name, contents) in &seeds {
+            if name.starts_with("proof-hex-") {
+                assert!(KzgProof::from_hex(core::str::from_utf8(contents).unwrap()).is_ok());
+            } else if name.ends_with(".json") {
+                let report = spec_tests::run_vectors(
+                    "public_agg_proof.json",
+                    core::str::from_utf8(contents).unwrap(),
+                    &kzg_settings,
+                );
+                assert!(report.is_success(), "{:?}", report);
+            }
+        }
+    }
+
+    #[test]
+    fn test_insecure_test_setup() {
+        let insecure_settings = KzgSettings::insecure_test_setup(1).unwrap();
+        let sidecar = test_utils::valid_sidecar(2, &insecure_settings).unwrap();
+        assert!(sidecar
+            .proof
+            .verify_aggregate_kzg_proof(&sidecar.blobs, &sidecar.commitments, &insecure_settings)
+            .unwrap());
+
+        #[cfg(not(feature = "mock"))]
+        {
+            let kzg_settings = load_embedded_trusted_setup();
+            let commitment =
+                KzgCommitment::blob_to_kzg_commitment_ref(&sidecar.blobs[0], &kzg_settings)
+                    .unwrap();
+            assert_ne!(commitment.to_bytes(), sidecar.commitments[0].to_bytes());
+            assert!(!sidecar
+                .proof
+                .verify_aggregate_kzg_proof(&sidecar.blobs, &sidecar.commitments, &kzg_settings)
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn test_commit_partial() {
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rand::thread_rng());
+        let field_elements: Vec<[u8; BYTES_PER_FIELD_ELEMENT]> = blob
+            .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+            .map(|bytes| bytes.try_into().unwrap())
+            .collect();
+        for n in [
+            0,
+            1,
+            3,
+            FIELD_ELEMENTS_PER_BLOB.min(100),
+            FIELD_ELEMENTS_PER_BLOB,
+        ] {
+            let mut padded = AlignedBlob::new_boxed_aligned();
+            padded[..n * BYTES_PER_FIELD_ELEMENT]
+                .copy_from_slice(&blob[..n * BYTES_PER_FIELD_ELEMENT]);
+            let expected =
+                KzgCommitment::blob_to_kzg_commitment_ref(&padded, &kzg_settings).unwrap();
+            let partial =
+                KzgCommitment::commit_partial(&field_elements[..n], &kzg_settings).unwrap();
+            assert_eq!(
+                partial.to_bytes(),
+                expected.to_bytes(),
+                "{} field elements",
+                n
+            );
+        }
+
+        let mut too_many = field_elements.clone();
+        too_many.push([0; BYTES_PER_FIELD_ELEMENT]);
+        assert!(matches!(
+            KzgCommitment::commit_partial(&too_many, &kzg_settings),
+            Err(Error::CError {
+                argument: Some("field_elements"),
+                ..
+            })
+        ));
+        #[cfg(not(feature = "mock"))]
+        {
+            let mut invalid = field_elements[..3].to_vec();
+            invalid[2] = BLS_MODULUS_BYTES;
+            assert_eq!(
+                KzgCommitment::commit_partial(&invalid, &kzg_settings).err(),
+                Some(non_canonical("commit_partial", 2))
+            );
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_blob_to_kzg_commitment_from_reader() {
+        use std::io::{Cursor, ErrorKind};
+
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rng);
+        let commitment = KzgCommitment::blob_to_kzg_commitment_ref(&blob, &kzg_settings).unwrap();
+        let mut reader = Cursor::new(blob.to_vec());
+        assert_eq!(
+            KzgCommitment::blob_to_kzg_commitment_from_reader(&mut reader, &kzg_settings)
+                .unwrap()
+                .to_bytes(),
+            commitment.to_bytes()
+        );
+        assert_eq!(reader.position(), BYTES_PER_BLOB as u64);
+
+        assert_eq!(
+            KzgCommitment::blob_to_kzg_commitment_from_reader(
+                &blob[..BYTES_PER_BLOB - 1],
+                &kzg_settings
+            )
+            .err(),
+            Some(Error::ReadError {
+                kind: ErrorKind::UnexpectedEof
+            })
+        );
+        assert!(Error::ReadError {
+            kind: ErrorKind::UnexpectedEof
+        }
+        .to_string()
+        .starts_with("could not read the input: "));
+
+        #[cfg(not(any(feature = "mock", feature = "constant-time")))]
+        {
+            let mut invalid = blob;
+            invalid[BYTES_PER_FIELD_ELEMENT..2 * BYTES_PER_FIELD_ELEMENT]
+                .copy_from_slice(&BLS_MODULUS_BYTES);
+            let mut reader = Cursor::new(invalid.to_vec());
+            assert_eq!(
+                KzgCommitment::blob_to_kzg_commitment_from_reader(&mut reader, &kzg_settings).err(),
+                Some(Error::InvalidFieldElement { position: 1 })
+            );
+            // The rest of the blob is not read.
+            assert!(reader.position() <= READ_CHUNK_SIZE as u64);
+        }
+    }
+
+    #[test]
+    fn test_g1_lincomb() {
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let points: Vec<_> = (0..10)
+            .map(|_| {
+                let blob = random_valid_blob(&mut rng);
+                KzgCommitment::blob_to_kzg_commitment_ref(&blob, &kzg_settings)
+                    .unwrap()
+                    .to_bytes()
+            })
+            .collect();
+        let scalars: Vec<_> = (0..10u8)
+            .map(|i| {
+                let mut bytes = [0; BYTES_PER_FIELD_ELEMENT];
+                bytes[0] = i + 1;
+                bytes
+            })
+            .collect();
+        let sum = g1_lincomb(&points, &scalars).unwrap();
+        assert_eq!(g1_lincomb(&points, &scalars).unwrap(), sum);
+        #[cfg(not(feature = "mock"))]
+        {
+            // Both the direct loop for fewer than 8 points and Pippenger for more agree with
+            // scalar multiplications done by hand.
+            let one = scalars[0];
+            assert_eq!(g1_lincomb(&points[..1], &[one]).unwrap(), points[0]);
+            let doubled = g1_lincomb(&[points[0], points[0]], &[one, one]).unwrap();
+            assert_eq!(g1_lincomb(&points[..1], &scalars[1..2]).unwrap(), doubled);
+            let mut repeated = vec![points[0]; 10];
+            repeated.extend_from_slice(&points[1..]);
+            let ones = vec![one; repeated.len()];
+            let mut coefficients = vec![[0; BYTES_PER_FIELD_ELEMENT]; 10];
+            coefficients[0][0] = 10;
+            coefficients[1..].copy_from_slice(&ones[..9]);
+            assert_eq!(
+                g1_lincomb(&repeated, &ones).unwrap(),
+                g1_lincomb(&points, &coefficients).unwrap()
+            );
+            let mut infinity = [0; BYTES_PER_G1_POINT];
+            infinity[0] = 0xc0;
+            assert_eq!(g1_lincomb(&[], &[]).unwrap(), infinity);
+            assert_eq!(
+                g1_lincomb(&points[..1], &[BLS_MODULUS_BYTES]).err(),
+                Some(Error::InvalidFieldElement { position: 0 })
+            );
+            // Without the compression flag.
+            let invalid = [0; BYTES_PER_G1_POINT];
+            assert_eq!(
+                g1_lincomb(&[invalid], &[one]).err(),
+                Some(Error::InvalidG1Point)
+            );
+            let (not_in_subgroup, _) = point_not_in_subgroup();
+            assert_eq!(
+                g1_lincomb(&[points[0], not_in_subgroup], &[one, one]).err(),
+                Some(Error::InvalidG1Point)
+            );
+        }
+        assert!(matches!(
+            g1_lincomb(&points, &scalars[1..]),
+            Err(Error::MismatchedLengths {
+                first_len: 10,
+                second_len: 9,
+                ..
+            })
+        ));
+    }
+
+    #[cfg(feature = "hiding")]
+    #[test]
+    fn test_hiding_commitment() {
+        let kzg_settings = load_embedded_trusted_setup();
+        let sidecar = valid_sidecar(2, &kzg_settings).unwrap();
+        let blob = &sidecar.blobs[0];
+        let mut bytes = [0x42; BYTES_PER_FIELD_ELEMENT];
+        bytes[BYTES_PER_FIELD_ELEMENT - 1] = 0;
+        let blinding_factor = BlsFieldElement::bytes_to_bls_field(bytes).unwrap();
+        bytes[0] ^= 1;
+        let other_factor = BlsFieldElement::bytes_to_bls_field(bytes).unwrap();
+
+        let hiding = KzgCommitment::commit_hiding(blob, &blinding_factor, &kzg_settings).unwrap();
+        assert_ne!(hiding.to_bytes(), sidecar.commitments[0].to_bytes());
+        assert!(hiding
+            .verify_hiding_opening(blob, &blinding_factor, &kzg_settings)
+            .unwrap());
+        assert!(!hiding
+            .verify_hiding_opening(blob, &other_factor, &kzg_settings)
+            .unwrap());
+        assert!(!hiding
+            .verify_hiding_opening(&sidecar.blobs[1], &blinding_factor, &kzg_settings)
+            .unwrap());
+
+        // Once opened, the aggregate proof verifies against the unblinded commitment.
+        let commitments = [
+            hiding.unblind(&blinding_factor),
+            KzgCommitment::from_bytes(&sidecar.commitments[1].to_bytes()).unwrap(),
+        ];
+        assert!(sidecar
+            .proof
+            .verify_aggregate_kzg_proof(&sidecar.blobs, &commitments, &kzg_settings)
+            .unwrap());
+    }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn test_commitment_cache() {
+        let kzg_settings = load_embedded_trusted_setup();
+        let sidecar = valid_sidecar(2, &kzg_settings).unwrap();
+        let mut cache = CommitmentCache::new(1);
+
+        let bytes = sidecar.commitments[0].to_bytes();
+        let versioned_hash = sidecar.commitments[0].to_versioned_hash();
+        assert_eq!(versioned_hash[0], VERSIONED_HASH_VERSION_KZG);
+        assert!(cache.get(&versioned_hash).is_none());
+        assert_eq!(cache.commitment(&bytes).unwrap().to_bytes(), bytes);
+        assert_eq!(cache.commitment(&bytes).unwrap().to_bytes(), bytes);
+        assert_eq!(cache.get(&versioned_hash).unwrap().to_bytes(), bytes);
+        assert!(cache.commitment(&bytes[1..]).is_err());
+        // The capacity is one, so the second commitment evicts the first.
+        cache
+            .commitment(&sidecar.commitments[1].to_bytes())
+            .unwrap();
+        assert!(cache.get(&versioned_hash).is_none());
+
+        for _ in 0..2 {
+            assert!(cache
+                .verify_aggregate_kzg_proof(
+                    &sidecar.proof,
+                    &sidecar.blobs,
+                    &sidecar.commitments,
+                    &kzg_settings
+                )
+                .unwrap());
+        }
+        assert!(cache
+            .verify_aggregate_kzg_proof(
+                &sidecar.proof,
+                &sidecar.blobs[1..],
+                &sidecar.commitments,
+                &kzg_settings
+            )
+            .is_err());
+        // A proof verified with one setup is not taken as verified with another.
+        #[cfg(not(feature = "mock"))]
+        assert!(!cache
+            .verify_aggregate_kzg_proof(
+                &sidecar.proof,
+                &sidecar.blobs,
+                &sidecar.commitments,
+                &KzgSettings::insecure_test_setup(1).unwrap()
+            )
+            .unwrap());
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                commitment_hits: 2,
+                commitment_misses: 5,
+                proof_hits: 1,
+                proof_misses: if cfg!(feature = "mock") { 1 } else { 2 },
+            }
+        );
+        assert_eq!(cache.len(), 2);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats(), CacheStats::default());
+    }
+
+    #[cfg(feature = "low-level")]
+    #[test]
+    fn test_low_level() {
+        let kzg_settings = load_embedded_trusted_setup();
+        let mut rng = rand::thread_rng();
+        let blob = random_valid_blob(&mut rng);
+        let polynomial = blob_to_polynomial(&blob).unwrap();
+        assert_eq!(polynomial.len(), FIELD_ELEMENTS_PER_BLOB);
+        for (field_element, bytes) in polynomial
+            .iter()
+            .zip(blob.chunks_exact(BYTES_PER_FIELD_ELEMENT))
+        {
+            assert_eq!(field_element.to_bytes(), bytes);
+        }
+        #[cfg(not(feature = "mock"))]
+        {
+            let mut blob = blob;
+            blob[2 * BYTES_PER_FIELD_ELEMENT - 1] = 0xff;
+            assert_eq!(
+                blob_to_polynomial(&blob).err(),
+                Some(Error::InvalidFieldElement { position: 1 })
+            );
+        }
+
+        // A blob with the same value everywhere is a constant polynomial, which takes that value at
+        // every point and whose proofs are the point at infinity.
+        let mut bytes = [0; BYTES_PER_FIELD_ELEMENT];
+        bytes[0] = 5;
+        let five = BlsFieldElement::bytes_to_bls_field(bytes).unwrap();
+        let constant: Vec<u8> = bytes.repeat(FIELD_ELEMENTS_PER_BLOB);
+        let commitment =
+            KzgCommitment::blob_to_kzg_commitment_ref(&constant.try_into().unwrap(), &kzg_settings)
+                .unwrap();
+        let mut infinity = [0; BYTES_PER_PROOF];
+        infinity[0] = 0xc0;
+        let proof = KzgProof::from_bytes(&infinity).unwrap();
+        let z = polynomial[0];
+        assert!(proof
+            .verify_kzg_proof_field_elements(&commitment, &z, &five, &kzg_settings)
+            .unwrap());
+        #[cfg(not(feature = "mock"))]
+        {
+            bytes[0] = 6;
+            let six = BlsFieldElement::bytes_to_bls_field(bytes).unwrap();
+            assert!(!proof
+                .verify_kzg_proof_field_elements(&commitment, &z, &six, &kzg_settings)
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn test_monomial_basis() {
+        let kzg_settings = load_embedded_trusted_setup();
+        let field_element = |value: u8| {
+            let mut bytes = [0; BYTES_PER_FIELD_ELEMENT];
+            bytes[0] = value;
+            bytes
+        };
+
+        // p(X) = 3 + 2X opens to 17 at 7.
+        let coefficients = [field_element(3), field_element(2)];
+        let commitment = KzgCommitment::commit_coefficients(&coefficients, &kzg_settings).unwrap();
+        let (proof, y) =
+            KzgProof::compute_proof_monomial(&coefficients, field_element(7), &kzg_settings)
+                .unwrap();
+        #[cfg(not(feature = "mock"))]
+        assert_eq!(y, field_element(17));
+        assert!(proof
+            .verify_kzg_proof(commitment, field_element(7), y, &kzg_settings)
+            .unwrap());
+
+        let mut rng = rand::thread_rng();
+        let blob = random_valid_blob(&mut rng);
+        let coefficients: Vec<[u8; BYTES_PER_FIELD_ELEMENT]> = blob
+            .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+            .map(|bytes| bytes.try_into().unwrap())
+            .collect();
+        let commitment = KzgCommitment::commit_coefficients(&coefficients, &kzg_settings).unwrap();
+        let z = coefficients[0];
+        let (proof, y) = KzgProof::compute_proof_monomial(&coefficients, z, &kzg_settings).unwrap();
+        assert!(proof
+            .verify_kzg_proof(
+                KzgCommitment::from_bytes(&commitment.to_bytes()).unwrap(),
+                z,
+                y,
+                &kzg_settings
+            )
+            .unwrap());
+
+        #[cfg(not(feature = "mock"))]
+        {
+            // A constant polynomial commits like the blob of its value.
+            let constant: Vec<u8> = field_element(5).repeat(FIELD_ELEMENTS_PER_BLOB);
+            assert_eq!(
+                KzgCommitment::commit_coefficients(&[field_element(5)], &kzg_settings)
+                    .unwrap()
+                    .to_bytes(),
+                KzgCommitment::blob_to_kzg_commitment_ref(
+                    &constant.try_into().unwrap(),
+                    &kzg_settings
+                )
+                .unwrap()
+                .to_bytes()
+            );
+
+            let mut wrong_y = y;
+            wrong_y[0] ^= 1;
+            assert!(!proof
+                .verify_kzg_proof(commitment, z, wrong_y, &kzg_settings)
+                .unwrap());
+
+            let mut coefficients = coefficients;
+            coefficients[1] = [0xff; BYTES_PER_FIELD_ELEMENT];
+            assert_eq!(
+                KzgCommitment::commit_coefficients(&coefficients, &kzg_settings).err(),
+                Some(non_canonical("commit_coefficients", 1))
+            );
+            assert_eq!(
+                KzgProof::compute_proof_monomial(&coefficients, z, &kzg_settings).err(),
+                Some(non_canonical("compute_proof_monomial", 1))
+            );
+            assert!(matches!(
+                KzgProof::compute_proof_monomial(&coefficients, [0xff; 32], &kzg_settings),
+                Err(Error::CError {
+                    argument: Some("z"),
+                    ..
+                })
+            ));
+        }
+
+        let too_many = vec![[0; BYTES_PER_FIELD_ELEMENT]; FIELD_ELEMENTS_PER_BLOB + 1];
+        assert!(KzgCommitment::commit_coefficients(&too_many, &kzg_settings).is_err());
+        assert!(KzgProof::compute_proof_monomial(&too_many, [0; 32], &kzg_settings).is_err());
+    }
+
+    #[test]
+    fn test_degree_bound_proof() {
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rng);
+        let len = FIELD_ELEMENTS_PER_BLOB.min(10) - 1;
+        let coefficients: Vec<[u8; BYTES_PER_FIELD_ELEMENT]> = blob
+            .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+            .take(len)
+            .map(|bytes| bytes.try_into().unwrap())
+            .collect();
+        let commitment = KzgCommitment::commit_coefficients(&coefficients, &kzg_settings).unwrap();
+
+        // Shifts of several steps, of one and of 64 after the first, of one step, and none.
+        let bounds = [
+            len,
+            FIELD_ELEMENTS_PER_BLOB.saturating_sub(65).max(len),
+            FIELD_ELEMENTS_PER_BLOB.saturating_sub(64).max(len),
+            FIELD_ELEMENTS_PER_BLOB,
+        ];
+        for degree_bound in bounds {
+            let proof =
+                KzgProof::compute_degree_bound_proof(&coefficients, degree_bound, &kzg_settings)
+                    .unwrap();
+            assert_eq!(
+                proof.len(),
+                (FIELD_ELEMENTS_PER_BLOB - degree_bound).div_ceil(64)
+            );
+            assert!(KzgProof::verify_degree_bound_proof(
+                &proof,
+                &commitment,
+                degree_bound,
+                &kzg_settings
+            )
+            .unwrap());
+        }
+
+        // The polynomial has degree `len - 1`, so it cannot be proven to be below that.
+        assert!(matches!(
+            KzgProof::compute_degree_bound_proof(&coefficients, len - 1, &kzg_settings),
+            Err(Error::CError {
+                argument: Some("coefficients"),
+                ..
+            })
+        ));
+        let proof =
+            KzgProof::compute_degree_bound_proof(&coefficients, len, &kzg_settings).unwrap();
+        #[cfg(not(feature = "mock"))]
+        {
+            // A proof of the same length for the lower bound, but shifted one step short.
+            assert_eq!(
+                (FIELD_ELEMENTS_PER_BLOB - len + 1).div_ceil(64),
+                proof.len()
+            );
+            assert!(!KzgProof::verify_degree_bound_proof(
+                &proof,
+                &commitment,
+                len - 1,
+                &kzg_settings
+            )
+            .unwrap());
+            // Only the zero polynomial is below a bound of zero.
+            let zero = KzgCommitment::commit_coefficients(&[], &kzg_settings).unwrap();
+            let zero_proof = KzgProof::compute_degree_bound_proof(&[], 0, &kzg_settings).unwrap();
+            assert!(
+                KzgProof::verify_degree_bound_proof(&zero_proof, &zero, 0, &kzg_settings).unwrap()
+            );
+            assert!(!KzgProof::verify_degree_bound_proof(
+                &zero_proof,
+                &commitment,
+                0,
+                &kzg_settings
+            )
+            .unwrap());
+
+            let mut coefficients = coefficients.clone();
+            coefficients[1] = [0xff; BYTES_PER_FIELD_ELEMENT];
+            assert_eq!(
+                KzgProof::compute_degree_bound_proof(&coefficients, len, &kzg_settings).err(),
+                Some(non_canonical("compute_degree_bound_proof", 1))
+            );
+        }
+        assert!(matches!(
+            KzgProof::verify_degree_bound_proof(&proof[1..], &commitment, len, &kzg_settings),
+            Err(Error::CError {
+                argument: Some("proofs"),
+                ..
+            })
+        ));
+        assert!(matches!(
+            KzgProof::compute_degree_bound_proof(
+                &coefficients,
+                FIELD_ELEMENTS_PER_BLOB + 1,
+                &kzg_settings
+            ),
+            Err(Error::CError {
+                argument: Some("degree_bound"),
+                ..
+            })
+        ));
+    }
+
+    /// Seeded, so that failures reproduce. Payloads span several blobs, so keep the number of cases
+    /// low.
+    fn codec_config() -> proptest::test_runner::Config {
+        proptest::test_runner::Config {
+            cases: 32,
+            rng_seed: proptest::test_runner::RngSeed::Fixed(4844),
+            failure_persistence: None,
+            ..Default::default()
+        }
+    }
+
+    /// Payloads of sizes around the edges of the codec: empty and small ones, sizes around
+    /// multiples of the 31 data bytes of a field element, and sizes around the capacity of one and
+    /// two blobs.
+    fn codec_payload() -> impl proptest::strategy::Strategy<Value = Vec<u8>> {
+        use proptest::prelude::*;
+
+        let one_blob = USABLE_BYTES_PER_BLOB - 8;
+        let two_blobs = one_blob + USABLE_BYTES_PER_BLOB;
+        let len = prop_oneof![
+            0..=64usize,
+            (1..=8usize, 0..=2usize).prop_map(|(k, d)| 31 * k + d - 1),
+            (0..=2usize).prop_map(move |d| one_blob + d - 1),
+            (0..=2usize).prop_map(move |d| two_blobs + d - 1),
+            0..=2 * USABLE_BYTES_PER_BLOB + 64,
+        ];
+        len.prop_flat_map(|len| proptest::collection::vec(any::<u8>(), len))
+    }
+
+    proptest::proptest! {
+        #![proptest_config(codec_config())]
+
+        #[test]
+        fn test_codec_round_trip(data in codec_payload()) {
+            let blobs = encode_blobs(&data);
+            proptest::prop_assert_eq!(
+                blobs.len(),
+                (data.len() + 8).div_ceil(USABLE_BYTES_PER_BLOB).max(1)
+            );
+            let field_elements = blobs
+                .iter()
+                .flat_map(|blob| blob.chunks_exact(BYTES_PER_FIELD_ELEMENT));
+            for field_element in field_elements {
+                proptest::prop_assert_eq!(field_element[BYTES_PER_FIELD_ELEMENT - 1], 0);
+            }
+            proptest::prop_assert_eq!(decode_blobs(&blobs), Some(data));
+        }
+
+        #[test]
+        fn test_codec_corrupted_length(data in codec_payload(), bit in 0..64u32) {
+            let mut blobs = encode_blobs(&data);
+            let corrupted = (data.len() as u64) ^ (1 << bit);
+            blobs[0][..8].copy_from_slice(&corrupted.to_le_bytes());
+            proptest::prop_assert_ne!(decode_blobs(&blobs), Some(data));
+        }
+    }
+
+    #[test]
+    fn test_codec_invalid_blobs() {
+        assert_eq!(decode_blobs(&[]), None);
+
+        let mut blobs = encode_blobs(b"data");
+        blobs[0][BYTES_PER_FIELD_ELEMENT - 1] = 1;
+        assert_eq!(decode_blobs(&blobs), None);
+
+        // Padding after the data and whole trailing blobs are rejected.
+        let mut blobs = encode_blobs(b"data");
+        blobs[0][12] = 1;
+        assert_eq!(decode_blobs(&blobs), None);
+        let mut blobs = encode_blobs(b"data");
+        blobs.push([0; BYTES_PER_BLOB]);
+        assert_eq!(decode_blobs(&blobs), None);
+    }
+
+    #[test]
+    fn test_invalid_length_errors() {
+        assert_eq!(
+            KzgProof::from_bytes(&[0; 47]).err(),
+            Some(Error::InvalidKzgProof {
+                expected: BYTES_PER_PROOF,
+                actual: 47
+            })
+        );
+        assert_eq!(
+            KzgCommitment::from_bytes(&[0; 49]).err(),
+            Some(Error::InvalidKzgCommitment {
+                expected: BYTES_PER_COMMITMENT,
+                actual: 49
+            })
+        );
+        assert_eq!(
+            KzgSettings::load_trusted_setup(vec![], vec![]).err(),
+            Some(Error::InvalidTrustedSetup(
+                TrustedSetupError::InvalidG1PointCount {
+                    expected: FIELD_ELEMENTS_PER_BLOB,
+                    actual: 0
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_field_constants() {
+        assert_eq!(BLS_MODULUS_BYTES[0], 0x01);
+        assert_eq!(BLS_MODULUS_BYTES[31], 0x73);
+        assert_eq!(SCALE2_ROOT_OF_UNITY[0], [1, 0, 0, 0]);
+        // The primitive square root of unity is -1.
+        let mut minus_one = BLS_MODULUS;
+        minus_one[0] -= 1;
+        assert_eq!(SCALE2_ROOT_OF_UNITY[1], minus_one);
+
+        for bytes in SCALE2_ROOT_OF_UNITY_BYTES {
+            assert!(BlsFieldElement::bytes_to_bls_field(bytes).is_ok());
+        }
+        #[cfg(not(feature = "mock"))]
+        assert!(BlsFieldElement::bytes_to_bls_field(BLS_MODULUS_BYTES).is_err());
     }
-}
 
-pub struct KzgCommitment(bindings::KZGCommitment);
+    #[test]
+    fn test_error_context() {
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rng);
+        let commitment = KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings).unwrap();
+        let proof = KzgProof::compute_aggregate_kzg_proof(&[blob], &kzg_settings).unwrap();
 
-impl KzgCommitment {
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
-        if bytes.len() != BYTES_PER_COMMITMENT {
-            return Err(Error::InvalidKzgCommitment(format!(
-                "Invalid byte length. Expected {} got {}",
-                BYTES_PER_PROOF,
-                bytes.len(),
-            )));
+        let error = proof
+            .verify_aggregate_kzg_proof(
+                &[blob, blob],
+                core::slice::from_ref(&commitment),
+                &kzg_settings,
+            )
+            .unwrap_err();
+        assert_eq!(
+            error,
+            Error::MismatchedLengths {
+                operation: "verify_aggregate_kzg_proof",
+                first: "blobs",
+                first_len: 2,
+                second: "commitments",
+                second_len: 1,
+            }
+        );
+        #[cfg(feature = "std")]
+        assert_eq!(
+            error.to_string(),
+            "verify_aggregate_kzg_proof: blobs length 2 does not match commitments length 1"
+        );
+
+        #[cfg(not(feature = "mock"))]
+        {
+            let error = proof
+                .verify_kzg_proof(commitment, [0; 32], [0xff; 32], &kzg_settings)
+                .unwrap_err();
+            assert_eq!(
+                error,
+                Error::from(C_KZG_RET::C_KZG_BADARGS).in_operation("verify_kzg_proof", Some("y"))
+            );
+            assert_eq!(
+                KzgProof::from_bytes(&[0xff; BYTES_PER_PROOF]).err(),
+                Some(Error::InvalidG1Point)
+            );
+        }
+        assert_eq!(bytes_to_g1(&[0xc0]).err(), Some(Error::InvalidG1Point));
+        #[cfg(not(feature = "mock"))]
+        {
+            let (bytes, _) = point_not_in_subgroup();
+            assert_eq!(bytes_to_g1(&bytes).err(), Some(Error::InvalidG1Point));
+            assert_eq!(
+                KzgCommitment::from_bytes(&bytes).err(),
+                Some(Error::InvalidG1Point)
+            );
         }
-        let mut proof_bytes = [0; BYTES_PER_COMMITMENT];
-        proof_bytes.copy_from_slice(bytes);
-        Ok(Self(bytes_to_g1(bytes)?))
     }
 
-    pub fn to_bytes(&self) -> [u8; BYTES_PER_G1_POINT] {
-        bytes_from_g1(self.0)
+    #[cfg(all(feature = "std", unix))]
+    #[test]
+    fn test_load_trusted_setup_file_non_utf8_path() {
+        use std::ffi::OsStr;
+
+        let trusted_setup = embedded_trusted_setup_text();
+        let mut file_name = format!("c-kzg-{}-", std::process::id()).into_bytes();
+        file_name.extend_from_slice(b"\xff.txt");
+        let path = std::env::temp_dir().join(OsStr::from_bytes(&file_name));
+        std::fs::write(&path, trusted_setup).unwrap();
+        let loaded = KzgSettings::load_trusted_setup_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(loaded.is_ok());
     }
 
-    pub fn as_hex_string(&self) -> String {
-        hex::encode(self.to_bytes())
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_load_trusted_setup_json() {
+        let trusted_setup = embedded_trusted_setup_text();
+        let (g1_bytes, g2_bytes) = trusted_setup_from_text(trusted_setup).unwrap();
+        let points = |points: Vec<String>| format!("[{}]", points.join(", "));
+        let g1 = points(
+            g1_bytes
+                .iter()
+                .map(|point| format!("\"0x{}\"", hex::encode(point)))
+                .collect(),
+        );
+        let g2 = points(
+            g2_bytes
+                .iter()
+                .map(|point| format!("\"0x{}\"", hex::encode(point)))
+                .collect(),
+        );
+        // The ceremony output also lists the g1 points in Lagrange form, which are not read.
+        let json = format!(
+            "{{\"g1_monomial\": {}, \"g1_lagrange\": [], \"g2_monomial\": {}}}",
+            g1, g2
+        );
+        assert_eq!(
+            trusted_setup_from_json(&json).unwrap(),
+            (g1_bytes.clone(), g2_bytes.clone())
+        );
+        let settings = KzgSettings::load_trusted_setup_json(&json).unwrap();
+        let blob = random_valid_blob(&mut rand::thread_rng());
+        assert_eq!(
+            KzgCommitment::blob_to_kzg_commitment_ref(&blob, &settings)
+                .unwrap()
+                .to_bytes(),
+            KzgCommitment::blob_to_kzg_commitment_ref(&blob, &load_embedded_trusted_setup())
+                .unwrap()
+                .to_bytes()
+        );
+
+        let invalid_format = Some(Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat));
+        assert_eq!(
+            trusted_setup_from_json(&format!("{{\"g1_monomial\": {}}}", g1)).err(),
+            invalid_format
+        );
+        assert_eq!(
+            trusted_setup_from_json(&json.replacen("\"0x", "\"0xzz", 1)).err(),
+            invalid_format
+        );
+        assert!(matches!(
+            trusted_setup_from_json(&format!("{{\"g1_monomial\": [], \"g2_monomial\": {}}}", g2)),
+            Err(Error::InvalidTrustedSetup(
+                TrustedSetupError::InvalidG1PointCount { actual: 0, .. }
+            ))
+        ));
     }
 
-    pub fn blob_to_kzg_commitment(mut blob: Blob, kzg_settings: &KzgSettings) -> Self {
-        let mut kzg_commitment: MaybeUninit<bindings::KZGCommitment> = MaybeUninit::uninit();
-        unsafe {
-            bindings::blob_to_kzg_commitment(
-                kzg_commitment.as_mut_ptr(),
-                blob.as_mut_ptr(),
-                &kzg_settings.0,
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_load_trusted_setup_reader() {
+        use std::io::{Cursor, ErrorKind, Read};
+
+        let trusted_setup = embedded_trusted_setup_text();
+        let blob = random_valid_blob(&mut rand::thread_rng());
+        let expected =
+            KzgCommitment::blob_to_kzg_commitment_ref(&blob, &load_embedded_trusted_setup())
+                .unwrap()
+                .to_bytes();
+        let from_reader =
+            KzgSettings::load_trusted_setup_reader(Cursor::new(trusted_setup)).unwrap();
+        let from_str: KzgSettings = trusted_setup.parse().unwrap();
+        for settings in [from_reader, from_str] {
+            assert_eq!(
+                KzgCommitment::blob_to_kzg_commitment_ref(&blob, &settings)
+                    .unwrap()
+                    .to_bytes(),
+                expected
             );
-            Self(kzg_commitment.assume_init())
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rand::{rngs::ThreadRng, Rng};
+        assert_eq!(
+            KzgSettings::load_trusted_setup_reader(&b"4096\n65\n\xff"[..]).err(),
+            Some(Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat))
+        );
+        assert!(matches!(
+            "1\n65".parse::<KzgSettings>(),
+            Err(Error::InvalidTrustedSetup(
+                TrustedSetupError::InvalidG1PointCount { actual: 1, .. }
+            ))
+        ));
+
+        struct FailingReader;
+        impl Read for FailingReader {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(ErrorKind::ConnectionReset.into())
+            }
+        }
+        assert_eq!(
+            KzgSettings::load_trusted_setup_reader(FailingReader).err(),
+            Some(Error::ReadError {
+                kind: ErrorKind::ConnectionReset
+            })
+        );
+    }
 
-    fn generate_random_blob(rng: &mut ThreadRng) -> Blob {
-        let mut arr: Blob = [0; BYTES_PER_BLOB];
-        rng.fill(&mut arr[..]);
-        // Ensure that the blob is canonical by ensuring that
-        // each field element contained in the blob is < BLS_MODULUS
-        for i in 0..FIELD_ELEMENTS_PER_BLOB {
-            arr[i * BYTES_PER_FIELD_ELEMENT + BYTES_PER_FIELD_ELEMENT - 1] = 0;
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_load_missing_trusted_setup_file() {
+        let error = match KzgSettings::load_trusted_setup_file(PathBuf::from("does/not/exist.txt"))
+        {
+            Ok(_) => panic!("loaded a missing file"),
+            Err(error) => error,
+        };
+        match error {
+            Error::IoError { code } => assert_eq!(
+                std::io::Error::from_raw_os_error(code).kind(),
+                std::io::ErrorKind::NotFound
+            ),
+            error => panic!("unexpected error: {:?}", error),
         }
-        arr
+        assert!(error
+            .to_string()
+            .starts_with("could not read the trusted setup file: "));
     }
 
-    fn test_simple(trusted_setup_file: PathBuf) {
+    #[cfg(not(feature = "mock"))]
+    #[test]
+    fn test_invalid_field_element() {
         let mut rng = rand::thread_rng();
-        assert!(trusted_setup_file.exists());
-        let kzg_settings = KzgSettings::load_trusted_setup_file(trusted_setup_file).unwrap();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rng);
+        let mut invalid_blob = random_valid_blob(&mut rng);
+        let position = FIELD_ELEMENTS_PER_BLOB - 1;
+        invalid_blob[position * BYTES_PER_FIELD_ELEMENT..].copy_from_slice(&BLS_MODULUS_BYTES);
 
-        let num_blobs: usize = rng.gen_range(0..16);
-        let mut blobs: Vec<Blob> = (0..num_blobs)
-            .map(|_| generate_random_blob(&mut rng))
-            .collect();
+        assert_eq!(
+            KzgCommitment::blob_to_kzg_commitment(invalid_blob, &kzg_settings).err(),
+            Some(non_canonical("blob_to_kzg_commitment", position))
+        );
+        assert_eq!(
+            KzgProof::compute_aggregate_kzg_proof(&[blob, invalid_blob], &kzg_settings).err(),
+            Some(non_canonical(
+                "compute_aggregate_kzg_proof",
+                FIELD_ELEMENTS_PER_BLOB + position
+            ))
+        );
+        assert_eq!(
+            BlsFieldElement::bytes_to_bls_field(BLS_MODULUS_BYTES).err(),
+            Some(Error::InvalidFieldElement { position: 0 })
+        );
+    }
 
-        let kzg_commitments: Vec<KzgCommitment> = blobs
-            .clone()
-            .into_iter()
-            .map(|blob| KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings))
-            .collect();
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_error_display_and_source() {
+        use std::error::Error as _;
 
-        let kzg_proof = KzgProof::compute_aggregate_kzg_proof(&blobs, &kzg_settings).unwrap();
+        let error = Error::InvalidKzgProof {
+            expected: BYTES_PER_PROOF,
+            actual: 47,
+        };
+        assert_eq!(
+            error.to_string(),
+            "expected a 48 byte KZG proof, got 47 bytes"
+        );
+        assert!(error.source().is_none());
 
-        assert!(kzg_proof
-            .verify_aggregate_kzg_proof(&blobs, &kzg_commitments, &kzg_settings)
+        let error = Error::InvalidTrustedSetup(TrustedSetupError::CError(C_KZG_RET::C_KZG_BADARGS));
+        let source = error.source().unwrap();
+        assert_eq!(
+            source.to_string(),
+            "the c-kzg library could not load the trusted setup"
+        );
+        assert_eq!(
+            source.source().unwrap().to_string(),
+            "the supplied data is invalid in some way"
+        );
+
+        assert!(C_KZG_RET::C_KZG_BADARGS.is_bad_args());
+        assert!(!C_KZG_RET::C_KZG_BADARGS.is_internal());
+        assert!(C_KZG_RET::C_KZG_ERROR.is_internal());
+        assert!(C_KZG_RET::C_KZG_MALLOC.is_internal());
+        assert!(!C_KZG_RET::C_KZG_OK.is_bad_args() && !C_KZG_RET::C_KZG_OK.is_internal());
+        assert_eq!(C_KZG_RET::from_raw(1), C_KZG_RET::C_KZG_BADARGS);
+        assert_eq!(C_KZG_RET::from_raw(4), C_KZG_RET::C_KZG_ERROR);
+        assert_eq!(
+            Error::from(C_KZG_RET::C_KZG_ERROR).to_string(),
+            "c-kzg: the c-kzg library returned an error"
+        );
+
+        let error: Box<dyn std::error::Error> = Error::from(C_KZG_RET::C_KZG_MALLOC)
+            .in_operation("compute_aggregate_kzg_proof", None)
+            .into();
+        assert_eq!(
+            error.to_string(),
+            "compute_aggregate_kzg_proof: the c-kzg library returned an error"
+        );
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "could not allocate memory"
+        );
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_mock() {
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blobs: Vec<Blob> = (0..2).map(|_| random_valid_blob(&mut rng)).collect();
+
+        let commitment = KzgCommitment::blob_to_kzg_commitment(blobs[0], &kzg_settings).unwrap();
+        assert_eq!(
+            commitment.to_bytes(),
+            KzgCommitment::blob_to_kzg_commitment(blobs[0], &kzg_settings)
+                .unwrap()
+                .to_bytes()
+        );
+        assert_ne!(
+            commitment.to_bytes(),
+            KzgCommitment::blob_to_kzg_commitment(blobs[1], &kzg_settings)
+                .unwrap()
+                .to_bytes()
+        );
+        assert_eq!(
+            KzgCommitment::from_bytes(&commitment.to_bytes())
+                .unwrap()
+                .to_bytes(),
+            commitment.to_bytes()
+        );
+
+        let commitments = [
+            commitment,
+            KzgCommitment::blob_to_kzg_commitment(blobs[1], &kzg_settings).unwrap(),
+        ];
+        let proof = KzgProof::compute_aggregate_kzg_proof(&blobs, &kzg_settings).unwrap();
+        assert!(proof
+            .verify_aggregate_kzg_proof(&blobs, &commitments, &kzg_settings)
             .unwrap());
+    }
 
-        let incorrect_blob = generate_random_blob(&mut rng);
-        blobs.pop();
-        blobs.push(incorrect_blob);
+    #[test]
+    fn test_equivalence_proof() {
+        use sha2::Digest;
 
-        assert!(!kzg_proof
-            .verify_aggregate_kzg_proof(&blobs, &kzg_commitments, &kzg_settings)
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rng);
+        let commitment = KzgCommitment::blob_to_kzg_commitment_ref(&blob, &kzg_settings).unwrap();
+        let data_hash: [u8; 32] = sha2::Sha256::digest(blob).into();
+
+        let (proof, y) =
+            KzgProof::compute_equivalence_proof(&blob, &commitment, &data_hash, &kzg_settings)
+                .unwrap();
+        assert!(proof
+            .verify_equivalence_proof(&commitment, &data_hash, &y, &kzg_settings)
             .unwrap());
+
+        #[cfg(not(feature = "mock"))]
+        {
+            // The point as documented, so that verifiers elsewhere can derive it themselves.
+            let mut hasher = sha2::Sha256::new();
+            hasher.update(EQUIVALENCE_PROTOCOL_DOMAIN);
+            hasher.update(commitment.to_bytes());
+            hasher.update(data_hash);
+            let hashed: [u8; 32] = hasher.finalize().into();
+            let mut limbs: [u64; 4] = core::array::from_fn(|i| {
+                u64::from_le_bytes(hashed[8 * i..8 * i + 8].try_into().unwrap())
+            });
+            while limbs.iter().rev().cmp(BLS_MODULUS.iter().rev()).is_ge() {
+                let mut borrow = false;
+                for (limb, modulus) in limbs.iter_mut().zip(BLS_MODULUS) {
+                    let (difference, b1) = limb.overflowing_sub(modulus);
+                    let (difference, b2) = difference.overflowing_sub(borrow as u64);
+                    *limb = difference;
+                    borrow = b1 || b2;
+                }
+            }
+            let z = limbs_to_bytes(limbs);
+            assert!(proof
+                .verify_kzg_proof(
+                    KzgCommitment::from_bytes(&commitment.to_bytes()).unwrap(),
+                    z,
+                    y,
+                    &kzg_settings
+                )
+                .unwrap());
+
+            let mut other_hash = data_hash;
+            other_hash[0] ^= 1;
+            assert!(!proof
+                .verify_equivalence_proof(&commitment, &other_hash, &y, &kzg_settings)
+                .unwrap());
+            let mut other_y = y;
+            other_y[0] ^= 1;
+            assert!(!proof
+                .verify_equivalence_proof(&commitment, &data_hash, &other_y, &kzg_settings)
+                .unwrap());
+            assert_eq!(
+                proof
+                    .verify_equivalence_proof(
+                        &commitment,
+                        &data_hash,
+                        &BLS_MODULUS_BYTES,
+                        &kzg_settings
+                    )
+                    .err(),
+                Some(Error::CError {
+                    operation: "verify_equivalence_proof",
+                    argument: Some("y"),
+                    ret: C_KZG_RET::C_KZG_BADARGS,
+                })
+            );
+
+            let mut invalid = blob;
+            invalid[..BYTES_PER_FIELD_ELEMENT].copy_from_slice(&BLS_MODULUS_BYTES);
+            assert_eq!(
+                KzgProof::compute_equivalence_proof(
+                    &invalid,
+                    &commitment,
+                    &data_hash,
+                    &kzg_settings
+                )
+                .err(),
+                Some(non_canonical("compute_equivalence_proof", 0))
+            );
+        }
     }
 
     #[test]
-    fn test_end_to_end() {
-        let trusted_setup_file = if cfg!(feature = "minimal-spec") {
-            PathBuf::from("../../src/trusted_setup_4.txt")
-        } else {
-            PathBuf::from("../../src/trusted_setup.txt")
+    fn test_uncompressed_points() {
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rng);
+        let commitment = KzgCommitment::blob_to_kzg_commitment_ref(&blob, &kzg_settings).unwrap();
+        let proof = KzgProof::compute_aggregate_kzg_proof(&[blob], &kzg_settings).unwrap();
+
+        let uncompressed = commitment.to_uncompressed_bytes();
+        assert_eq!(
+            KzgCommitment::from_uncompressed_bytes(&uncompressed)
+                .unwrap()
+                .to_bytes(),
+            commitment.to_bytes()
+        );
+        assert_eq!(
+            KzgProof::from_uncompressed_bytes(&proof.to_uncompressed_bytes())
+                .unwrap()
+                .to_bytes(),
+            proof.to_bytes()
+        );
+        let mut compressed_flag = uncompressed;
+        compressed_flag[0] |= 0x80;
+        assert_eq!(
+            KzgCommitment::from_uncompressed_bytes(&compressed_flag).err(),
+            Some(Error::InvalidG1Point)
+        );
+
+        #[cfg(not(feature = "mock"))]
+        {
+            // Both encodings start with `x`, and differ in the flag bits.
+            let compressed = commitment.to_bytes();
+            assert_eq!(uncompressed[0], compressed[0] & 0x1f);
+            assert_eq!(uncompressed[1..48], compressed[1..]);
+
+            let mut infinity = [0; BYTES_PER_G1_POINT_UNCOMPRESSED];
+            infinity[0] = 0x40;
+            let point = KzgProof::from_uncompressed_bytes(&infinity).unwrap();
+            assert_eq!(point.to_bytes()[0], 0xc0);
+            assert_eq!(point.to_uncompressed_bytes(), infinity);
+
+            // x = 0, y = 2 is on y^2 = x^3 + 4 but outside of the G1 subgroup, and y = 3 is not
+            // on the curve.
+            for y in [2, 3] {
+                let mut point = [0; BYTES_PER_G1_POINT_UNCOMPRESSED];
+                point[BYTES_PER_G1_POINT_UNCOMPRESSED - 1] = y;
+                assert_eq!(
+                    KzgProof::from_uncompressed_bytes(&point).err(),
+                    Some(Error::InvalidG1Point)
+                );
+            }
+        }
+    }
+
+    #[cfg(all(feature = "blst-interop", not(feature = "mock")))]
+    #[test]
+    fn test_blst_interop() {
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rng);
+        let commitment = KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings).unwrap();
+
+        let affine = commitment.to_blst_p1_affine();
+        let roundtrip = KzgCommitment::from_blst_p1_affine(&affine).unwrap();
+        assert_eq!(roundtrip.to_bytes(), commitment.to_bytes());
+        assert_eq!(roundtrip.to_blst_p1_affine(), affine);
+
+        // A point on the curve but outside of the G1 subgroup: x = 0, y = 2 on y^2 = x^3 + 4.
+        let mut not_in_g1 = blst_p1_affine {
+            x: blst_fp { l: [0; 6] },
+            y: blst_fp { l: [0; 6] },
         };
-        test_simple(trusted_setup_file);
+        unsafe { bindings::blst_fp_from_uint64(&mut not_in_g1.y, [2, 0, 0, 0, 0, 0].as_ptr()) };
+        assert!(unsafe { bindings::blst_p1_affine_on_curve(&not_in_g1) });
+        assert_eq!(
+            KzgProof::from_blst_p1_affine(&not_in_g1).err(),
+            Some(Error::InvalidG1Point)
+        );
     }
 
-    #[cfg(not(feature = "minimal-spec"))]
+    #[cfg(all(feature = "ark", not(feature = "mock")))]
     #[test]
-    fn test_compute_agg_proof() {
-        let trusted_setup_file = PathBuf::from("../../src/trusted_setup.txt");
-        assert!(trusted_setup_file.exists());
-        let kzg_settings = KzgSettings::load_trusted_setup_file(trusted_setup_file).unwrap();
+    fn test_ark_conversions() {
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rng);
+        let commitment = KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings).unwrap();
+        let proof = KzgProof::compute_aggregate_kzg_proof(&[blob], &kzg_settings).unwrap();
 
-        let test_file = PathBuf::from("test_vectors/public_agg_proof.json");
-        let json_data: serde_json::Value =
-            serde_json::from_reader(std::fs::File::open(test_file).unwrap()).unwrap();
+        let point = ark_bls12_381::G1Affine::try_from(&commitment).unwrap();
+        assert_eq!(
+            KzgCommitment::try_from(&point).unwrap().to_bytes(),
+            commitment.to_bytes()
+        );
+        let point = ark_bls12_381::G1Affine::try_from(&proof).unwrap();
+        assert_eq!(
+            KzgProof::try_from(&point).unwrap().to_bytes(),
+            proof.to_bytes()
+        );
 
-        let tests = json_data.get("TestCases").unwrap().as_array().unwrap();
-        for test in tests.iter() {
-            let expected_proof = test.get("Proof").unwrap().as_str().unwrap();
+        let mut bytes = [0; BYTES_PER_FIELD_ELEMENT];
+        rng.fill(&mut bytes[..BYTES_PER_FIELD_ELEMENT - 1]);
+        let element = BlsFieldElement::bytes_to_bls_field(bytes).unwrap();
+        let fr = ark_bls12_381::Fr::try_from(&element).unwrap();
+        assert_eq!(
+            fr,
+            ark_serialize::CanonicalDeserialize::deserialize_compressed(&bytes[..]).unwrap()
+        );
+        assert_eq!(BlsFieldElement::try_from(&fr).unwrap().to_bytes(), bytes);
+    }
 
-            let expected_kzg_commitments = test
-                .get("Commitments")
-                .unwrap()
-                .as_array()
-                .unwrap()
+    #[cfg(all(feature = "subtle", not(feature = "mock")))]
+    #[test]
+    fn test_constant_time_eq() {
+        use subtle::ConstantTimeEq;
+
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rng);
+        let commitment = KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings).unwrap();
+        let proof = KzgProof::compute_aggregate_kzg_proof(&[blob], &kzg_settings).unwrap();
+
+        let same = KzgCommitment::from_bytes(&commitment.to_bytes()).unwrap();
+        assert!(bool::from(commitment.ct_eq(&same)));
+        let same = KzgProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(bool::from(proof.ct_eq(&same)));
+
+        let other = KzgProof::compute_aggregate_kzg_proof(&[], &kzg_settings).unwrap();
+        assert!(!bool::from(proof.ct_eq(&other)));
+    }
+
+    #[cfg(not(feature = "mock"))]
+    #[test]
+    fn test_kzg_backend() {
+        fn roundtrip<B: KzgBackend>(backend: &B, blobs: &[Blob]) -> bool
+        where
+            B::Error: core::fmt::Debug,
+        {
+            let commitments = blobs
                 .iter()
-                .map(|data| data.as_str().unwrap())
+                .map(|blob| backend.blob_to_kzg_commitment(blob).unwrap())
                 .collect::<Vec<_>>();
+            let proof = backend.compute_aggregate_kzg_proof(blobs).unwrap();
+            backend
+                .verify_aggregate_kzg_proof(blobs, &commitments, &proof)
+                .unwrap()
+        }
+
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blobs: Vec<Blob> = (0..2).map(|_| random_valid_blob(&mut rng)).collect();
+        assert!(roundtrip(&kzg_settings, &blobs));
+    }
+
+    #[cfg(not(feature = "mock"))]
+    #[test]
+    fn test_kzg_settings_raw() {
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rng);
+        let commitment = KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings).unwrap();
 
-            let blobs = test
-                .get("Polynomials")
+        let mut raw = kzg_settings.into_raw();
+        let kzg_settings = unsafe { KzgSettings::from_raw(&mut raw) };
+        assert_eq!(
+            KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings)
                 .unwrap()
-                .as_array()
+                .to_bytes(),
+            commitment.to_bytes()
+        );
+    }
+
+    /// A compressed point on the curve but outside the G1 subgroup, and the point itself. Most
+    /// points on the curve are not in the subgroup.
+    #[cfg(not(feature = "mock"))]
+    fn point_not_in_subgroup() -> ([u8; BYTES_PER_G1_POINT], g1_t) {
+        (1..=u8::MAX)
+            .find_map(|x| {
+                let mut bytes = [0; BYTES_PER_G1_POINT];
+                bytes[0] = 0x80;
+                bytes[BYTES_PER_G1_POINT - 1] = x;
+                let mut affine = MaybeUninit::<bindings::blst_p1_affine>::uninit();
+                let mut point = MaybeUninit::<g1_t>::uninit();
+                unsafe {
+                    if bindings::blst_p1_uncompress(affine.as_mut_ptr(), bytes.as_ptr())
+                        != bindings::BLST_ERROR_BLST_SUCCESS
+                    {
+                        return None;
+                    }
+                    bindings::blst_p1_from_affine(point.as_mut_ptr(), affine.as_ptr());
+                    let point = point.assume_init();
+                    (!bindings::blst_p1_in_g1(&point)).then_some((bytes, point))
+                }
+            })
+            .unwrap()
+    }
+
+    /// The error of the blob and field element operations for the non-canonical field element at
+    /// `position`, which the `constant-time` feature does not reveal.
+    fn non_canonical(operation: &'static str, position: usize) -> Error {
+        if cfg!(feature = "constant-time") {
+            Error::from(C_KZG_RET::C_KZG_BADARGS).in_operation(operation, Some("field element"))
+        } else {
+            Error::InvalidFieldElement { position }
+        }
+    }
+
+    #[cfg(not(feature = "mock"))]
+    #[test]
+    fn test_diagnose() {
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blobs: Vec<Blob> = (0..2).map(|_| random_valid_blob(&mut rng)).collect();
+        let commitments: Vec<KzgCommitment> = blobs
+            .iter()
+            .map(|blob| KzgCommitment::blob_to_kzg_commitment_ref(blob, &kzg_settings).unwrap())
+            .collect();
+        let proof = KzgProof::compute_aggregate_kzg_proof(&blobs, &kzg_settings)
+            .unwrap()
+            .to_bytes();
+        let commitments: Vec<[u8; BYTES_PER_COMMITMENT]> = commitments
+            .iter()
+            .map(|commitment| commitment.to_bytes())
+            .collect();
+        let diagnose = |proof: [u8; BYTES_PER_PROOF],
+                        blobs: &[Blob],
+                        commitments: &[[u8; BYTES_PER_COMMITMENT]]| {
+            KzgProof::diagnose_aggregate_kzg_proof(proof, blobs, commitments, &kzg_settings)
                 .unwrap()
-                .iter()
-                .map(|data| {
-                    let data = data.as_str().unwrap();
-                    let blob = hex::decode(data).unwrap();
-                    let mut blob_data = [0; BYTES_PER_BLOB];
-                    blob_data.copy_from_slice(&blob);
-                    blob_data
+        };
+
+        assert_eq!(
+            diagnose(proof, &blobs, &commitments),
+            VerificationDiagnosis::Valid
+        );
+        assert_eq!(
+            diagnose(proof, &[blobs[1], blobs[0]], &commitments),
+            VerificationDiagnosis::PairingCheckFailed
+        );
+
+        let mut invalid_blob = blobs[1];
+        invalid_blob[..BYTES_PER_FIELD_ELEMENT].copy_from_slice(&BLS_MODULUS_BYTES);
+        let diagnosis = KzgProof::diagnose_aggregate_kzg_proof(
+            proof,
+            &[blobs[0], invalid_blob],
+            &commitments,
+            &kzg_settings,
+        );
+        if cfg!(feature = "constant-time") {
+            assert_eq!(
+                diagnosis.err(),
+                Some(non_canonical("verify_aggregate_kzg_proof", 0))
+            );
+        } else {
+            assert_eq!(
+                diagnosis.unwrap(),
+                VerificationDiagnosis::InvalidFieldElement {
+                    position: FIELD_ELEMENTS_PER_BLOB
+                }
+            );
+        }
+
+        let (not_in_subgroup, _) = point_not_in_subgroup();
+        assert_eq!(
+            diagnose(proof, &blobs, &[commitments[0], not_in_subgroup]),
+            VerificationDiagnosis::InvalidCommitment { index: 1 }
+        );
+        // Without the compression flag the bytes do not decode at all.
+        let not_compressed = [0; BYTES_PER_G1_POINT];
+        assert_eq!(
+            diagnose(proof, &blobs, &[not_compressed, commitments[1]]),
+            VerificationDiagnosis::InvalidCommitment { index: 0 }
+        );
+        assert_eq!(
+            diagnose(not_in_subgroup, &blobs, &commitments),
+            VerificationDiagnosis::InvalidProof
+        );
+        assert_eq!(
+            KzgProof::diagnose_kzg_proof(
+                not_compressed,
+                commitments[0],
+                [0; 32],
+                [0; 32],
+                &kzg_settings
+            )
+            .unwrap(),
+            VerificationDiagnosis::InvalidProof
+        );
+        assert_eq!(
+            KzgProof::diagnose_kzg_proof(proof, not_in_subgroup, [0; 32], [0; 32], &kzg_settings)
+                .unwrap(),
+            VerificationDiagnosis::InvalidCommitment { index: 0 }
+        );
+        assert_eq!(
+            KzgProof::diagnose_kzg_proof(proof, commitments[0], [0; 32], [0xff; 32], &kzg_settings)
+                .unwrap(),
+            VerificationDiagnosis::InvalidFieldElement { position: 1 }
+        );
+    }
+
+    #[cfg(not(feature = "mock"))]
+    #[test]
+    fn test_is_well_formed() {
+        let kzg_settings = load_embedded_trusted_setup();
+        assert_eq!(kzg_settings.is_well_formed(), Ok(()));
+
+        // Check corrupted copies, which must not be dropped since they share the tables.
+        let mut raw = kzg_settings.into_raw();
+        let mut fs = unsafe { *raw.fs };
+        fs.max_width /= 2;
+        let corrupted = ManuallyDrop::new(KzgSettings(KZGSettings {
+            fs: &fs,
+            g1_values: core::ptr::null(),
+            g2_values: raw.g2_values,
+        }));
+        assert_eq!(
+            corrupted.is_well_formed(),
+            Err(vec![
+                SettingsProblem::NullPointer("g1_values"),
+                SettingsProblem::InvalidMaxWidth {
+                    expected: FIELD_ELEMENTS_PER_BLOB as u64,
+                    actual: FIELD_ELEMENTS_PER_BLOB as u64 / 2,
+                },
+            ])
+        );
+
+        let mut roots =
+            unsafe { core::slice::from_raw_parts(fs.roots_of_unity, FIELD_ELEMENTS_PER_BLOB) }
+                .to_vec();
+        roots.swap(0, 1);
+        let fs = FFTSettings {
+            max_width: FIELD_ELEMENTS_PER_BLOB as u64,
+            roots_of_unity: roots.as_ptr(),
+            ..fs
+        };
+        let corrupted = ManuallyDrop::new(KzgSettings(KZGSettings {
+            fs: &fs,
+            ..raw.clone()
+        }));
+        assert_eq!(
+            corrupted.is_well_formed(),
+            Err(vec![
+                SettingsProblem::InvalidRootOfUnity { index: 0 },
+                SettingsProblem::InvalidRootOfUnity { index: 1 },
+            ])
+        );
+
+        drop(unsafe { KzgSettings::from_raw(&mut raw) });
+    }
+
+    #[test]
+    fn test_aligned_blob() {
+        let mut rng = rand::thread_rng();
+        let aligned = AlignedBlob::new_boxed_aligned();
+        assert_eq!(aligned.as_ptr() as usize % 64, 0);
+        assert!(aligned.iter().all(|b| *b == 0));
+
+        let blob = random_valid_blob(&mut rng);
+        let aligned = [
+            *AlignedBlob::from_blob(&blob),
+            *AlignedBlob::from_blob(&blob),
+        ];
+        assert_eq!(AlignedBlob::as_blobs(&aligned), &[blob, blob]);
+    }
+
+    #[cfg(all(feature = "std", feature = "small-stack"))]
+    #[test]
+    fn test_small_stack() {
+        let mut rng = rand::thread_rng();
+        let trusted_setup_file = if cfg!(feature = "minimal-spec") {
+            PathBuf::from("../../src/trusted_setup_4.txt")
+        } else {
+            PathBuf::from("../../src/trusted_setup.txt")
+        };
+        let kzg_settings = KzgSettings::load_trusted_setup_file(trusted_setup_file).unwrap();
+
+        let blob = random_valid_blob(&mut rng);
+        let expected = KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings).unwrap();
+        let blob = AlignedBlob::from_blob(&blob);
+
+        // The spawned thread's stack is smaller than a single mainnet blob.
+        let commitment = std::thread::scope(|scope| {
+            std::thread::Builder::new()
+                .stack_size(64 * 1024)
+                .spawn_scoped(scope, || {
+                    KzgCommitment::blob_to_kzg_commitment_ref(&blob, &kzg_settings).unwrap()
                 })
-                .collect::<Vec<_>>();
+                .unwrap()
+                .join()
+                .unwrap()
+        });
+        assert_eq!(commitment.to_bytes(), expected.to_bytes());
+    }
+
+    #[test]
+    fn test_cancellable_batches() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
 
-            let proof = KzgProof::compute_aggregate_kzg_proof(&blobs, &kzg_settings).unwrap();
-            assert_eq!(proof.as_hex_string(), expected_proof);
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blobs: Vec<Blob> = (0..4).map(|_| random_valid_blob(&mut rng)).collect();
 
-            for (i, blob) in blobs.into_iter().enumerate() {
-                let commitment = KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings);
-                assert_eq!(
-                    commitment.as_hex_string().as_str(),
-                    expected_kzg_commitments[i]
-                );
-            }
+        let commitments =
+            KzgCommitment::blob_to_kzg_commitment_batch_cancellable(&blobs, &kzg_settings, &|| {
+                false
+            })
+            .unwrap();
+        assert_eq!(commitments.len(), blobs.len());
+
+        // Cancel after two blobs.
+        let calls = AtomicUsize::new(0);
+        let should_cancel = || calls.fetch_add(1, Ordering::SeqCst) >= 2;
+        assert_eq!(
+            KzgCommitment::blob_to_kzg_commitment_batch_cancellable(
+                &blobs,
+                &kzg_settings,
+                &should_cancel
+            )
+            .err(),
+            Some(Error::Cancelled)
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let proofs: Vec<KzgProof> = blobs
+            .iter()
+            .map(|blob| KzgProof::compute_aggregate_kzg_proof(&[*blob], &kzg_settings).unwrap())
+            .collect();
+        let blob_sets: Vec<&[Blob]> = blobs.chunks(1).collect();
+        let commitment_sets: Vec<&[KzgCommitment]> = commitments.chunks(1).collect();
+        assert_eq!(
+            KzgProof::verify_aggregate_kzg_proof_batch_cancellable(
+                &proofs,
+                &blob_sets,
+                &commitment_sets,
+                &kzg_settings,
+                &|| false
+            )
+            .unwrap(),
+            [true; 4]
+        );
+        let calls = AtomicUsize::new(0);
+        let should_cancel = || calls.fetch_add(1, Ordering::SeqCst) >= 2;
+        assert_eq!(
+            KzgProof::verify_aggregate_kzg_proof_batch_cancellable(
+                &proofs,
+                &blob_sets,
+                &commitment_sets,
+                &kzg_settings,
+                &should_cancel
+            )
+            .err(),
+            Some(Error::Cancelled)
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(matches!(
+            KzgProof::verify_aggregate_kzg_proof_batch_cancellable(
+                &proofs,
+                &blob_sets[1..],
+                &commitment_sets,
+                &kzg_settings,
+                &|| false
+            ),
+            Err(Error::MismatchedLengths { .. })
+        ));
+
+        #[cfg(feature = "parallel")]
+        assert_eq!(
+            KzgCommitment::blob_to_kzg_commitment_batch_parallel_cancellable(
+                &blobs,
+                &kzg_settings,
+                &|| true
+            )
+            .err(),
+            Some(Error::Cancelled)
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_blob_to_kzg_commitment_batch_parallel() {
+        let mut rng = rand::thread_rng();
+        let trusted_setup_file = if cfg!(feature = "minimal-spec") {
+            PathBuf::from("../../src/trusted_setup_4.txt")
+        } else {
+            PathBuf::from("../../src/trusted_setup.txt")
+        };
+        let kzg_settings = KzgSettings::load_trusted_setup_file(trusted_setup_file).unwrap();
+
+        let blobs: Vec<Blob> = (0..8).map(|_| random_valid_blob(&mut rng)).collect();
+        let commitments =
+            KzgCommitment::blob_to_kzg_commitment_batch_parallel(&blobs, &kzg_settings).unwrap();
+        for (blob, commitment) in blobs.into_iter().zip(commitments) {
+            assert_eq!(
+                KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings)
+                    .unwrap()
+                    .to_bytes(),
+                commitment.to_bytes()
+            );
         }
     }
 
-    #[cfg(not(feature = "minimal-spec"))]
+    #[cfg(all(feature = "std", not(feature = "mock")))]
     #[test]
-    fn test_verify_kzg_proof() {
-        let trusted_setup_file = PathBuf::from("../../src/trusted_setup.txt");
+    fn test_end_to_end() {
+        let trusted_setup_file = if cfg!(feature = "minimal-spec") {
+            PathBuf::from("../../src/trusted_setup_4.txt")
+        } else {
+            PathBuf::from("../../src/trusted_setup.txt")
+        };
         assert!(trusted_setup_file.exists());
-        let kzg_settings = KzgSettings::load_trusted_setup_file(trusted_setup_file).unwrap();
+        test_simple(KzgSettings::load_trusted_setup_file(trusted_setup_file).unwrap());
+    }
+
+    #[cfg(not(feature = "mock"))]
+    #[test]
+    fn test_end_to_end_embedded_setup() {
+        test_simple(load_embedded_trusted_setup());
+    }
 
-        let test_file = PathBuf::from("test_vectors/public_verify_kzg_proof.json");
-        let json_data: serde_json::Value =
-            serde_json::from_reader(std::fs::File::open(test_file).unwrap()).unwrap();
+    #[cfg(all(not(feature = "minimal-spec"), not(feature = "mock")))]
+    #[test]
+    fn test_compute_agg_proof() {
+        let report = spec_tests::run_vectors(
+            "public_agg_proof.json",
+            include_str!("../test_vectors/public_agg_proof.json"),
+            &load_embedded_trusted_setup(),
+        );
+        assert!(report.is_success(), "{:?}", report);
+    }
 
-        let tests = json_data.get("TestCases").unwrap().as_array().unwrap();
-        for test in tests.iter() {
-            let proof = test.get("Proof").unwrap().as_str().unwrap();
-            let kzg_proof = KzgProof::from_bytes(&hex::decode(proof).unwrap()).unwrap();
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_from_hex() {
+        let mut rng = rand::thread_rng();
+        let kzg_settings = load_embedded_trusted_setup();
+        let blob = random_valid_blob(&mut rng);
+        let commitment = KzgCommitment::blob_to_kzg_commitment(blob, &kzg_settings).unwrap();
+        let hex_str = commitment.as_hex_string();
 
-            let commitment = test.get("Commitment").unwrap().as_str().unwrap();
-            let kzg_commitment =
-                KzgCommitment::from_bytes(&hex::decode(commitment).unwrap()).unwrap();
+        for input in [hex_str.clone(), format!("0x{}", hex_str)] {
+            assert_eq!(
+                KzgCommitment::from_hex(&input).unwrap().to_bytes(),
+                commitment.to_bytes()
+            );
+        }
+        assert_eq!(
+            AlignedBlob::from_hex(&format!("0x{}", hex::encode(blob)))
+                .unwrap()
+                .0,
+            blob
+        );
+        let aligned = AlignedBlob::from_blob(&blob);
+        assert_eq!(aligned.as_hex_string(), hex::encode(blob));
+        let bytes = SCALE2_ROOT_OF_UNITY_BYTES[2];
+        let element = BlsFieldElement::from_hex(&format!("0x{}", hex::encode(bytes))).unwrap();
+        assert_eq!(element.to_bytes(), bytes);
+        assert_eq!(element.as_hex_string(), hex::encode(bytes));
 
-            let z = test.get("InputPoint").unwrap().as_str().unwrap();
-            let mut z_bytes = [0; BYTES_PER_FIELD_ELEMENT];
-            z_bytes.copy_from_slice(&hex::decode(z).unwrap());
+        for input in ["", "0x", "0", "0xzz", "\u{e9}"] {
+            assert_eq!(
+                KzgProof::from_hex(input).err(),
+                Some(Error::InvalidHexFormat)
+            );
+            assert_eq!(
+                AlignedBlob::from_hex(input).err(),
+                Some(Error::InvalidHexFormat)
+            );
+        }
+        assert_eq!(
+            KzgProof::from_hex(&hex_str[2..]).err(),
+            Some(Error::InvalidHexFormat)
+        );
+        assert_eq!(
+            AlignedBlob::from_hex(&hex_str).err(),
+            Some(Error::InvalidHexFormat)
+        );
+    }
 
-            let y = test.get("ClaimedValue").unwrap().as_str().unwrap();
-            let mut y_bytes = [0; BYTES_PER_FIELD_ELEMENT];
-            y_bytes.copy_from_slice(&hex::decode(y).unwrap());
+    /// The first commitment and the proof of `public_agg_proof.json`, as encoded by `as_hex_string`.
+    #[cfg(all(feature = "hex", not(feature = "mock")))]
+    const GOLDEN_COMMITMENT: &str = "a97456b8097baed6e90ce381d2b21c970a3f9ad4f6c92b1bb26337f919bd639dd43bd470839153db09115e2862051f33";
+    #[cfg(all(feature = "hex", not(feature = "mock")))]
+    const GOLDEN_PROOF: &str = "95ebeeea1e3993974ead1b3c909b6d3a01e8f862ee648c51e5afda300a09a910f9496cfae9a3d0d1e2c57f139649b26a";
+    /// The first three field elements of the first blob of that test case.
+    #[cfg(all(feature = "hex", not(feature = "mock"), not(feature = "minimal-spec")))]
+    const GOLDEN_BLOB_PREFIX: &str = "000000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000";
 
-            assert!(kzg_proof
-                .verify_kzg_proof(kzg_commitment, z_bytes, y_bytes, &kzg_settings)
-                .unwrap());
+    /// Pins the hex encodings byte for byte, so that changes to their prefix, casing or length are
+    /// caught here rather than by consumers parsing them.
+    #[cfg(all(feature = "hex", not(feature = "mock")))]
+    #[test]
+    fn test_hex_golden_vectors() {
+        let commitment = KzgCommitment::from_hex(GOLDEN_COMMITMENT).unwrap();
+        assert_eq!(commitment.as_hex_string(), GOLDEN_COMMITMENT);
+        let proof = KzgProof::from_hex(GOLDEN_PROOF).unwrap();
+        assert_eq!(proof.as_hex_string(), GOLDEN_PROOF);
+
+        // Prefixed and upper case input decodes to the same bytes, but is never produced.
+        let prefixed = format!("0x{}", GOLDEN_COMMITMENT.to_uppercase());
+        assert_eq!(
+            KzgCommitment::from_hex(&prefixed).unwrap().to_bytes(),
+            commitment.to_bytes()
+        );
+
+        #[cfg(not(feature = "minimal-spec"))]
+        {
+            let json_data: serde_json::Value =
+                serde_json::from_str(include_str!("../test_vectors/public_agg_proof.json"))
+                    .unwrap();
+            let blobs: Vec<Blob> = json_data["TestCases"][0]["Polynomials"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|blob_hex| AlignedBlob::from_hex(blob_hex.as_str().unwrap()).unwrap().0)
+                .collect();
+            assert_eq!(hex::encode(&blobs[0][..96]), GOLDEN_BLOB_PREFIX);
+            let kzg_settings = load_embedded_trusted_setup();
+            assert_eq!(
+                KzgCommitment::blob_to_kzg_commitment_ref(&blobs[0], &kzg_settings)
+                    .unwrap()
+                    .as_hex_string(),
+                GOLDEN_COMMITMENT
+            );
+            assert_eq!(
+                KzgProof::compute_aggregate_kzg_proof(&blobs, &kzg_settings)
+                    .unwrap()
+                    .as_hex_string(),
+                GOLDEN_PROOF
+            );
         }
     }
+
+    #[cfg(not(feature = "minimal-spec"))]
+    #[test]
+    fn test_verify_kzg_proof() {
+        let report = spec_tests::run_vectors(
+            "public_verify_kzg_proof.json",
+            include_str!("../test_vectors/public_verify_kzg_proof.json"),
+            &load_embedded_trusted_setup(),
+        );
+        assert_eq!(report.passed(), 2);
+        assert!(report.is_success(), "{:?}", report);
+
+        let report = spec_tests::run_vectors(
+            "public_verify_kzg_proof.json",
+            "{}",
+            &load_embedded_trusted_setup(),
+        );
+        assert_eq!(
+            report.failed().next().unwrap().result,
+            Err(String::from("missing TestCases"))
+        );
+    }
+
+    #[cfg(all(feature = "std", not(feature = "minimal-spec"), not(feature = "mock")))]
+    #[test]
+    fn test_spec_tests_run_all() {
+        let report = spec_tests::run_all(
+            std::path::Path::new("test_vectors"),
+            &load_embedded_trusted_setup(),
+        );
+        assert_eq!(report.vectors.len(), 3);
+        assert!(report.is_success(), "{:?}", report);
+    }
 }
@@ -0,0 +1,58 @@
+//! Operations on values that are already field elements, for integrators that embed the library in
+//! larger protocols and keep blobs and evaluations in that form between calls, instead of
+//! converting them from and to bytes at every call.
+//!
+//! Enabled by the `low-level` feature.
+
+use crate::bindings;
+use crate::metrics::{self, KzgOperation};
+use crate::{
+    Blob, BlsFieldElement, Error, KzgCommitment, KzgProof, KzgSettings, BYTES_PER_FIELD_ELEMENT,
+    C_KZG_RET,
+};
+use alloc::vec::Vec;
+use core::mem::MaybeUninit;
+
+impl KzgProof {
+    /// Like `verify_kzg_proof`, with the point `z` and the claimed value `y` as field elements,
+    /// which the library then does not need to check.
+    pub fn verify_kzg_proof_field_elements(
+        &self,
+        kzg_commitment: &KzgCommitment,
+        z: &BlsFieldElement,
+        y: &BlsFieldElement,
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, Error> {
+        let mut verified: MaybeUninit<bool> = MaybeUninit::uninit();
+        metrics::observe_verification(KzgOperation::VerifyKzgProof, || unsafe {
+            let res = C_KZG_RET::from_raw(bindings::verify_kzg_proof_field_elements(
+                verified.as_mut_ptr(),
+                &kzg_commitment.0,
+                &z.0,
+                &y.0,
+                &self.0,
+                &kzg_settings.0,
+            ));
+            match res {
+                C_KZG_RET::C_KZG_OK => Ok(verified.assume_init()),
+                _ => Err(Error::from(res).in_operation("verify_kzg_proof_field_elements", None)),
+            }
+        })
+    }
+}
+
+/// The field elements of `blob` in order, which are the evaluations of its polynomial at the roots
+/// of unity in bit-reversal order.
+///
+/// Fails with `Error::InvalidFieldElement` at the first field element that is not canonical.
+pub fn blob_to_polynomial(blob: &Blob) -> Result<Vec<BlsFieldElement>, Error> {
+    blob.chunks_exact(BYTES_PER_FIELD_ELEMENT)
+        .enumerate()
+        .map(|(position, bytes)| {
+            let mut field_element = [0; BYTES_PER_FIELD_ELEMENT];
+            field_element.copy_from_slice(bytes);
+            BlsFieldElement::bytes_to_bls_field(field_element)
+                .map_err(|_| Error::InvalidFieldElement { position })
+        })
+        .collect()
+}
@@ -0,0 +1,147 @@
+//! Hooks for reporting operation counts, latencies and verification verdicts.
+//!
+//! Register an observer once at startup with `set_metrics`. Until then, and without the `std`
+//! feature, operations are not timed and nothing is reported.
+
+use core::time::Duration;
+
+/// The operations reported to a `KzgMetrics` observer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KzgOperation {
+    BlobToKzgCommitment,
+    ComputeAggregateKzgProof,
+    VerifyAggregateKzgProof,
+    VerifyKzgProof,
+    VerifyEquivalenceProof,
+    VerifyDegreeBoundProof,
+}
+
+impl KzgOperation {
+    /// The snake case name of the operation, e.g. for metric labels.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BlobToKzgCommitment => "blob_to_kzg_commitment",
+            Self::ComputeAggregateKzgProof => "compute_aggregate_kzg_proof",
+            Self::VerifyAggregateKzgProof => "verify_aggregate_kzg_proof",
+            Self::VerifyKzgProof => "verify_kzg_proof",
+            Self::VerifyEquivalenceProof => "verify_equivalence_proof",
+            Self::VerifyDegreeBoundProof => "verify_degree_bound_proof",
+        }
+    }
+}
+
+/// Receives a callback after every operation. Both methods default to doing nothing.
+pub trait KzgMetrics: Send + Sync {
+    /// Called after every operation, including failed ones, with the time it took.
+    fn on_operation(&self, _operation: KzgOperation, _duration: Duration) {}
+
+    /// Called after every verification that ran to completion, with whether the proof was valid.
+    fn on_verification(&self, _operation: KzgOperation, _valid: bool) {}
+}
+
+/// A `KzgMetrics` observer that ignores every callback.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopMetrics;
+
+impl KzgMetrics for NoopMetrics {}
+
+#[cfg(feature = "std")]
+static METRICS: std::sync::OnceLock<&'static dyn KzgMetrics> = std::sync::OnceLock::new();
+
+/// Registers the observer for all operations of this crate. It can only be set once, later calls
+/// return the rejected observer.
+#[cfg(feature = "std")]
+pub fn set_metrics(metrics: &'static dyn KzgMetrics) -> Result<(), &'static dyn KzgMetrics> {
+    METRICS.set(metrics)
+}
+
+/// Runs `f`, reporting its duration if an observer is registered.
+pub(crate) fn observe<T>(operation: KzgOperation, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "std")]
+    if let Some(metrics) = METRICS.get() {
+        let start = std::time::Instant::now();
+        let result = f();
+        metrics.on_operation(operation, start.elapsed());
+        return result;
+    }
+    let _ = operation;
+    f()
+}
+
+/// Like `observe`, but also reports the verdict of a verification.
+pub(crate) fn observe_verification<E>(
+    operation: KzgOperation,
+    f: impl FnOnce() -> Result<bool, E>,
+) -> Result<bool, E> {
+    let result = observe(operation, f);
+    #[cfg(feature = "std")]
+    if let (Some(metrics), Ok(valid)) = (METRICS.get(), &result) {
+        metrics.on_verification(operation, *valid);
+    }
+    result
+}
+
+/// A `KzgMetrics` observer that exports to a `prometheus` registry:
+///
+/// - `kzg_operations_total{operation}`: the number of operations,
+/// - `kzg_operation_duration_seconds{operation}`: a histogram of their latency,
+/// - `kzg_verifications_total{operation, valid}`: the number of verdicts.
+#[cfg(feature = "prometheus")]
+pub struct PrometheusMetrics {
+    operations: prometheus::IntCounterVec,
+    durations: prometheus::HistogramVec,
+    verifications: prometheus::IntCounterVec,
+}
+
+#[cfg(feature = "prometheus")]
+impl PrometheusMetrics {
+    /// Creates the metrics and registers them with `registry`.
+    pub fn new(registry: &prometheus::Registry) -> prometheus::Result<Self> {
+        use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts};
+
+        let operations = IntCounterVec::new(
+            Opts::new("kzg_operations_total", "Number of KZG operations"),
+            &["operation"],
+        )?;
+        let durations = HistogramVec::new(
+            HistogramOpts::new(
+                "kzg_operation_duration_seconds",
+                "Duration of KZG operations",
+            ),
+            &["operation"],
+        )?;
+        let verifications = IntCounterVec::new(
+            Opts::new(
+                "kzg_verifications_total",
+                "Number of KZG verification verdicts",
+            ),
+            &["operation", "valid"],
+        )?;
+        registry.register(Box::new(operations.clone()))?;
+        registry.register(Box::new(durations.clone()))?;
+        registry.register(Box::new(verifications.clone()))?;
+        Ok(Self {
+            operations,
+            durations,
+            verifications,
+        })
+    }
+}
+
+#[cfg(feature = "prometheus")]
+impl KzgMetrics for PrometheusMetrics {
+    fn on_operation(&self, operation: KzgOperation, duration: Duration) {
+        let labels = [operation.as_str()];
+        self.operations.with_label_values(&labels).inc();
+        self.durations
+            .with_label_values(&labels)
+            .observe(duration.as_secs_f64());
+    }
+
+    fn on_verification(&self, operation: KzgOperation, valid: bool) {
+        let valid = if valid { "true" } else { "false" };
+        self.verifications
+            .with_label_values(&[operation.as_str(), valid])
+            .inc();
+    }
+}
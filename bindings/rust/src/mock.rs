@@ -0,0 +1,501 @@
+//! Pure-Rust stand-ins for the C library, enabled with the `mock` feature.
+//!
+//! They have the same signatures as the `extern "C"` declarations in `bindings`, so the safe API is
+//! unchanged, but nothing is linked: commitments and proofs are deterministic digests of their
+//! inputs and every proof verifies. This is only meant for downstream unit tests and Miri runs.
+
+#[cfg(all(feature = "std", not(windows)))]
+use libc::FILE;
+
+use crate::bindings::blst_scalar;
+use crate::bindings::{
+    blst_fp, blst_fr, blst_p1, blst_p1_affine, c_kzg_calloc_fn, c_kzg_error_fn, c_kzg_free_fn,
+    c_kzg_malloc_fn, g1_t, BLSFieldElement, KZGCommitment, KZGProof, KZGSettings, BLST_ERROR,
+    BLST_ERROR_BLST_SUCCESS, BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT, C_KZG_RET,
+    C_KZG_RET_C_KZG_BADARGS, C_KZG_RET_C_KZG_OK, FIELD_ELEMENTS_PER_BLOB,
+};
+#[cfg(any(test, feature = "test-utils"))]
+use crate::bindings::{blst_fp2, blst_p2};
+
+/// A non-cryptographic 48 byte digest (FNV-1a, one lane per 8 bytes).
+fn digest(data: &[u8]) -> [u8; 48] {
+    let mut out = [0; 48];
+    for (lane, chunk) in out.chunks_mut(8).enumerate() {
+        let mut h = 0xcbf29ce484222325u64 ^ lane as u64;
+        for byte in data {
+            h ^= *byte as u64;
+            h = h.wrapping_mul(0x100000001b3);
+        }
+        chunk.copy_from_slice(&h.to_le_bytes());
+    }
+    out
+}
+
+/// Stores 48 bytes in the `x` coordinate of a point, so that they round-trip through
+/// `bytes_from_g1`.
+fn g1_from_bytes(bytes: &[u8; 48]) -> g1_t {
+    let mut x = blst_fp { l: [0; 6] };
+    for (limb, chunk) in x.l.iter_mut().zip(bytes.chunks(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    let zero = blst_fp { l: [0; 6] };
+    blst_p1 {
+        x,
+        y: zero,
+        z: zero,
+    }
+}
+
+#[cfg(feature = "blst-interop")]
+pub unsafe fn blst_p1_to_affine(out: *mut blst_p1_affine, in_: *const blst_p1) {
+    out.write(blst_p1_affine {
+        x: (*in_).x,
+        y: (*in_).y,
+    });
+}
+
+pub unsafe fn blst_p1_from_affine(out: *mut blst_p1, in_: *const blst_p1_affine) {
+    out.write(blst_p1 {
+        x: (*in_).x,
+        y: (*in_).y,
+        z: blst_fp { l: [0; 6] },
+    });
+}
+
+pub unsafe fn blst_p1_affine_on_curve(_p: *const blst_p1_affine) -> bool {
+    true
+}
+
+pub unsafe fn blst_p1_affine_in_g1(_p: *const blst_p1_affine) -> bool {
+    true
+}
+
+/// Stores the `x` limbs in the second half, so that the flag bits of the first byte stay clear.
+pub unsafe fn blst_p1_serialize(out: *mut u8, in_: *const blst_p1) {
+    out.write_bytes(0, 48);
+    for (i, limb) in (*in_).x.l.iter().enumerate() {
+        out.add(48 + 8 * i)
+            .copy_from_nonoverlapping(limb.to_le_bytes().as_ptr(), 8);
+    }
+}
+
+pub unsafe fn blst_p1_deserialize(out: *mut blst_p1_affine, in_: *const u8) -> BLST_ERROR {
+    let mut x = [0; 48];
+    x.copy_from_slice(core::slice::from_raw_parts(in_.add(48), 48));
+    let point = g1_from_bytes(&x);
+    out.write(blst_p1_affine {
+        x: point.x,
+        y: point.y,
+    });
+    BLST_ERROR_BLST_SUCCESS
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+static GENERATOR_P1: blst_p1 = blst_p1 {
+    x: blst_fp {
+        l: [1, 0, 0, 0, 0, 0],
+    },
+    y: blst_fp { l: [0; 6] },
+    z: blst_fp { l: [0; 6] },
+};
+
+#[cfg(any(test, feature = "test-utils"))]
+static GENERATOR_P2: blst_p2 = blst_p2 {
+    x: blst_fp2 {
+        fp: [
+            blst_fp {
+                l: [1, 0, 0, 0, 0, 0],
+            },
+            blst_fp { l: [0; 6] },
+        ],
+    },
+    y: blst_fp2 {
+        fp: [blst_fp { l: [0; 6] }; 2],
+    },
+    z: blst_fp2 {
+        fp: [blst_fp { l: [0; 6] }; 2],
+    },
+};
+
+#[cfg(any(test, feature = "test-utils"))]
+pub unsafe fn blst_p1_generator() -> *const blst_p1 {
+    &GENERATOR_P1
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+pub unsafe fn blst_p2_generator() -> *const blst_p2 {
+    &GENERATOR_P2
+}
+
+/// A digest of the point and the scalar.
+#[cfg(any(test, feature = "test-utils", feature = "hiding"))]
+pub unsafe fn blst_p1_mult(out: *mut blst_p1, p: *const blst_p1, scalar: *const u8, nbits: usize) {
+    let mut data = [0; 48 + 32];
+    bytes_from_g1(data.as_mut_ptr(), p);
+    data[48..48 + nbits.div_ceil(8)]
+        .copy_from_slice(core::slice::from_raw_parts(scalar, nbits.div_ceil(8)));
+    out.write(g1_from_bytes(&digest(&data)));
+}
+
+/// The mock ignores the points of the trusted setup, so the point is left unchanged.
+#[cfg(any(test, feature = "test-utils"))]
+pub unsafe fn blst_p2_mult(
+    out: *mut blst_p2,
+    p: *const blst_p2,
+    _scalar: *const u8,
+    _nbits: usize,
+) {
+    out.write(*p);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+pub unsafe fn blst_p1_compress(out: *mut u8, in_: *const blst_p1) {
+    bytes_from_g1(out, in_);
+}
+
+#[cfg(any(test, feature = "test-utils", feature = "cache"))]
+pub unsafe fn blst_p2_compress(out: *mut u8, in_: *const blst_p2) {
+    for (i, fp) in (*in_).x.fp.iter().enumerate() {
+        for (j, limb) in fp.l.iter().enumerate() {
+            out.add(i * 48 + j * 8)
+                .copy_from_nonoverlapping(limb.to_le_bytes().as_ptr(), 8);
+        }
+    }
+}
+
+#[cfg(feature = "hiding")]
+pub unsafe fn blst_hash_to_g1(
+    out: *mut blst_p1,
+    _msg: *const u8,
+    _msg_len: usize,
+    dst: *const u8,
+    dst_len: usize,
+    _aug: *const u8,
+    _aug_len: usize,
+) {
+    out.write(g1_from_bytes(&digest(core::slice::from_raw_parts(
+        dst, dst_len,
+    ))));
+}
+
+/// Points are added by XOR-ing their encodings, so that every point is its own negation.
+#[cfg(feature = "hiding")]
+pub unsafe fn blst_p1_add_or_double(out: *mut blst_p1, a: *const blst_p1, b: *const blst_p1) {
+    let mut x = (*a).x;
+    for (limb, other) in x.l.iter_mut().zip((*b).x.l) {
+        *limb ^= other;
+    }
+    out.write(blst_p1 {
+        x,
+        y: blst_fp { l: [0; 6] },
+        z: blst_fp { l: [0; 6] },
+    });
+}
+
+#[cfg(feature = "hiding")]
+pub unsafe fn blst_p1_cneg(_p: *mut blst_p1, _cbit: bool) {}
+
+pub unsafe fn blst_scalar_from_fr(ret: *mut blst_scalar, a: *const blst_fr) {
+    let mut b = [0; 32];
+    for (chunk, limb) in b.chunks_mut(8).zip((*a).l.iter()) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    ret.write(blst_scalar { b });
+}
+
+pub unsafe fn blst_lendian_from_scalar(out: *mut u8, a: *const blst_scalar) {
+    out.copy_from_nonoverlapping((*a).b.as_ptr(), 32);
+}
+
+pub unsafe fn c_kzg_set_allocator(
+    _malloc_impl: c_kzg_malloc_fn,
+    _calloc_impl: c_kzg_calloc_fn,
+    _free_impl: c_kzg_free_fn,
+) {
+}
+
+/// The mock never fails, so there is nothing to report.
+pub unsafe fn c_kzg_set_error_callback(_callback: c_kzg_error_fn) {}
+
+pub unsafe fn bytes_to_g1(out: *mut g1_t, in_: *const u8) -> C_KZG_RET {
+    out.write(g1_from_bytes(&*(in_ as *const [u8; 48])));
+    C_KZG_RET_C_KZG_OK
+}
+
+pub unsafe fn bytes_from_g1(out: *mut u8, in_: *const g1_t) {
+    for (i, limb) in (*in_).x.l.iter().enumerate() {
+        out.add(i * 8)
+            .copy_from_nonoverlapping(limb.to_le_bytes().as_ptr(), 8);
+    }
+}
+
+pub unsafe fn bytes_to_bls_field(out: *mut BLSFieldElement, in_: *const u8) -> C_KZG_RET {
+    let mut fr = blst_fr { l: [0; 4] };
+    for (i, limb) in fr.l.iter_mut().enumerate() {
+        *limb = u64::from_le_bytes(*(in_.add(i * 8) as *const [u8; 8]));
+    }
+    out.write(fr);
+    C_KZG_RET_C_KZG_OK
+}
+
+#[cfg(all(feature = "std", not(windows)))]
+pub unsafe fn load_trusted_setup_file(out: *mut KZGSettings, in_: *mut FILE) -> C_KZG_RET {
+    if in_.is_null() {
+        return C_KZG_RET_C_KZG_BADARGS;
+    }
+    load_trusted_setup(out, core::ptr::null(), 0, core::ptr::null(), 0)
+}
+
+pub unsafe fn load_trusted_setup(
+    out: *mut KZGSettings,
+    _g1_bytes: *const u8,
+    _n1: usize,
+    _g2_bytes: *const u8,
+    _n2: usize,
+) -> C_KZG_RET {
+    out.write(KZGSettings {
+        fs: core::ptr::null(),
+        g1_values: core::ptr::null(),
+        g2_values: core::ptr::null(),
+    });
+    C_KZG_RET_C_KZG_OK
+}
+
+/// The mock settings hold no points, so zeros stand in for them.
+#[cfg(feature = "std")]
+pub unsafe fn save_trusted_setup_lagrange(out: *mut u8, _s: *const KZGSettings) -> C_KZG_RET {
+    out.write_bytes(0, (FIELD_ELEMENTS_PER_BLOB + 65) * 96 + 32);
+    C_KZG_RET_C_KZG_OK
+}
+
+pub unsafe fn load_trusted_setup_lagrange(out: *mut KZGSettings, _bytes: *const u8) -> C_KZG_RET {
+    load_trusted_setup(out, core::ptr::null(), 0, core::ptr::null(), 0)
+}
+
+/// Unlike the C library, the mock still computes commitments and proofs with these settings.
+pub unsafe fn load_trusted_setup_verify_only(
+    out: *mut KZGSettings,
+    _g2_bytes: *const u8,
+    _n2: usize,
+) -> C_KZG_RET {
+    load_trusted_setup(out, core::ptr::null(), 0, core::ptr::null(), 0)
+}
+
+pub unsafe fn free_trusted_setup(_s: *mut KZGSettings) {}
+
+pub unsafe fn compute_aggregate_kzg_proof(
+    out: *mut KZGProof,
+    blobs: *const u8,
+    n: usize,
+    _s: *const KZGSettings,
+) -> C_KZG_RET {
+    let bytes = if n == 0 {
+        &[]
+    } else {
+        core::slice::from_raw_parts(blobs, n * BYTES_PER_BLOB)
+    };
+    out.write(g1_from_bytes(&digest(bytes)));
+    C_KZG_RET_C_KZG_OK
+}
+
+pub unsafe fn verify_aggregate_kzg_proof(
+    out: *mut bool,
+    _blobs: *const u8,
+    _expected_kzg_commitments: *const KZGCommitment,
+    _n: usize,
+    _kzg_aggregated_proof: *const KZGProof,
+    _s: *const KZGSettings,
+) -> C_KZG_RET {
+    out.write(true);
+    C_KZG_RET_C_KZG_OK
+}
+
+pub unsafe fn blob_to_kzg_commitment(
+    out: *mut KZGCommitment,
+    blob: *const u8,
+    _s: *const KZGSettings,
+) -> C_KZG_RET {
+    let bytes = core::slice::from_raw_parts(blob, BYTES_PER_BLOB);
+    out.write(g1_from_bytes(&digest(bytes)));
+    C_KZG_RET_C_KZG_OK
+}
+
+/// The digest of the padded blob, as `blob_to_kzg_commitment` would return.
+pub unsafe fn blob_prefix_to_kzg_commitment(
+    out: *mut KZGCommitment,
+    field_elements: *const u8,
+    n: usize,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    if n > FIELD_ELEMENTS_PER_BLOB {
+        return C_KZG_RET_C_KZG_BADARGS;
+    }
+    let mut blob = alloc::vec![0; BYTES_PER_BLOB];
+    blob[..n * BYTES_PER_FIELD_ELEMENT].copy_from_slice(core::slice::from_raw_parts(
+        field_elements,
+        n * BYTES_PER_FIELD_ELEMENT,
+    ));
+    blob_to_kzg_commitment(out, blob.as_ptr(), s)
+}
+
+/// The digest of the coefficients.
+pub unsafe fn commit_coefficients(
+    out: *mut KZGCommitment,
+    coefficients: *const u8,
+    n: usize,
+    _s: *const KZGSettings,
+) -> C_KZG_RET {
+    if n > FIELD_ELEMENTS_PER_BLOB {
+        return C_KZG_RET_C_KZG_BADARGS;
+    }
+    let bytes = core::slice::from_raw_parts(coefficients, n * BYTES_PER_FIELD_ELEMENT);
+    out.write(g1_from_bytes(&digest(bytes)));
+    C_KZG_RET_C_KZG_OK
+}
+
+/// A digest of the coefficients and point as the proof, and a canonical part of it as `y`.
+pub unsafe fn compute_proof_monomial(
+    proof_out: *mut KZGProof,
+    y_out: *mut u8,
+    coefficients: *const u8,
+    n: usize,
+    z: *const u8,
+    _s: *const KZGSettings,
+) -> C_KZG_RET {
+    if n > FIELD_ELEMENTS_PER_BLOB {
+        return C_KZG_RET_C_KZG_BADARGS;
+    }
+    let mut data = alloc::vec::Vec::from(core::slice::from_raw_parts(
+        coefficients,
+        n * BYTES_PER_FIELD_ELEMENT,
+    ));
+    data.extend_from_slice(core::slice::from_raw_parts(z, BYTES_PER_FIELD_ELEMENT));
+    let digest = digest(&data);
+    proof_out.write(g1_from_bytes(&digest));
+    y_out.write_bytes(0, BYTES_PER_FIELD_ELEMENT);
+    y_out.copy_from_nonoverlapping(digest.as_ptr(), BYTES_PER_FIELD_ELEMENT - 1);
+    C_KZG_RET_C_KZG_OK
+}
+
+/// The digest of the points and scalars.
+pub unsafe fn g1_linear_combination(
+    out: *mut g1_t,
+    points: *const g1_t,
+    scalars: *const BLSFieldElement,
+    n: usize,
+) -> C_KZG_RET {
+    let mut data = alloc::vec::Vec::new();
+    for i in 0..n {
+        for limb in (*points.add(i)).x.l {
+            data.extend_from_slice(&limb.to_le_bytes());
+        }
+        for limb in (*scalars.add(i)).l {
+            data.extend_from_slice(&limb.to_le_bytes());
+        }
+    }
+    out.write(g1_from_bytes(&digest(&data)));
+    C_KZG_RET_C_KZG_OK
+}
+
+pub unsafe fn verify_kzg_proof(
+    out: *mut bool,
+    _polynomial_kzg: *const KZGCommitment,
+    _z: *const u8,
+    _y: *const u8,
+    _kzg_proof: *const KZGProof,
+    _s: *const KZGSettings,
+) -> C_KZG_RET {
+    out.write(true);
+    C_KZG_RET_C_KZG_OK
+}
+
+#[cfg(feature = "low-level")]
+pub unsafe fn verify_kzg_proof_field_elements(
+    out: *mut bool,
+    _commitment: *const KZGCommitment,
+    _z: *const BLSFieldElement,
+    _y: *const BLSFieldElement,
+    _kzg_proof: *const KZGProof,
+    _s: *const KZGSettings,
+) -> C_KZG_RET {
+    out.write(true);
+    C_KZG_RET_C_KZG_OK
+}
+
+/// A digest of the blob, commitment and hash as the proof, and a canonical part of it as `y`.
+pub unsafe fn compute_equivalence_proof(
+    proof_out: *mut KZGProof,
+    y_out: *mut u8,
+    blob: *const u8,
+    commitment: *const KZGCommitment,
+    data_hash: *const u8,
+    _s: *const KZGSettings,
+) -> C_KZG_RET {
+    let mut data = alloc::vec::Vec::from(core::slice::from_raw_parts(blob, BYTES_PER_BLOB));
+    for limb in (*commitment).x.l {
+        data.extend_from_slice(&limb.to_le_bytes());
+    }
+    data.extend_from_slice(core::slice::from_raw_parts(data_hash, 32));
+    let digest = digest(&data);
+    proof_out.write(g1_from_bytes(&digest));
+    y_out.write_bytes(0, BYTES_PER_FIELD_ELEMENT);
+    y_out.copy_from_nonoverlapping(digest.as_ptr(), BYTES_PER_FIELD_ELEMENT - 1);
+    C_KZG_RET_C_KZG_OK
+}
+
+pub unsafe fn verify_equivalence_proof(
+    out: *mut bool,
+    _commitment: *const KZGCommitment,
+    _data_hash: *const u8,
+    _y: *const u8,
+    _proof: *const KZGProof,
+    _s: *const KZGSettings,
+) -> C_KZG_RET {
+    out.write(true);
+    C_KZG_RET_C_KZG_OK
+}
+
+/// One point per step of 64, as in the C library.
+pub unsafe fn degree_bound_proof_length(degree_bound: usize) -> usize {
+    FIELD_ELEMENTS_PER_BLOB
+        .saturating_sub(degree_bound)
+        .div_ceil(64)
+}
+
+/// Digests of the coefficients, the bound and the index of each point.
+pub unsafe fn compute_degree_bound_proof(
+    proofs_out: *mut KZGProof,
+    coefficients: *const u8,
+    n: usize,
+    degree_bound: usize,
+    _s: *const KZGSettings,
+) -> C_KZG_RET {
+    if degree_bound > FIELD_ELEMENTS_PER_BLOB || n > degree_bound {
+        return C_KZG_RET_C_KZG_BADARGS;
+    }
+    let mut data = alloc::vec::Vec::from(core::slice::from_raw_parts(
+        coefficients,
+        n * BYTES_PER_FIELD_ELEMENT,
+    ));
+    data.extend_from_slice(&degree_bound.to_le_bytes());
+    for i in 0..degree_bound_proof_length(degree_bound) {
+        data.push(i as u8);
+        proofs_out.add(i).write(g1_from_bytes(&digest(&data)));
+    }
+    C_KZG_RET_C_KZG_OK
+}
+
+pub unsafe fn verify_degree_bound_proof(
+    out: *mut bool,
+    _commitment: *const KZGCommitment,
+    degree_bound: usize,
+    _proofs: *const KZGProof,
+    n: usize,
+    _s: *const KZGSettings,
+) -> C_KZG_RET {
+    if degree_bound > FIELD_ELEMENTS_PER_BLOB || n != degree_bound_proof_length(degree_bound) {
+        return C_KZG_RET_C_KZG_BADARGS;
+    }
+    out.write(true);
+    C_KZG_RET_C_KZG_OK
+}
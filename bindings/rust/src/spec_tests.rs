@@ -0,0 +1,212 @@
+//! Runner for the JSON test vectors in the format of `test_vectors/`, so that client teams can check
+//! this crate against their own copy of the vectors.
+//!
+//! Each file holds a `TestCases` array and is dispatched on its name:
+//!
+//! - `public_agg_proof.json`: `Polynomials` commit to `Commitments` and aggregate to `Proof`,
+//! - `public_verify_kzg_proof.json`: `Proof` shows that `Commitment` evaluates to `ClaimedValue` at
+//!   `InputPoint`.
+//!
+//! Enabled by the `spec-tests` feature.
+
+use crate::{Blob, KzgCommitment, KzgProof, KzgSettings, BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde_json::Value;
+
+/// The outcome of one test case, or of a whole file if it could not be read or parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VectorResult {
+    /// The file name, followed by `#` and the index of the test case unless the whole file failed.
+    pub name: String,
+    /// Why the test case failed.
+    pub result: Result<(), String>,
+}
+
+/// The outcomes of all test cases that were run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub vectors: Vec<VectorResult>,
+}
+
+impl Report {
+    /// The number of test cases that passed.
+    pub fn passed(&self) -> usize {
+        self.vectors
+            .iter()
+            .filter(|vector| vector.result.is_ok())
+            .count()
+    }
+
+    /// The test cases and files that failed.
+    pub fn failed(&self) -> impl Iterator<Item = &VectorResult> {
+        self.vectors.iter().filter(|vector| vector.result.is_err())
+    }
+
+    /// Whether at least one test case ran and none failed.
+    pub fn is_success(&self) -> bool {
+        !self.vectors.is_empty() && self.failed().next().is_none()
+    }
+}
+
+type Runner = fn(&Value, &KzgSettings) -> Result<(), String>;
+
+fn runner(file_name: &str) -> Option<Runner> {
+    match file_name {
+        "public_agg_proof.json" => Some(run_agg_proof),
+        "public_verify_kzg_proof.json" => Some(run_verify_kzg_proof),
+        _ => None,
+    }
+}
+
+/// Runs every known vector file in `dir`. Other files are ignored.
+#[cfg(feature = "std")]
+pub fn run_all(dir: &std::path::Path, settings: &KzgSettings) -> Report {
+    let mut report = Report::default();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) => {
+            report.vectors.push(VectorResult {
+                name: dir.display().to_string(),
+                result: Err(error.to_string()),
+            });
+            return report;
+        }
+    };
+    let mut file_names: Vec<String> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|file_name| runner(file_name).is_some())
+        .collect();
+    file_names.sort();
+    for file_name in file_names {
+        match std::fs::read_to_string(dir.join(&file_name)) {
+            Ok(json) => report
+                .vectors
+                .extend(run_vectors(&file_name, &json, settings).vectors),
+            Err(error) => report.vectors.push(VectorResult {
+                name: file_name,
+                result: Err(error.to_string()),
+            }),
+        }
+    }
+    report
+}
+
+/// Runs the test cases of one vector file, given its name and contents.
+pub fn run_vectors(file_name: &str, json: &str, settings: &KzgSettings) -> Report {
+    let file_error = |reason: String| Report {
+        vectors: alloc::vec![VectorResult {
+            name: file_name.to_string(),
+            result: Err(reason),
+        }],
+    };
+    let Some(runner) = runner(file_name) else {
+        return file_error(String::from("unknown vector file"));
+    };
+    let test_cases = match serde_json::from_str::<Value>(json) {
+        Ok(value) => match value.get("TestCases").and_then(Value::as_array) {
+            Some(test_cases) => test_cases.clone(),
+            None => return file_error(String::from("missing TestCases")),
+        },
+        Err(error) => return file_error(error.to_string()),
+    };
+    Report {
+        vectors: test_cases
+            .iter()
+            .enumerate()
+            .map(|(index, test_case)| VectorResult {
+                name: format!("{}#{}", file_name, index),
+                result: runner(test_case, settings),
+            })
+            .collect(),
+    }
+}
+
+fn hex_field(test_case: &Value, key: &str) -> Result<Vec<u8>, String> {
+    let hex_str = test_case
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("missing {}", key))?;
+    hex::decode(hex_str).map_err(|error| format!("{}: {}", key, error))
+}
+
+fn hex_fields(test_case: &Value, key: &str) -> Result<Vec<Vec<u8>>, String> {
+    test_case
+        .get(key)
+        .and_then(Value::as_array)
+        .ok_or_else(|| format!("missing {}", key))?
+        .iter()
+        .map(|hex_str| {
+            let hex_str = hex_str
+                .as_str()
+                .ok_or_else(|| format!("{}: not a string", key))?;
+            hex::decode(hex_str).map_err(|error| format!("{}: {}", key, error))
+        })
+        .collect()
+}
+
+fn to_array<const N: usize>(key: &str, bytes: &[u8]) -> Result<[u8; N], String> {
+    bytes
+        .try_into()
+        .map_err(|_| format!("{}: expected {} bytes, got {}", key, N, bytes.len()))
+}
+
+fn run_agg_proof(test_case: &Value, settings: &KzgSettings) -> Result<(), String> {
+    let polynomials = hex_fields(test_case, "Polynomials")?;
+    // Copy into place instead of going through `to_array`, which would put several blobs on the
+    // stack.
+    let mut blobs: Vec<Blob> = Vec::with_capacity(polynomials.len());
+    for bytes in &polynomials {
+        if bytes.len() != BYTES_PER_BLOB {
+            return Err(format!(
+                "Polynomials: expected {} bytes, got {}",
+                BYTES_PER_BLOB,
+                bytes.len()
+            ));
+        }
+        blobs.push([0; BYTES_PER_BLOB]);
+        blobs.last_mut().unwrap().copy_from_slice(bytes);
+    }
+    let commitments = hex_fields(test_case, "Commitments")?;
+    let proof = hex_field(test_case, "Proof")?;
+    if commitments.len() != blobs.len() {
+        return Err(format!(
+            "{} commitments for {} polynomials",
+            commitments.len(),
+            blobs.len()
+        ));
+    }
+
+    for (index, (blob, expected)) in blobs.iter().zip(&commitments).enumerate() {
+        let commitment = KzgCommitment::blob_to_kzg_commitment_ref(blob, settings)
+            .map_err(|error| error.to_string())?;
+        if commitment.to_bytes()[..] != expected[..] {
+            return Err(format!("commitment {} does not match", index));
+        }
+    }
+    let computed = KzgProof::compute_aggregate_kzg_proof(&blobs, settings)
+        .map_err(|error| error.to_string())?;
+    if computed.to_bytes()[..] != proof[..] {
+        return Err(String::from("proof does not match"));
+    }
+    Ok(())
+}
+
+fn run_verify_kzg_proof(test_case: &Value, settings: &KzgSettings) -> Result<(), String> {
+    let commitment = KzgCommitment::from_bytes(&hex_field(test_case, "Commitment")?)
+        .map_err(|error| error.to_string())?;
+    let proof =
+        KzgProof::from_bytes(&hex_field(test_case, "Proof")?).map_err(|error| error.to_string())?;
+    let z =
+        to_array::<BYTES_PER_FIELD_ELEMENT>("InputPoint", &hex_field(test_case, "InputPoint")?)?;
+    let y = to_array::<BYTES_PER_FIELD_ELEMENT>(
+        "ClaimedValue",
+        &hex_field(test_case, "ClaimedValue")?,
+    )?;
+    match proof.verify_kzg_proof(commitment, z, y, settings) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(String::from("proof does not verify")),
+        Err(error) => Err(error.to_string()),
+    }
+}
@@ -0,0 +1,88 @@
+//! SSZ encoding of trusted setups, for clients that already ship SSZ tooling.
+//!
+//! A setup is the container
+//!
+//! ```text
+//! class TrustedSetup(Container):
+//!     g1_monomial: List[Bytes48, FIELD_ELEMENTS_PER_BLOB]
+//!     g2_monomial: List[Bytes96, 65]
+//! ```
+//!
+//! which serializes to two little-endian `u32` offsets, of the g1 and of the g2 points, followed by
+//! the compressed g1 points and the compressed g2 points.
+
+use crate::{
+    check_point_counts, Error, KzgSettings, TrustedSetupError, TrustedSetupPoints,
+    BYTES_PER_G1_POINT, BYTES_PER_G2_POINT,
+};
+use alloc::vec::Vec;
+
+/// The size of an SSZ offset.
+const BYTES_PER_OFFSET: usize = 4;
+
+/// The size of the fixed part of the container, which holds the offsets of its two lists.
+const FIXED_PART_SIZE: usize = 2 * BYTES_PER_OFFSET;
+
+fn read_offset(bytes: &[u8], index: usize) -> usize {
+    let start = index * BYTES_PER_OFFSET;
+    let mut offset = [0; BYTES_PER_OFFSET];
+    offset.copy_from_slice(&bytes[start..start + BYTES_PER_OFFSET]);
+    u32::from_le_bytes(offset) as usize
+}
+
+/// Decodes an SSZ encoded trusted setup into g1 and g2 points, in the form taken by
+/// `KzgSettings::load_trusted_setup`. The points are not checked to be on the curve.
+pub fn trusted_setup_from_ssz(bytes: &[u8]) -> Result<TrustedSetupPoints, Error> {
+    let invalid_format = Error::InvalidTrustedSetup(TrustedSetupError::InvalidFormat);
+    if bytes.len() < FIXED_PART_SIZE {
+        return Err(invalid_format);
+    }
+    let g1_offset = read_offset(bytes, 0);
+    let g2_offset = read_offset(bytes, 1);
+    // The first offset points right after the fixed part, and the lists follow each other.
+    if g1_offset != FIXED_PART_SIZE || g2_offset < g1_offset || g2_offset > bytes.len() {
+        return Err(invalid_format);
+    }
+    let g1 = &bytes[g1_offset..g2_offset];
+    let g2 = &bytes[g2_offset..];
+    if !g1.len().is_multiple_of(BYTES_PER_G1_POINT) || !g2.len().is_multiple_of(BYTES_PER_G2_POINT)
+    {
+        return Err(invalid_format);
+    }
+    check_point_counts(g1.len() / BYTES_PER_G1_POINT, g2.len() / BYTES_PER_G2_POINT)?;
+    let g1_bytes = g1
+        .chunks_exact(BYTES_PER_G1_POINT)
+        .map(|point| point.try_into().unwrap())
+        .collect();
+    let g2_bytes = g2
+        .chunks_exact(BYTES_PER_G2_POINT)
+        .map(|point| point.try_into().unwrap())
+        .collect();
+    Ok((g1_bytes, g2_bytes))
+}
+
+/// Encodes g1 and g2 points as SSZ, the inverse of `trusted_setup_from_ssz`.
+pub fn trusted_setup_to_ssz(
+    g1_bytes: &[[u8; BYTES_PER_G1_POINT]],
+    g2_bytes: &[[u8; BYTES_PER_G2_POINT]],
+) -> Vec<u8> {
+    let g2_offset = FIXED_PART_SIZE + BYTES_PER_G1_POINT * g1_bytes.len();
+    let mut bytes = Vec::with_capacity(g2_offset + BYTES_PER_G2_POINT * g2_bytes.len());
+    bytes.extend_from_slice(&(FIXED_PART_SIZE as u32).to_le_bytes());
+    bytes.extend_from_slice(&(g2_offset as u32).to_le_bytes());
+    for point in g1_bytes {
+        bytes.extend_from_slice(point);
+    }
+    for point in g2_bytes {
+        bytes.extend_from_slice(point);
+    }
+    bytes
+}
+
+impl KzgSettings {
+    /// Loads a trusted setup encoded by `trusted_setup_to_ssz`.
+    pub fn load_trusted_setup_ssz(bytes: &[u8]) -> Result<Self, Error> {
+        let (g1_bytes, g2_bytes) = trusted_setup_from_ssz(bytes)?;
+        Self::load_trusted_setup(g1_bytes, g2_bytes)
+    }
+}
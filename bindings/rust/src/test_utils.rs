@@ -0,0 +1,291 @@
+//! Generators of spec-valid inputs, and of invalid variants of them, for tests and benchmarks, also
+//! for downstream crates.
+//!
+//! Enabled by the `test-utils` feature. Nothing here is meant for production use.
+
+use crate::{
+    bindings, AlignedBlob, Blob, Error, KzgCommitment, KzgProof, KzgSettings, BYTES_PER_BLOB,
+    BYTES_PER_COMMITMENT, BYTES_PER_FIELD_ELEMENT, BYTES_PER_G1_POINT, BYTES_PER_G2_POINT,
+    BYTES_PER_PROOF, FIELD_ELEMENTS_PER_BLOB, NUM_G2_POINTS,
+};
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "hex")]
+use alloc::{format, string::String};
+use core::slice;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub use crate::codec::{decode_blobs, encode_blobs, USABLE_BYTES_PER_BLOB};
+
+/// The trusted setup of the enabled preset, `trusted_setup.txt` or with `minimal-spec`
+/// `trusted_setup_4.txt`, embedded in the binary so that tests do not need to locate the file.
+pub fn embedded_trusted_setup_text() -> &'static str {
+    if cfg!(feature = "minimal-spec") {
+        include_str!("../../../src/trusted_setup_4.txt")
+    } else {
+        include_str!("../../../src/trusted_setup.txt")
+    }
+}
+
+/// Returns a random blob whose field elements are all canonical, i.e. smaller than `BLS_MODULUS`.
+pub fn random_valid_blob<R: Rng + ?Sized>(rng: &mut R) -> Blob {
+    let mut blob: Blob = [0; BYTES_PER_BLOB];
+    rng.fill(&mut blob[..]);
+    // Field elements are little-endian, so clearing the top byte keeps them below the modulus.
+    for field_element in blob.chunks_exact_mut(BYTES_PER_FIELD_ELEMENT) {
+        field_element[BYTES_PER_FIELD_ELEMENT - 1] = 0;
+    }
+    blob
+}
+
+impl KzgSettings {
+    /// Loads a trusted setup generated from a secret derived from `seed`, with which anyone can
+    /// forge proofs. The same seed always gives the same setup, which commits to different values
+    /// than the mainnet one.
+    ///
+    /// Meant for tests that need working settings but not the ceremony output. With the
+    /// `minimal-spec` feature it takes milliseconds instead of reading and parsing
+    /// `trusted_setup.txt`; with `mainnet-spec` most of the time is spent converting the points to
+    /// Lagrange form, as for any setup.
+    pub fn insecure_test_setup(seed: u64) -> Result<Self, Error> {
+        let mut secret = [0; 32];
+        StdRng::seed_from_u64(seed).fill(&mut secret);
+        secret[31] &= 0x7f;
+
+        // The powers of the secret times the generators, in monomial form like `trusted_setup.txt`.
+        let mut g1_bytes = vec![[0; BYTES_PER_G1_POINT]; FIELD_ELEMENTS_PER_BLOB];
+        let mut g2_bytes = vec![[0; BYTES_PER_G2_POINT]; NUM_G2_POINTS];
+        unsafe {
+            let mut point = *bindings::blst_p1_generator();
+            for bytes in &mut g1_bytes {
+                bindings::blst_p1_compress(bytes.as_mut_ptr(), &point);
+                let previous = point;
+                bindings::blst_p1_mult(&mut point, &previous, secret.as_ptr(), 255);
+            }
+            let mut point = *bindings::blst_p2_generator();
+            for bytes in &mut g2_bytes {
+                bindings::blst_p2_compress(bytes.as_mut_ptr(), &point);
+                let previous = point;
+                bindings::blst_p2_mult(&mut point, &previous, secret.as_ptr(), 255);
+            }
+        }
+        Self::load_trusted_setup(g1_bytes, g2_bytes)
+    }
+}
+
+/// Blobs together with their commitments and aggregate proof, which verifies.
+pub struct ValidSidecar {
+    pub blobs: Vec<Blob>,
+    pub commitments: Vec<KzgCommitment>,
+    pub proof: KzgProof,
+}
+
+/// Returns `n` random blobs with their commitments and aggregate proof. The blobs are derived from
+/// `n` alone, so the same call always returns the same sidecar.
+pub fn valid_sidecar(n: usize, settings: &KzgSettings) -> Result<ValidSidecar, Error> {
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    let blobs: Vec<Blob> = (0..n).map(|_| random_valid_blob(&mut rng)).collect();
+    let commitments = blobs
+        .iter()
+        .map(|blob| KzgCommitment::blob_to_kzg_commitment_ref(blob, settings))
+        .collect::<Result<_, _>>()?;
+    let proof = KzgProof::compute_aggregate_kzg_proof(&blobs, settings)?;
+    Ok(ValidSidecar {
+        blobs,
+        commitments,
+        proof,
+    })
+}
+
+/// A change to one component of a valid blob, commitment and proof, see
+/// `assert_bit_flips_rejected`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitFlip {
+    Blob {
+        bit: usize,
+    },
+    Commitment {
+        bit: usize,
+    },
+    Proof {
+        bit: usize,
+    },
+    /// All bits of the byte at `index` are flipped.
+    CommitmentByte {
+        index: usize,
+    },
+    /// All bits of the byte at `index` are flipped.
+    ProofByte {
+        index: usize,
+    },
+}
+
+/// Whether the triple fails to verify, either with `false` or with an error such as a commitment or
+/// proof that is not a valid point.
+fn is_rejected(
+    blob: &Blob,
+    commitment: &[u8; BYTES_PER_COMMITMENT],
+    proof: &[u8; BYTES_PER_PROOF],
+    settings: &KzgSettings,
+) -> bool {
+    let (Ok(commitment), Ok(proof)) = (
+        KzgCommitment::from_bytes(commitment),
+        KzgProof::from_bytes(proof),
+    ) else {
+        return true;
+    };
+    let verified = proof.verify_aggregate_kzg_proof(
+        slice::from_ref(blob),
+        slice::from_ref(&commitment),
+        settings,
+    );
+    !matches!(verified, Ok(true))
+}
+
+/// Checks that a valid blob, commitment and proof stop verifying after any single change. Every
+/// bit and every byte of the commitment and proof is flipped, as well as every `blob_stride`-th bit
+/// of the blob, since verifying a flip of each of its bits takes long.
+///
+/// Panics with the `BitFlip` if a changed triple still verifies, or if the triple does not verify
+/// to begin with.
+pub fn assert_bit_flips_rejected(
+    blob: &Blob,
+    commitment: &KzgCommitment,
+    proof: &KzgProof,
+    settings: &KzgSettings,
+    blob_stride: usize,
+) {
+    let mut blob_copy = AlignedBlob::new_boxed_aligned();
+    blob_copy.copy_from_slice(blob);
+    let blob = &mut blob_copy.0;
+    let mut commitment = commitment.to_bytes();
+    let mut proof = proof.to_bytes();
+    assert!(
+        !is_rejected(blob, &commitment, &proof, settings),
+        "the triple does not verify"
+    );
+
+    for (index, mask) in flips(BYTES_PER_COMMITMENT) {
+        commitment[index] ^= mask;
+        let rejected = is_rejected(blob, &commitment, &proof, settings);
+        commitment[index] ^= mask;
+        let flip = describe(
+            index,
+            mask,
+            |bit| BitFlip::Commitment { bit },
+            |index| BitFlip::CommitmentByte { index },
+        );
+        assert!(rejected, "{:?} still verifies", flip);
+    }
+    for (index, mask) in flips(BYTES_PER_PROOF) {
+        proof[index] ^= mask;
+        let rejected = is_rejected(blob, &commitment, &proof, settings);
+        proof[index] ^= mask;
+        let flip = describe(
+            index,
+            mask,
+            |bit| BitFlip::Proof { bit },
+            |index| BitFlip::ProofByte { index },
+        );
+        assert!(rejected, "{:?} still verifies", flip);
+    }
+    for bit in (0..8 * BYTES_PER_BLOB).step_by(blob_stride) {
+        blob[bit / 8] ^= 1 << (bit % 8);
+        let rejected = is_rejected(blob, &commitment, &proof, settings);
+        blob[bit / 8] ^= 1 << (bit % 8);
+        assert!(rejected, "{:?} still verifies", BitFlip::Blob { bit });
+    }
+}
+
+/// The flips of each single bit of `len` bytes, followed by the flips of whole bytes, as the index
+/// of the byte and the mask to XOR it with.
+fn flips(len: usize) -> impl Iterator<Item = (usize, u8)> {
+    let bits = (0..8 * len).map(|bit| (bit / 8, 1 << (bit % 8)));
+    bits.chain((0..len).map(|index| (index, 0xff)))
+}
+
+fn describe(
+    index: usize,
+    mask: u8,
+    bit: impl FnOnce(usize) -> BitFlip,
+    byte: impl FnOnce(usize) -> BitFlip,
+) -> BitFlip {
+    if mask == 0xff {
+        byte(index)
+    } else {
+        bit(8 * index + mask.trailing_zeros() as usize)
+    }
+}
+
+/// Returns the serialized forms of valid sidecars of 1 to `max_blobs` blobs as named files, to seed
+/// the corpora of fuzz targets with inputs that get past input validation: the raw and hex encoded
+/// blobs, commitments and proofs, and each sidecar as a `public_agg_proof.json` test vector.
+#[cfg(feature = "hex")]
+pub fn corpus_seeds(
+    max_blobs: usize,
+    settings: &KzgSettings,
+) -> Result<Vec<(String, Vec<u8>)>, Error> {
+    let mut seeds = Vec::new();
+    for n in 1..=max_blobs {
+        let sidecar = valid_sidecar(n, settings)?;
+        for (i, (blob, commitment)) in sidecar.blobs.iter().zip(&sidecar.commitments).enumerate() {
+            seeds.push((format!("blob-{}-{}", n, i), blob.to_vec()));
+            seeds.push((
+                format!("commitment-{}-{}", n, i),
+                commitment.to_bytes().to_vec(),
+            ));
+            seeds.push((
+                format!("commitment-hex-{}-{}", n, i),
+                format!("0x{}", commitment.as_hex_string()).into_bytes(),
+            ));
+        }
+        seeds.push((format!("proof-{}", n), sidecar.proof.to_bytes().to_vec()));
+        seeds.push((
+            format!("proof-hex-{}", n),
+            format!("0x{}", sidecar.proof.as_hex_string()).into_bytes(),
+        ));
+        seeds.push((
+            format!("agg-proof-{}.json", n),
+            agg_proof_vector(&sidecar).into_bytes(),
+        ));
+    }
+    Ok(seeds)
+}
+
+#[cfg(feature = "hex")]
+fn agg_proof_vector(sidecar: &ValidSidecar) -> String {
+    let quoted = |hex_str: String| format!("\"{}\"", hex_str);
+    let polynomials: Vec<String> = sidecar
+        .blobs
+        .iter()
+        .map(|blob| quoted(hex::encode(blob)))
+        .collect();
+    let commitments: Vec<String> = sidecar
+        .commitments
+        .iter()
+        .map(|commitment| quoted(commitment.as_hex_string()))
+        .collect();
+    format!(
+        "{{\"NumTestCases\": 1, \"TestCases\": [{{\"NumPolys\": {}, \"PolyDegree\": {}, \"Polynomials\": [{}], \"Proof\": \"{}\", \"Commitments\": [{}]}}]}}",
+        sidecar.blobs.len(),
+        FIELD_ELEMENTS_PER_BLOB,
+        polynomials.join(", "),
+        sidecar.proof.as_hex_string(),
+        commitments.join(", ")
+    )
+}
+
+/// Writes the `corpus_seeds` into `dir`, creating it if needed, and returns how many were written.
+#[cfg(all(feature = "std", feature = "hex"))]
+pub fn write_corpus_seeds(
+    dir: &std::path::Path,
+    max_blobs: usize,
+    settings: &KzgSettings,
+) -> std::io::Result<usize> {
+    let seeds = corpus_seeds(max_blobs, settings).map_err(std::io::Error::other)?;
+    std::fs::create_dir_all(dir)?;
+    for (name, contents) in &seeds {
+        std::fs::write(dir.join(name), contents)?;
+    }
+    Ok(seeds.len())
+}
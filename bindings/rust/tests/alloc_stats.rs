@@ -0,0 +1,47 @@
+//! Runs in its own process, since the counting allocator cannot free memory allocated by tests
+//! running concurrently with the default allocator.
+#![cfg(all(feature = "alloc-stats", not(feature = "mock")))]
+
+use c_kzg::{alloc_stats, track_allocations, AllocStats, KzgProof, KzgSettings};
+use std::path::PathBuf;
+
+#[test]
+fn test_alloc_stats() {
+    unsafe { track_allocations() };
+    let trusted_setup_file = if cfg!(feature = "minimal-spec") {
+        PathBuf::from("../../src/trusted_setup_4.txt")
+    } else {
+        PathBuf::from("../../src/trusted_setup.txt")
+    };
+    let kzg_settings = KzgSettings::load_trusted_setup_file(trusted_setup_file).unwrap();
+    let loaded = alloc_stats();
+    assert_eq!(loaded.live_settings, 1);
+    assert!(loaded.bytes_in_use() > 0);
+    assert!(loaded.allocations_in_use() > 0);
+
+    KzgProof::compute_aggregate_kzg_proof(&[], &kzg_settings).unwrap();
+    assert_eq!(alloc_stats().bytes_in_use(), loaded.bytes_in_use());
+
+    let mut raw = kzg_settings.into_raw();
+    assert_eq!(alloc_stats().live_settings, 0);
+    drop(unsafe { KzgSettings::from_raw(&mut raw) });
+
+    let dropped = alloc_stats();
+    assert_eq!(dropped.live_settings, 0);
+    assert_eq!(dropped.bytes_in_use(), 0);
+    assert_eq!(dropped.allocations_in_use(), 0);
+    assert!(dropped.bytes_allocated > loaded.bytes_allocated);
+}
+
+#[test]
+fn test_bytes_in_use_of_torn_snapshot() {
+    let stats = AllocStats {
+        live_settings: 0,
+        bytes_allocated: 16,
+        bytes_freed: 32,
+        allocations: 1,
+        frees: 2,
+    };
+    assert_eq!(stats.bytes_in_use(), 0);
+    assert_eq!(stats.allocations_in_use(), 0);
+}
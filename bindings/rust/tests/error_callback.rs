@@ -0,0 +1,21 @@
+//! Runs in its own process, as the only test in it, since `set_error_callback` must not be called
+//! while other threads use the library.
+#![cfg(not(feature = "mock"))]
+
+use c_kzg::{set_error_callback, BlsFieldElement, CErrorReport, BLS_MODULUS_BYTES, C_KZG_RET};
+use std::sync::Mutex;
+
+#[test]
+fn test_error_callback() {
+    static REPORTS: Mutex<Vec<CErrorReport>> = Mutex::new(Vec::new());
+    unsafe { set_error_callback(Some(|report| REPORTS.lock().unwrap().push(*report))) };
+    assert!(BlsFieldElement::bytes_to_bls_field(BLS_MODULUS_BYTES).is_err());
+
+    let reports = REPORTS.lock().unwrap();
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+    assert_eq!(report.ret, C_KZG_RET::C_KZG_BADARGS);
+    assert!(report.file.ends_with("c_kzg_4844.c"));
+    assert!(report.line > 0);
+    assert_eq!(report.message, "blst_scalar_fr_check(&tmp)");
+}
@@ -0,0 +1,118 @@
+//! Runs in its own process, since the observer registered with `set_metrics` is global and would
+//! also see the operations of tests running concurrently.
+#![cfg(feature = "std")]
+
+use c_kzg::metrics::{self, KzgMetrics, KzgOperation};
+use c_kzg::test_utils::{embedded_trusted_setup_text, random_valid_blob};
+use c_kzg::{trusted_setup_from_text, KzgCommitment, KzgProof, KzgSettings};
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[derive(Default)]
+struct Recording {
+    operations: Mutex<Vec<KzgOperation>>,
+    verifications: Mutex<Vec<(KzgOperation, bool)>>,
+}
+
+impl KzgMetrics for Recording {
+    fn on_operation(&self, operation: KzgOperation, _duration: Duration) {
+        self.operations.lock().unwrap().push(operation);
+    }
+
+    fn on_verification(&self, operation: KzgOperation, valid: bool) {
+        self.verifications.lock().unwrap().push((operation, valid));
+    }
+}
+
+/// The only test in this process that calls into the library.
+#[test]
+fn test_metrics() {
+    let recording: &'static Recording = Box::leak(Box::default());
+    assert!(metrics::set_metrics(recording).is_ok());
+    assert!(metrics::set_metrics(&metrics::NoopMetrics).is_err());
+
+    let (g1_bytes, g2_bytes) = trusted_setup_from_text(embedded_trusted_setup_text()).unwrap();
+    let kzg_settings = KzgSettings::load_trusted_setup(g1_bytes, g2_bytes).unwrap();
+    let mut rng = rand::thread_rng();
+    let blobs = [random_valid_blob(&mut rng), random_valid_blob(&mut rng)];
+    let commitment = KzgCommitment::blob_to_kzg_commitment(blobs[0], &kzg_settings).unwrap();
+    let proof = KzgProof::compute_aggregate_kzg_proof(&blobs[..1], &kzg_settings).unwrap();
+    assert!(proof
+        .verify_aggregate_kzg_proof(
+            &blobs[..1],
+            std::slice::from_ref(&commitment),
+            &kzg_settings
+        )
+        .unwrap());
+    #[cfg(not(feature = "mock"))]
+    assert!(!proof
+        .verify_aggregate_kzg_proof(
+            &blobs[1..],
+            std::slice::from_ref(&commitment),
+            &kzg_settings
+        )
+        .unwrap());
+
+    let data_hash = [7; 32];
+    let (proof, y) =
+        KzgProof::compute_equivalence_proof(&blobs[0], &commitment, &data_hash, &kzg_settings)
+            .unwrap();
+    assert!(proof
+        .verify_equivalence_proof(&commitment, &data_hash, &y, &kzg_settings)
+        .unwrap());
+    #[cfg(not(feature = "mock"))]
+    assert!(!proof
+        .verify_equivalence_proof(&commitment, &[8; 32], &y, &kzg_settings)
+        .unwrap());
+
+    let mut operations = vec![
+        KzgOperation::BlobToKzgCommitment,
+        KzgOperation::ComputeAggregateKzgProof,
+        KzgOperation::VerifyAggregateKzgProof,
+    ];
+    let mut verifications = vec![(KzgOperation::VerifyAggregateKzgProof, true)];
+    if cfg!(not(feature = "mock")) {
+        operations.push(KzgOperation::VerifyAggregateKzgProof);
+        verifications.push((KzgOperation::VerifyAggregateKzgProof, false));
+    }
+    operations.push(KzgOperation::VerifyEquivalenceProof);
+    verifications.push((KzgOperation::VerifyEquivalenceProof, true));
+    if cfg!(not(feature = "mock")) {
+        operations.push(KzgOperation::VerifyEquivalenceProof);
+        verifications.push((KzgOperation::VerifyEquivalenceProof, false));
+    }
+    assert_eq!(*recording.operations.lock().unwrap(), operations);
+    assert_eq!(*recording.verifications.lock().unwrap(), verifications);
+}
+
+#[cfg(feature = "prometheus")]
+#[test]
+fn test_prometheus_metrics() {
+    let registry = prometheus::Registry::new();
+    let metrics = metrics::PrometheusMetrics::new(&registry).unwrap();
+    metrics.on_operation(KzgOperation::VerifyKzgProof, Duration::from_millis(2));
+    metrics.on_verification(KzgOperation::VerifyKzgProof, false);
+
+    let families = registry.gather();
+    let family = |name: &str| {
+        families
+            .iter()
+            .find(|family| family.get_name() == name)
+            .unwrap()
+    };
+    let operations = &family("kzg_operations_total").get_metric()[0];
+    assert_eq!(operations.get_label()[0].get_value(), "verify_kzg_proof");
+    assert_eq!(operations.get_counter().get_value(), 1.0);
+    let durations = &family("kzg_operation_duration_seconds").get_metric()[0];
+    assert_eq!(durations.get_histogram().get_sample_count(), 1);
+    let verifications = &family("kzg_verifications_total").get_metric()[0];
+    let labels: Vec<_> = verifications
+        .get_label()
+        .iter()
+        .map(|label| (label.get_name(), label.get_value()))
+        .collect();
+    assert_eq!(
+        labels,
+        [("operation", "verify_kzg_proof"), ("valid", "false")]
+    );
+}
@@ -0,0 +1,41 @@
+//! Runs in its own process, as the only test in it, since `set_allocator` must not be called while
+//! other threads use the library.
+#![cfg(all(feature = "std", not(feature = "mock")))]
+
+use c_kzg::{set_allocator, KzgProof, KzgSettings};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe extern "C" fn counting_malloc(size: usize) -> *mut libc::c_void {
+    ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+    libc::malloc(size)
+}
+
+unsafe extern "C" fn counting_calloc(nmemb: usize, size: usize) -> *mut libc::c_void {
+    ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+    libc::calloc(nmemb, size)
+}
+
+#[test]
+fn test_set_allocator() {
+    unsafe {
+        set_allocator(
+            Some(counting_malloc),
+            Some(counting_calloc),
+            Some(libc::free),
+        )
+    };
+    let trusted_setup_file = if cfg!(feature = "minimal-spec") {
+        PathBuf::from("../../src/trusted_setup_4.txt")
+    } else {
+        PathBuf::from("../../src/trusted_setup.txt")
+    };
+    let kzg_settings = KzgSettings::load_trusted_setup_file(trusted_setup_file).unwrap();
+    KzgProof::compute_aggregate_kzg_proof(&[], &kzg_settings).unwrap();
+    drop(kzg_settings);
+    unsafe { set_allocator(None, None, None) };
+
+    assert!(ALLOCATIONS.load(Ordering::SeqCst) > 0);
+}
@@ -0,0 +1,82 @@
+//! Shares one `KzgSettings` between many threads committing, proving and verifying at the same time,
+//! and checks every result against one computed by a single thread. Meant to be run under
+//! ThreadSanitizer as evidence for the `Sync` implementation of the settings.
+//!
+//! Runs for `C_KZG_STRESS_SECS` seconds, 3 by default.
+#![cfg(feature = "std")]
+
+use c_kzg::test_utils::{valid_sidecar, ValidSidecar};
+use c_kzg::{KzgCommitment, KzgProof, KzgSettings};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const NUM_THREADS: usize = 32;
+const MAX_BLOBS: usize = 4;
+
+#[test]
+fn test_shared_settings_across_threads() {
+    let trusted_setup_file = if cfg!(feature = "minimal-spec") {
+        PathBuf::from("../../src/trusted_setup_4.txt")
+    } else {
+        PathBuf::from("../../src/trusted_setup.txt")
+    };
+    let settings = Arc::new(KzgSettings::load_trusted_setup_file(trusted_setup_file).unwrap());
+    let sidecars: Arc<Vec<ValidSidecar>> = Arc::new(
+        (1..=MAX_BLOBS)
+            .map(|num_blobs| valid_sidecar(num_blobs, &settings).unwrap())
+            .collect(),
+    );
+    let secs = std::env::var("C_KZG_STRESS_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(3);
+    let deadline = Instant::now() + Duration::from_secs(secs);
+
+    let handles: Vec<_> = (0..NUM_THREADS)
+        .map(|thread_index| {
+            let settings = Arc::clone(&settings);
+            let sidecars = Arc::clone(&sidecars);
+            thread::spawn(move || {
+                let mut iterations = 0;
+                while iterations == 0 || Instant::now() < deadline {
+                    let sidecar = &sidecars[(thread_index + iterations) % sidecars.len()];
+                    match (thread_index + iterations) % 3 {
+                        0 => {
+                            let index = iterations % sidecar.blobs.len();
+                            let commitment = KzgCommitment::blob_to_kzg_commitment_ref(
+                                &sidecar.blobs[index],
+                                &settings,
+                            )
+                            .unwrap();
+                            assert_eq!(
+                                commitment.to_bytes(),
+                                sidecar.commitments[index].to_bytes()
+                            );
+                        }
+                        1 => {
+                            let proof =
+                                KzgProof::compute_aggregate_kzg_proof(&sidecar.blobs, &settings)
+                                    .unwrap();
+                            assert_eq!(proof.to_bytes(), sidecar.proof.to_bytes());
+                        }
+                        _ => assert!(sidecar
+                            .proof
+                            .verify_aggregate_kzg_proof(
+                                &sidecar.blobs,
+                                &sidecar.commitments,
+                                &settings
+                            )
+                            .unwrap()),
+                    }
+                    iterations += 1;
+                }
+                iterations
+            })
+        })
+        .collect();
+    for handle in handles {
+        assert!(handle.join().unwrap() > 0);
+    }
+}
@@ -0,0 +1,222 @@
+//! Kotlin and Swift bindings on top of the `c-kzg` Rust crate, generated with UniFFI.
+//!
+//! Build the library for the target platform, then generate the bindings from it, e.g.
+//!
+//! ```text
+//! cargo build --release -p c-kzg-uniffi
+//! cargo run -p c-kzg-uniffi --bin uniffi-bindgen -- generate \
+//!     --library target/release/libckzg_uniffi.so --language kotlin --out-dir out
+//! ```
+//!
+//! Byte strings cross the boundary as `ByteArray`/`Data` and are checked for their length here, so
+//! the generated code never hands the C library a short buffer.
+
+use c_kzg::{
+    Blob, KzgCommitment, KzgProof, BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT, BYTES_PER_G1_POINT,
+    BYTES_PER_G2_POINT,
+};
+use core::fmt;
+use std::sync::Arc;
+
+uniffi::setup_scaffolding!();
+
+#[derive(Debug, uniffi::Error)]
+pub enum KzgError {
+    /// An input does not have the expected byte length.
+    InvalidLength { expected: u64, actual: u64 },
+    /// The number of commitments does not match the number of blobs.
+    CountMismatch { blobs: u64, commitments: u64 },
+    /// The input was rejected by the library, e.g. a point not in G1.
+    InvalidInput { reason: String },
+}
+
+impl fmt::Display for KzgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength { expected, actual } => {
+                write!(f, "expected {} bytes, got {}", expected, actual)
+            }
+            Self::CountMismatch { blobs, commitments } => {
+                write!(f, "got {} commitments for {} blobs", commitments, blobs)
+            }
+            Self::InvalidInput { reason } => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for KzgError {}
+
+impl From<c_kzg::Error> for KzgError {
+    fn from(error: c_kzg::Error) -> Self {
+        match error {
+            c_kzg::Error::InvalidKzgProof { expected, actual }
+            | c_kzg::Error::InvalidKzgCommitment { expected, actual } => Self::InvalidLength {
+                expected: expected as u64,
+                actual: actual as u64,
+            },
+            error => Self::InvalidInput {
+                reason: error.to_string(),
+            },
+        }
+    }
+}
+
+fn to_array<const N: usize>(bytes: &[u8]) -> Result<[u8; N], KzgError> {
+    bytes.try_into().map_err(|_| KzgError::InvalidLength {
+        expected: N as u64,
+        actual: bytes.len() as u64,
+    })
+}
+
+/// Copies the blobs into one buffer, so that they can be viewed as `&[Blob]` without putting a
+/// whole blob on the stack.
+fn concat_blobs(blobs: &[Vec<u8>]) -> Result<Vec<u8>, KzgError> {
+    let mut bytes = Vec::with_capacity(blobs.len() * BYTES_PER_BLOB);
+    for blob in blobs {
+        if blob.len() != BYTES_PER_BLOB {
+            return Err(KzgError::InvalidLength {
+                expected: BYTES_PER_BLOB as u64,
+                actual: blob.len() as u64,
+            });
+        }
+        bytes.extend_from_slice(blob);
+    }
+    Ok(bytes)
+}
+
+fn as_blobs(bytes: &[u8]) -> &[Blob] {
+    // `Blob` is a byte array, so it has no alignment requirement.
+    unsafe {
+        core::slice::from_raw_parts(bytes.as_ptr() as *const Blob, bytes.len() / BYTES_PER_BLOB)
+    }
+}
+
+/// A loaded trusted setup, exposing the KZG operations as methods.
+#[derive(uniffi::Object)]
+pub struct KzgSettings(c_kzg::KzgSettings);
+
+#[uniffi::export]
+impl KzgSettings {
+    /// Loads a trusted setup file in the format of `src/trusted_setup.txt`.
+    #[uniffi::constructor]
+    pub fn load_trusted_setup_file(path: String) -> Result<Arc<Self>, KzgError> {
+        let settings = c_kzg::KzgSettings::load_trusted_setup_file(path)?;
+        Ok(Arc::new(Self(settings)))
+    }
+
+    /// Loads a trusted setup from compressed g1 and g2 points.
+    #[uniffi::constructor]
+    pub fn load_trusted_setup(
+        g1_points: Vec<Vec<u8>>,
+        g2_points: Vec<Vec<u8>>,
+    ) -> Result<Arc<Self>, KzgError> {
+        let g1_points = g1_points
+            .iter()
+            .map(|point| to_array::<BYTES_PER_G1_POINT>(point))
+            .collect::<Result<_, _>>()?;
+        let g2_points = g2_points
+            .iter()
+            .map(|point| to_array::<BYTES_PER_G2_POINT>(point))
+            .collect::<Result<_, _>>()?;
+        let settings = c_kzg::KzgSettings::load_trusted_setup(g1_points, g2_points)?;
+        Ok(Arc::new(Self(settings)))
+    }
+
+    pub fn blob_to_kzg_commitment(&self, blob: Vec<u8>) -> Result<Vec<u8>, KzgError> {
+        let blob = concat_blobs(&[blob])?;
+        let commitment = KzgCommitment::blob_to_kzg_commitment_ref(&as_blobs(&blob)[0], &self.0)?;
+        Ok(commitment.to_bytes().to_vec())
+    }
+
+    pub fn compute_aggregate_kzg_proof(&self, blobs: Vec<Vec<u8>>) -> Result<Vec<u8>, KzgError> {
+        let blobs = concat_blobs(&blobs)?;
+        let proof = KzgProof::compute_aggregate_kzg_proof(as_blobs(&blobs), &self.0)?;
+        Ok(proof.to_bytes().to_vec())
+    }
+
+    pub fn verify_aggregate_kzg_proof(
+        &self,
+        blobs: Vec<Vec<u8>>,
+        commitments: Vec<Vec<u8>>,
+        proof: Vec<u8>,
+    ) -> Result<bool, KzgError> {
+        if blobs.len() != commitments.len() {
+            return Err(KzgError::CountMismatch {
+                blobs: blobs.len() as u64,
+                commitments: commitments.len() as u64,
+            });
+        }
+        let blobs = concat_blobs(&blobs)?;
+        let commitments = commitments
+            .iter()
+            .map(|commitment| KzgCommitment::from_bytes(commitment))
+            .collect::<Result<Vec<_>, _>>()?;
+        let proof = KzgProof::from_bytes(&proof)?;
+        Ok(proof.verify_aggregate_kzg_proof(as_blobs(&blobs), &commitments, &self.0)?)
+    }
+
+    pub fn verify_kzg_proof(
+        &self,
+        commitment: Vec<u8>,
+        z: Vec<u8>,
+        y: Vec<u8>,
+        proof: Vec<u8>,
+    ) -> Result<bool, KzgError> {
+        let commitment = KzgCommitment::from_bytes(&commitment)?;
+        let z = to_array::<BYTES_PER_FIELD_ELEMENT>(&z)?;
+        let y = to_array::<BYTES_PER_FIELD_ELEMENT>(&y)?;
+        let proof = KzgProof::from_bytes(&proof)?;
+        Ok(proof.verify_kzg_proof(commitment, z, y, &self.0)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_trusted_setup() -> Arc<KzgSettings> {
+        let trusted_setup = if cfg!(feature = "minimal-spec") {
+            include_str!("../../../../src/trusted_setup_4.txt")
+        } else {
+            include_str!("../../../../src/trusted_setup.txt")
+        };
+        let (g1_points, g2_points) = c_kzg::trusted_setup_from_text(trusted_setup).unwrap();
+        KzgSettings::load_trusted_setup(
+            g1_points.iter().map(|point| point.to_vec()).collect(),
+            g2_points.iter().map(|point| point.to_vec()).collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_aggregate_roundtrip() {
+        let settings = load_trusted_setup();
+        // A blob of one non-zero field element, which is not a constant polynomial.
+        let mut blob = vec![0u8; BYTES_PER_BLOB];
+        blob[0] = 1;
+        let blobs = vec![vec![0u8; BYTES_PER_BLOB], blob];
+        let commitments = blobs
+            .iter()
+            .map(|blob| settings.blob_to_kzg_commitment(blob.clone()).unwrap())
+            .collect::<Vec<_>>();
+        let proof = settings.compute_aggregate_kzg_proof(blobs.clone()).unwrap();
+        assert!(settings
+            .verify_aggregate_kzg_proof(blobs.clone(), commitments.clone(), proof.clone())
+            .unwrap());
+        assert!(!settings
+            .verify_aggregate_kzg_proof(blobs[..1].to_vec(), commitments[..1].to_vec(), proof)
+            .unwrap());
+
+        assert!(matches!(
+            settings.blob_to_kzg_commitment(vec![0; BYTES_PER_BLOB - 1]),
+            Err(KzgError::InvalidLength { .. })
+        ));
+        assert!(matches!(
+            settings.verify_aggregate_kzg_proof(blobs, commitments[..1].to_vec(), vec![0; 48]),
+            Err(KzgError::CountMismatch {
+                blobs: 2,
+                commitments: 1
+            })
+        ));
+    }
+}
@@ -20,8 +20,31 @@
 #include <stdlib.h>
 #include <string.h>
 
+/** The allocation functions used for all memory allocated by this library, see #c_kzg_set_allocator. */
+static c_kzg_malloc_fn malloc_fn = malloc;
+static c_kzg_calloc_fn calloc_fn = calloc;
+static c_kzg_free_fn free_fn = free;
+
+void c_kzg_set_allocator(c_kzg_malloc_fn malloc_impl, c_kzg_calloc_fn calloc_impl, c_kzg_free_fn free_impl) {
+    malloc_fn = malloc_impl != NULL ? malloc_impl : malloc;
+    calloc_fn = calloc_impl != NULL ? calloc_impl : calloc;
+    free_fn = free_impl != NULL ? free_impl : free;
+}
+
+/** The callback told about every failure, see #c_kzg_set_error_callback. */
+static c_kzg_error_fn error_fn = NULL;
+
+void c_kzg_set_error_callback(c_kzg_error_fn callback) {
+    error_fn = callback;
+}
+
 /**
- * Wrapped `malloc()` that reports failures to allocate.
+ * Evaluates to `ret` after reporting the failure to the error callback, if one is set.
+ */
+#define FAIL(ret, message) ((error_fn != NULL ? error_fn((ret), __FILE__, __LINE__, (message)) : (void)0), (ret))
+
+/**
+ * Wrapped `malloc_fn` that reports failures to allocate.
  *
  * @param[out] x Pointer to the allocated space
  * @param[in]  n The number of bytes to be allocated
@@ -30,20 +53,37 @@
  */
 static C_KZG_RET c_kzg_malloc(void **x, size_t n) {
     if (n > 0) {
-        *x = malloc(n);
-        return *x != NULL ? C_KZG_OK : C_KZG_MALLOC;
+        *x = malloc_fn(n);
+        return *x != NULL ? C_KZG_OK : FAIL(C_KZG_MALLOC, "allocation failed");
     }
     *x = NULL;
     return C_KZG_OK;
 }
 
 #define CHECK(cond)                                                                                                    \
-    if (!(cond)) return C_KZG_BADARGS
+    if (!(cond)) return FAIL(C_KZG_BADARGS, #cond)
+
+/**
+ * Declare, allocate and free a `Polynomial` temporary, which is `FIELD_ELEMENTS_PER_BLOB` field elements large.
+ *
+ * By default these live on the stack. Define `C_KZG_SMALL_STACK` to allocate them with #c_kzg_malloc instead, for
+ * threads whose stack is too small to hold them. The pointer declared by `DECLARE_POLY` is valid (or `NULL`) from the
+ * point of declaration, so `FREE_POLY` may be called on any exit path.
+ */
+#ifdef C_KZG_SMALL_STACK
+#define DECLARE_POLY(name) Polynomial *name = NULL
+#define ALLOC_POLY(name) c_kzg_malloc((void **)&name, sizeof(Polynomial))
+#define FREE_POLY(name) if ((name) != NULL) free_fn(name)
+#else
+#define DECLARE_POLY(name) Polynomial name##_storage, *name = &name##_storage
+#define ALLOC_POLY(name) C_KZG_OK
+#define FREE_POLY(name) ((void)name)
+#endif
 
 /**
  * Allocate memory for an array of G1 group elements.
  *
- * @remark Free the space later using `free()`.
+ * @remark Free the space later using `free_fn`, the free function set with #c_kzg_set_allocator.
  *
  * @param[out] x Pointer to the allocated space
  * @param[in]  n The number of G1 elements to be allocated
@@ -57,7 +97,7 @@ static C_KZG_RET new_g1_array(g1_t **x, size_t n) {
 /**
  * Allocate memory for an array of G2 group elements.
  *
- * @remark Free the space later using `free()`.
+ * @remark Free the space later using `free_fn`, the free function set with #c_kzg_set_allocator.
  *
  * @param[out] x Pointer to the allocated space
  * @param[in]  n The number of G2 elements to be allocated
@@ -71,7 +111,7 @@ static C_KZG_RET new_g2_array(g2_t **x, size_t n) {
 /**
  * Allocate memory for an array of field elements.
  *
- * @remark Free the space later using `free()`.
+ * @remark Free the space later using `free_fn`, the free function set with #c_kzg_set_allocator.
  *
  * @param[out] x Pointer to the allocated space
  * @param[in]  n The number of field elements to be allocated
@@ -281,7 +321,7 @@ static C_KZG_RET fr_batch_inv(fr_t *out, const fr_t *a, size_t len) {
     out[0] = inv;
 
 out:
-    if (prod != NULL) free(prod);
+    if (prod != NULL) free_fn(prod);
     return ret;
 }
 
@@ -525,6 +565,33 @@ static void fft_g1_fast(g1_t *out, const g1_t *in, uint64_t stride, const fr_t *
     }
 }
 
+/**
+ * Fast Fourier Transform over field elements, as #fft_g1_fast over group elements.
+ *
+ * @param[out] out    The results (array of length @p n)
+ * @param[in]  in     The input data (array of length @p n * @p stride)
+ * @param[in]  stride The input data stride
+ * @param[in]  roots  Roots of unity (array of length @p n * @p roots_stride)
+ * @param[in]  roots_stride The stride interval among the roots of unity
+ * @param[in]  n      Length of the FFT, must be a power of two
+ */
+static void fft_fr_fast(fr_t *out, const fr_t *in, uint64_t stride, const fr_t *roots, uint64_t roots_stride,
+                        uint64_t n) {
+    uint64_t half = n / 2;
+    if (half > 0) {
+        fft_fr_fast(out, in, stride * 2, roots, roots_stride * 2, half);
+        fft_fr_fast(out + half, in + stride, stride * 2, roots, roots_stride * 2, half);
+        for (uint64_t i = 0; i < half; i++) {
+            fr_t y_times_root;
+            fr_mul(&y_times_root, &out[i + half], &roots[i * roots_stride]);
+            fr_sub(&out[i + half], &out[i], &y_times_root);
+            fr_add(&out[i], &out[i], &y_times_root);
+        }
+    } else {
+        *out = *in;
+    }
+}
+
 /**
  * The main entry point for forward and reverse FFTs over the finite field.
  *
@@ -540,6 +607,7 @@ static C_KZG_RET fft_g1(g1_t *out, const g1_t *in, bool inverse, uint64_t n, con
     uint64_t stride = fs->max_width / n;
     CHECK(n <= fs->max_width);
     CHECK(is_power_of_two(n));
+    CHECK(fs->expanded_roots_of_unity != NULL && fs->reverse_roots_of_unity != NULL);
     if (inverse) {
         fr_t inv_len;
         fr_from_uint64(&inv_len, n);
@@ -723,9 +791,9 @@ static C_KZG_RET new_fft_settings(FFTSettings *fs, unsigned int max_scale) {
     goto out_success;
 
 out_error:
-    if (fs->expanded_roots_of_unity != NULL) free(fs->expanded_roots_of_unity);
-    if (fs->reverse_roots_of_unity != NULL) free(fs->reverse_roots_of_unity);
-    if (fs->roots_of_unity != NULL) free(fs->roots_of_unity);
+    if (fs->expanded_roots_of_unity != NULL) free_fn(fs->expanded_roots_of_unity);
+    if (fs->reverse_roots_of_unity != NULL) free_fn(fs->reverse_roots_of_unity);
+    if (fs->roots_of_unity != NULL) free_fn(fs->roots_of_unity);
 out_success:
     return ret;
 }
@@ -736,21 +804,36 @@ out_success:
  * @param fs The settings to be freed
  */
 static void free_fft_settings(FFTSettings *fs) {
-    free(fs->expanded_roots_of_unity);
-    free(fs->reverse_roots_of_unity);
-    free(fs->roots_of_unity);
+    free_fn(fs->expanded_roots_of_unity);
+    free_fn(fs->reverse_roots_of_unity);
+    free_fn(fs->roots_of_unity);
     fs->max_width = 0;
 }
 
+/**
+ * Free the ascending and descending powers of the root of unity in an FFTSettings structure.
+ *
+ * These are only needed to perform FFTs, which happens once while loading the trusted setup. After that only the
+ * bit-reversal permuted `roots_of_unity` are used, so dropping the other two arrays shrinks the resident settings.
+ *
+ * @param fs The settings whose auxiliary roots are to be freed
+ */
+static void free_fft_auxiliary_roots(FFTSettings *fs) {
+    free_fn(fs->expanded_roots_of_unity);
+    free_fn(fs->reverse_roots_of_unity);
+    fs->expanded_roots_of_unity = NULL;
+    fs->reverse_roots_of_unity = NULL;
+}
+
 /**
  * Free the memory that was previously allocated by #new_kzg_settings.
  *
  * @param ks The settings to be freed
  */
 static void free_kzg_settings(KZGSettings *ks) {
-    free((FFTSettings*)ks->fs);
-    free(ks->g1_values);
-    free(ks->g2_values);
+    free_fn((FFTSettings*)ks->fs);
+    free_fn(ks->g1_values);
+    free_fn(ks->g2_values);
 }
 
 
@@ -796,10 +879,13 @@ void bytes_from_g1(uint8_t out[48], const g1_t *in) {
     blst_p1_compress(out, in);
 }
 
+/**
+ * Decompress a G1 point, rejecting points that are not on the curve or not in the G1 subgroup.
+ */
 C_KZG_RET bytes_to_g1(g1_t* out, const uint8_t bytes[48]) {
     blst_p1_affine tmp;
-    if (blst_p1_uncompress(&tmp, bytes) != BLST_SUCCESS)
-        return C_KZG_BADARGS;
+    CHECK(blst_p1_uncompress(&tmp, bytes) == BLST_SUCCESS);
+    CHECK(blst_p1_affine_in_g1(&tmp));
     blst_p1_from_affine(out, &tmp);
     return C_KZG_OK;
 }
@@ -847,14 +933,17 @@ C_KZG_RET load_trusted_setup(KZGSettings *out, const uint8_t g1_bytes[], size_t
     ret = reverse_bit_order(out->g1_values, sizeof(g1_t), n1);
     if (ret != C_KZG_OK) goto out_error;
 
+    // No more FFTs are performed after this point
+    free_fft_auxiliary_roots((FFTSettings*)out->fs);
+
     goto out_success;
 
 out_error:
-    if (out->fs != NULL) free((void *)out->fs);
-    if (out->g1_values != NULL) free(out->g1_values);
-    if (out->g2_values != NULL) free(out->g2_values);
+    if (out->fs != NULL) free_fn((void *)out->fs);
+    if (out->g1_values != NULL) free_fn(out->g1_values);
+    if (out->g2_values != NULL) free_fn(out->g2_values);
 out_success:
-    if (g1_projective != NULL) free(g1_projective);
+    if (g1_projective != NULL) free_fn(g1_projective);
     return ret;
 }
 
@@ -885,6 +974,153 @@ C_KZG_RET load_trusted_setup_file(KZGSettings *out, FILE *in) {
     return load_trusted_setup(out, g1_bytes, FIELD_ELEMENTS_PER_BLOB, g2_bytes, 65);
 }
 
+/** The bytes of the points written by #save_trusted_setup_lagrange, which are followed by their digest. */
+#define LAGRANGE_POINTS_BYTES (96 * (FIELD_ELEMENTS_PER_BLOB + 65))
+
+static void hash(uint8_t md[32], const uint8_t input[], size_t n);
+
+/**
+ * Write the points of loaded settings in the form they are used in, so that #load_trusted_setup_lagrange can restore
+ * them without the FFT of #load_trusted_setup.
+ *
+ * @param[out] out #FIELD_ELEMENTS_PER_BLOB uncompressed G1 points in Lagrange form and bit-reversal order, followed by
+ *                 the 65 compressed G2 points, 96 bytes each, and the SHA-256 digest of the points
+ * @param[in]  s   Settings loaded with #FIELD_ELEMENTS_PER_BLOB G1 and 65 G2 points
+ * @retval C_KZG_OK      All is well
+ * @retval C_KZG_BADARGS @p s was loaded with #load_trusted_setup_verify_only and has no G1 points
+ */
+C_KZG_RET save_trusted_setup_lagrange(uint8_t *out, const KZGSettings *s) {
+    uint64_t i;
+    CHECK(s->g1_values != NULL);
+    for (i = 0; i < FIELD_ELEMENTS_PER_BLOB; i++) {
+        blst_p1_serialize(&out[96 * i], &s->g1_values[i]);
+    }
+    for (i = 0; i < 65; i++) {
+        blst_p2_compress(&out[96 * (FIELD_ELEMENTS_PER_BLOB + i)], &s->g2_values[i]);
+    }
+    hash(&out[LAGRANGE_POINTS_BYTES], out, LAGRANGE_POINTS_BYTES);
+    return C_KZG_OK;
+}
+
+/**
+ * Load settings written by #save_trusted_setup_lagrange.
+ *
+ * The digest is checked first, so that corrupted or truncated input is rejected. The G2 points, which verification
+ * pairs against, are then checked to be in the G2 subgroup. The G1 points are only checked to be on the curve, since
+ * checking them to be in the G1 subgroup would take about as long as loading the setup with #load_trusted_setup. Like
+ * a trusted setup file, the bytes are trusted to hold the points of the setup.
+ *
+ * @param[out] out   The settings, to be freed with #free_trusted_setup
+ * @param[in]  bytes The output of #save_trusted_setup_lagrange
+ * @retval C_KZG_OK      All is well
+ * @retval C_KZG_BADARGS The digest does not match the points, or a point is invalid
+ * @retval C_KZG_ERROR   An internal error occurred
+ * @retval C_KZG_MALLOC  Memory allocation failed
+ */
+C_KZG_RET load_trusted_setup_lagrange(KZGSettings *out, const uint8_t *bytes) {
+    uint64_t i;
+    blst_p1_affine g1_affine;
+    blst_p2_affine g2_affine;
+    C_KZG_RET ret;
+
+    uint8_t digest[32];
+
+    out->fs = NULL;
+    out->g1_values = NULL;
+    out->g2_values = NULL;
+
+    hash(digest, bytes, LAGRANGE_POINTS_BYTES);
+    if (memcmp(digest, &bytes[LAGRANGE_POINTS_BYTES], 32) != 0) return FAIL(C_KZG_BADARGS, "digest mismatch");
+
+    ret = new_g1_array(&out->g1_values, FIELD_ELEMENTS_PER_BLOB);
+    if (ret != C_KZG_OK) goto out_error;
+    ret = new_g2_array(&out->g2_values, 65);
+    if (ret != C_KZG_OK) goto out_error;
+
+    for (i = 0; i < FIELD_ELEMENTS_PER_BLOB; i++) {
+        if (blst_p1_deserialize(&g1_affine, &bytes[96 * i]) != BLST_SUCCESS) {
+            ret = FAIL(C_KZG_BADARGS, "invalid G1 point");
+            goto out_error;
+        }
+        blst_p1_from_affine(&out->g1_values[i], &g1_affine);
+    }
+    bytes += 96 * FIELD_ELEMENTS_PER_BLOB;
+    for (i = 0; i < 65; i++) {
+        if (blst_p2_uncompress(&g2_affine, &bytes[96 * i]) != BLST_SUCCESS || !blst_p2_affine_in_g2(&g2_affine)) {
+            ret = FAIL(C_KZG_BADARGS, "invalid G2 point");
+            goto out_error;
+        }
+        blst_p2_from_affine(&out->g2_values[i], &g2_affine);
+    }
+
+    unsigned int max_scale = 0;
+    while (((uint64_t)1 << max_scale) < FIELD_ELEMENTS_PER_BLOB) max_scale++;
+
+    ret = c_kzg_malloc((void**)&out->fs, sizeof(FFTSettings));
+    if (ret != C_KZG_OK) goto out_error;
+    ret = new_fft_settings((FFTSettings*)out->fs, max_scale);
+    if (ret != C_KZG_OK) goto out_error;
+    free_fft_auxiliary_roots((FFTSettings*)out->fs);
+    return C_KZG_OK;
+
+out_error:
+    if (out->fs != NULL) free_fn((void *)out->fs);
+    if (out->g1_values != NULL) free_fn(out->g1_values);
+    if (out->g2_values != NULL) free_fn(out->g2_values);
+    return ret;
+}
+
+/**
+ * Load the parts of a trusted setup that verification needs: the G2 points and the roots of unity.
+ *
+ * The G1 points are only used to compute commitments and proofs, so they are neither taken nor converted to Lagrange
+ * form, which saves the FFT of #load_trusted_setup and the memory of the G1 points. Functions that compute commitments
+ * or proofs with the resulting settings return #C_KZG_BADARGS.
+ *
+ * @param[out] out      The settings, to be freed with #free_trusted_setup
+ * @param[in]  g2_bytes @p n2 compressed G2 points of the trusted setup, 96 bytes each
+ * @param[in]  n2       The number of G2 points, 65
+ * @retval C_KZG_OK      All is well
+ * @retval C_KZG_BADARGS @p n2 is not 65 or a point is invalid
+ * @retval C_KZG_ERROR   An internal error occurred
+ * @retval C_KZG_MALLOC  Memory allocation failed
+ */
+C_KZG_RET load_trusted_setup_verify_only(KZGSettings *out, const uint8_t g2_bytes[], size_t n2) {
+    uint64_t i;
+    blst_p2_affine g2_affine;
+    C_KZG_RET ret;
+
+    out->fs = NULL;
+    out->g1_values = NULL;
+    out->g2_values = NULL;
+    CHECK(n2 == 65);
+
+    ret = new_g2_array(&out->g2_values, n2);
+    if (ret != C_KZG_OK) goto out_error;
+    for (i = 0; i < n2; i++) {
+        if (blst_p2_uncompress(&g2_affine, &g2_bytes[96 * i]) != BLST_SUCCESS || !blst_p2_affine_in_g2(&g2_affine)) {
+            ret = FAIL(C_KZG_BADARGS, "invalid G2 point");
+            goto out_error;
+        }
+        blst_p2_from_affine(&out->g2_values[i], &g2_affine);
+    }
+
+    unsigned int max_scale = 0;
+    while (((uint64_t)1 << max_scale) < FIELD_ELEMENTS_PER_BLOB) max_scale++;
+
+    ret = c_kzg_malloc((void**)&out->fs, sizeof(FFTSettings));
+    if (ret != C_KZG_OK) goto out_error;
+    ret = new_fft_settings((FFTSettings*)out->fs, max_scale);
+    if (ret != C_KZG_OK) goto out_error;
+    free_fft_auxiliary_roots((FFTSettings*)out->fs);
+    return C_KZG_OK;
+
+out_error:
+    if (out->fs != NULL) free_fn((void *)out->fs);
+    if (out->g2_values != NULL) free_fn(out->g2_values);
+    return ret;
+}
+
 void free_trusted_setup(KZGSettings *s) {
     free_fft_settings((FFTSettings*)s->fs);
     free_kzg_settings(s);
@@ -907,7 +1143,7 @@ static void hash_to_bls_field(BLSFieldElement *out, const uint8_t bytes[32]) {
 C_KZG_RET bytes_to_bls_field(BLSFieldElement *out, const uint8_t bytes[32]) {
     blst_scalar tmp;
     blst_scalar_from_lendian(&tmp, bytes);
-    if (!blst_scalar_fr_check(&tmp)) return C_KZG_BADARGS;
+    CHECK(blst_scalar_fr_check(&tmp));
     blst_fr_from_scalar(out, &tmp);
     return C_KZG_OK;
 }
@@ -946,6 +1182,23 @@ static void poly_lincomb(Polynomial *out, const Polynomial *vectors, const fr_t
  * We do the second of these to save memory here.
  */
 static C_KZG_RET g1_lincomb(g1_t *out, const g1_t *p, const fr_t *coeffs, const uint64_t len) {
+#ifdef C_KZG_CONSTANT_TIME
+    // The coefficients are derived from blob data, which may be secret. Multiply every point by the
+    // full width of its coefficient with Blst's constant-time multiplication, which is much slower
+    // than Pippenger but does not depend on the values of the coefficients. The products are summed
+    // with `blst_p1_add_or_double`, which computes both the addition and the doubling and picks the
+    // result, and the identity cases, with masks rather than branches (`POINT_DADD_IMPL` in Blst's
+    // `ec_ops.h`), so a zero coefficient or two equal products take no other path.
+    g1_t tmp;
+    blst_scalar s;
+    *out = g1_identity;
+    for (uint64_t i = 0; i < len; i++) {
+        blst_scalar_from_fr(&s, &coeffs[i]);
+        blst_p1_mult(&tmp, &p[i], s.b, 255);
+        blst_p1_add_or_double(out, out, &tmp);
+    }
+    return C_KZG_OK;
+#else
     if (len < 8) { // Tunable parameter: must be at least 2 since Blst fails for 0 or 1
         // Direct approach
         g1_t tmp;
@@ -956,18 +1209,18 @@ static C_KZG_RET g1_lincomb(g1_t *out, const g1_t *p, const fr_t *coeffs, const
         }
     } else {
         // Blst's implementation of the Pippenger method
-        void *scratch = malloc(blst_p1s_mult_pippenger_scratch_sizeof(len));
-        if (scratch == NULL) return C_KZG_MALLOC;
-        blst_p1_affine *p_affine = malloc(len * sizeof(blst_p1_affine));
+        void *scratch = malloc_fn(blst_p1s_mult_pippenger_scratch_sizeof(len));
+        if (scratch == NULL) return FAIL(C_KZG_MALLOC, "allocation failed");
+        blst_p1_affine *p_affine = malloc_fn(len * sizeof(blst_p1_affine));
         if (p_affine == NULL) {
-            free(scratch);
-            return C_KZG_MALLOC;
+            free_fn(scratch);
+            return FAIL(C_KZG_MALLOC, "allocation failed");
         }
-        blst_scalar *scalars = malloc(len * sizeof(blst_scalar));
+        blst_scalar *scalars = malloc_fn(len * sizeof(blst_scalar));
         if (scalars == NULL) {
-            free(scratch);
-            free(p_affine);
-            return C_KZG_MALLOC;
+            free_fn(scratch);
+            free_fn(p_affine);
+            return FAIL(C_KZG_MALLOC, "allocation failed");
         }
 
         // Transform the points to affine representation
@@ -985,31 +1238,240 @@ static C_KZG_RET g1_lincomb(g1_t *out, const g1_t *p, const fr_t *coeffs, const
         blst_p1s_mult_pippenger(out, points_arg, len, scalars_arg, 256, scratch);
 
         // Tidy up
-        free(scratch);
-        free(p_affine);
-        free(scalars);
+        free_fn(scratch);
+        free_fn(p_affine);
+        free_fn(scalars);
     }
     return C_KZG_OK;
+#endif
 }
 
 static C_KZG_RET poly_to_kzg_commitment(KZGCommitment *out, const Polynomial *p, const KZGSettings *s) {
+    CHECK(s->g1_values != NULL);
     return g1_lincomb(out, s->g1_values, (const fr_t *)(&p->evals), FIELD_ELEMENTS_PER_BLOB);
 }
 
-static C_KZG_RET poly_from_blob(Polynomial *p, const Blob *blob) {
+static C_KZG_RET fr_array_from_bytes(fr_t *out, const uint8_t *bytes, size_t n) {
+#ifdef C_KZG_CONSTANT_TIME
+    // Check every field element before failing, so that the time taken does not reveal the position
+    // of the first non-canonical one.
+    blst_scalar tmp;
+    bool canonical = true;
+    for (size_t i = 0; i < n; i++) {
+        blst_scalar_from_lendian(&tmp, &bytes[i * BYTES_PER_FIELD_ELEMENT]);
+        canonical &= blst_scalar_fr_check(&tmp);
+        blst_fr_from_scalar(&out[i], &tmp);
+    }
+    CHECK(canonical);
+    return C_KZG_OK;
+#else
     C_KZG_RET ret;
-    for (size_t i = 0; i < FIELD_ELEMENTS_PER_BLOB; i++) {
-        ret = bytes_to_bls_field(&p->evals[i], &blob->bytes[i * BYTES_PER_FIELD_ELEMENT]);
+    for (size_t i = 0; i < n; i++) {
+        ret = bytes_to_bls_field(&out[i], &bytes[i * BYTES_PER_FIELD_ELEMENT]);
         if (ret != C_KZG_OK) return ret;
     }
     return C_KZG_OK;
+#endif
+}
+
+static C_KZG_RET poly_from_blob(Polynomial *p, const Blob *blob) {
+    return fr_array_from_bytes(p->evals, blob->bytes, FIELD_ELEMENTS_PER_BLOB);
 }
 
 C_KZG_RET blob_to_kzg_commitment(KZGCommitment *out, const Blob *blob, const KZGSettings *s) {
-    Polynomial p;
-    C_KZG_RET ret = poly_from_blob(&p, blob);
-    if (ret != C_KZG_OK) return ret;
-    return poly_to_kzg_commitment(out, &p, s);
+    DECLARE_POLY(p);
+    C_KZG_RET ret = ALLOC_POLY(p);
+    if (ret != C_KZG_OK) goto out;
+    ret = poly_from_blob(p, blob);
+    if (ret != C_KZG_OK) goto out;
+    ret = poly_to_kzg_commitment(out, p, s);
+
+out:
+    FREE_POLY(p);
+    return ret;
+}
+
+/**
+ * Compute the commitment to a blob whose field elements after the first @p n are zero, without padding it.
+ *
+ * The result is the same as #blob_to_kzg_commitment of the padded blob, but the multi-scalar multiplication only runs
+ * over the first @p n points of the setup.
+ *
+ * @param[out] out            The commitment
+ * @param[in]  field_elements @p n field elements of #BYTES_PER_FIELD_ELEMENT bytes each
+ * @param[in]  n              The number of field elements, at most #FIELD_ELEMENTS_PER_BLOB
+ * @param[in]  s              The trusted setup
+ * @retval C_KZG_OK      All is well
+ * @retval C_KZG_BADARGS @p n is too large or a field element is not canonical
+ * @retval C_KZG_MALLOC  Memory allocation failed
+ */
+C_KZG_RET blob_prefix_to_kzg_commitment(KZGCommitment *out, const uint8_t *field_elements, size_t n,
+                                        const KZGSettings *s) {
+    fr_t *evals = NULL;
+    C_KZG_RET ret;
+    CHECK(n <= FIELD_ELEMENTS_PER_BLOB);
+    CHECK(s->g1_values != NULL);
+    ret = new_fr_array(&evals, n);
+    if (ret != C_KZG_OK) goto out;
+    ret = fr_array_from_bytes(evals, field_elements, n);
+    if (ret != C_KZG_OK) goto out;
+    ret = g1_lincomb(out, s->g1_values, evals, n);
+
+out:
+    if (evals != NULL) free_fn(evals);
+    return ret;
+}
+
+/**
+ * Convert a polynomial in coefficient form to the evaluation form used with the trusted setup.
+ *
+ * The coefficients are evaluated at the roots of unity with an FFT. The loaded settings only keep the roots in
+ * bit-reversal permutation, so they are put back in ascending order first, and the evaluations are permuted to match
+ * the Lagrange form points of the setup.
+ *
+ * @param[out] out          The polynomial in evaluation form
+ * @param[in]  coefficients @p n coefficients, lowest degree first
+ * @param[in]  n            The number of coefficients, at most #FIELD_ELEMENTS_PER_BLOB
+ * @param[in]  s            The trusted setup
+ * @retval C_KZG_OK      All is well
+ * @retval C_KZG_BADARGS @p n is too large
+ * @retval C_KZG_MALLOC  Memory allocation failed
+ */
+static C_KZG_RET poly_from_coefficients(Polynomial *out, const fr_t *coefficients, size_t n, const KZGSettings *s) {
+    fr_t *padded = NULL;
+    fr_t *roots = NULL;
+    C_KZG_RET ret;
+    CHECK(n <= FIELD_ELEMENTS_PER_BLOB);
+
+    ret = new_fr_array(&padded, FIELD_ELEMENTS_PER_BLOB);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&roots, FIELD_ELEMENTS_PER_BLOB);
+    if (ret != C_KZG_OK) goto out;
+
+    for (uint64_t i = 0; i < FIELD_ELEMENTS_PER_BLOB; i++) {
+        padded[i] = i < n ? coefficients[i] : fr_zero;
+    }
+    int unused_bit_len = 32 - log2_pow2(FIELD_ELEMENTS_PER_BLOB);
+    for (uint32_t i = 0; i < FIELD_ELEMENTS_PER_BLOB; i++) {
+        roots[i] = s->fs->roots_of_unity[reverse_bits(i) >> unused_bit_len];
+    }
+
+    fft_fr_fast(out->evals, padded, 1, roots, 1, FIELD_ELEMENTS_PER_BLOB);
+    ret = reverse_bit_order(out->evals, sizeof(fr_t), FIELD_ELEMENTS_PER_BLOB);
+
+out:
+    if (padded != NULL) free_fn(padded);
+    if (roots != NULL) free_fn(roots);
+    return ret;
+}
+
+/**
+ * Commit to a polynomial in coefficient (monomial) form.
+ *
+ * The commitment is the same as the one of the blob holding the evaluations of the polynomial, so proofs computed with
+ * #compute_proof_monomial are checked with #verify_kzg_proof.
+ *
+ * @param[out] out          The commitment
+ * @param[in]  coefficients @p n coefficients of #BYTES_PER_FIELD_ELEMENT bytes each, lowest degree first
+ * @param[in]  n            The number of coefficients, at most #FIELD_ELEMENTS_PER_BLOB
+ * @param[in]  s            The trusted setup
+ * @retval C_KZG_OK      All is well
+ * @retval C_KZG_BADARGS @p n is too large or a coefficient is not canonical
+ * @retval C_KZG_MALLOC  Memory allocation failed
+ */
+C_KZG_RET commit_coefficients(KZGCommitment *out, const uint8_t *coefficients, size_t n, const KZGSettings *s) {
+    fr_t *coeffs = NULL;
+    DECLARE_POLY(p);
+    C_KZG_RET ret;
+    CHECK(n <= FIELD_ELEMENTS_PER_BLOB);
+
+    ret = new_fr_array(&coeffs, n);
+    if (ret != C_KZG_OK) goto out;
+    ret = fr_array_from_bytes(coeffs, coefficients, n);
+    if (ret != C_KZG_OK) goto out;
+    ret = ALLOC_POLY(p);
+    if (ret != C_KZG_OK) goto out;
+    ret = poly_from_coefficients(p, coeffs, n, s);
+    if (ret != C_KZG_OK) goto out;
+    ret = poly_to_kzg_commitment(out, p, s);
+
+out:
+    if (coeffs != NULL) free_fn(coeffs);
+    FREE_POLY(p);
+    return ret;
+}
+
+/**
+ * Compute a KZG proof of the value of a polynomial in coefficient (monomial) form at a point.
+ *
+ * The quotient `(p(X) - p(z)) / (X - z)` and the value `p(z)` come out of one pass of synthetic division, and the
+ * proof is the commitment to the quotient as in #commit_coefficients.
+ *
+ * @param[out] proof_out    The proof
+ * @param[out] y_out        The value of the polynomial at @p z
+ * @param[in]  coefficients @p n coefficients of #BYTES_PER_FIELD_ELEMENT bytes each, lowest degree first
+ * @param[in]  n            The number of coefficients, at most #FIELD_ELEMENTS_PER_BLOB
+ * @param[in]  z            The point to open the polynomial at
+ * @param[in]  s            The trusted setup
+ * @retval C_KZG_OK      All is well
+ * @retval C_KZG_BADARGS @p n is too large, or a coefficient or @p z is not canonical
+ * @retval C_KZG_MALLOC  Memory allocation failed
+ */
+C_KZG_RET compute_proof_monomial(KZGProof *proof_out,
+                                 uint8_t y_out[BYTES_PER_FIELD_ELEMENT],
+                                 const uint8_t *coefficients,
+                                 size_t n,
+                                 const uint8_t z[BYTES_PER_FIELD_ELEMENT],
+                                 const KZGSettings *s) {
+    fr_t *coeffs = NULL;
+    fr_t frz, y = fr_zero;
+    DECLARE_POLY(q);
+    C_KZG_RET ret;
+    CHECK(n <= FIELD_ELEMENTS_PER_BLOB);
+
+    ret = bytes_to_bls_field(&frz, z);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&coeffs, n);
+    if (ret != C_KZG_OK) goto out;
+    ret = fr_array_from_bytes(coeffs, coefficients, n);
+    if (ret != C_KZG_OK) goto out;
+
+    // Horner's rule, keeping the intermediate values as the coefficients of the quotient
+    for (size_t i = n; i > 0; i--) {
+        if (i < n) coeffs[i] = y;
+        fr_mul(&y, &y, &frz);
+        fr_add(&y, &y, &coeffs[i - 1]);
+    }
+
+    ret = ALLOC_POLY(q);
+    if (ret != C_KZG_OK) goto out;
+    ret = poly_from_coefficients(q, n > 0 ? &coeffs[1] : coeffs, n > 0 ? n - 1 : 0, s);
+    if (ret != C_KZG_OK) goto out;
+    ret = poly_to_kzg_commitment(proof_out, q, s);
+    if (ret != C_KZG_OK) goto out;
+    bytes_from_bls_field(y_out, &y);
+
+out:
+    if (coeffs != NULL) free_fn(coeffs);
+    FREE_POLY(q);
+    return ret;
+}
+
+/**
+ * Compute the linear combination of @p n G1 points with field element coefficients.
+ *
+ * This is the multi-scalar multiplication used for commitments, exposed for protocols that need one on the same
+ * curve. With #C_KZG_CONSTANT_TIME defined it does not depend on the values of the coefficients.
+ *
+ * @param[out] out     The sum of @p scalars[i] times @p points[i], or the identity if @p n is zero
+ * @param[in]  points  @p n points in G1
+ * @param[in]  scalars @p n field elements
+ * @param[in]  n       The number of points and of scalars
+ * @retval C_KZG_OK     All is well
+ * @retval C_KZG_MALLOC Memory allocation failed
+ */
+C_KZG_RET g1_linear_combination(g1_t *out, const g1_t *points, const BLSFieldElement *scalars, size_t n) {
+    return g1_lincomb(out, points, scalars, n);
 }
 
 /**
@@ -1054,6 +1516,27 @@ C_KZG_RET verify_kzg_proof(bool *out,
     return verify_kzg_proof_impl(out, commitment, &frz, &fry, kzg_proof, s);
 }
 
+/**
+ * Check a KZG proof at a point against a commitment, with the point and the claimed value already converted to field
+ * elements.
+ *
+ * @param[out] out        `true` if the proof is valid, `false` if not
+ * @param[in]  commitment The commitment to a polynomial
+ * @param[in]  z          The point at which the proof is to be checked (opened)
+ * @param[in]  y          The claimed value of the polynomial at @p z
+ * @param[in]  kzg_proof  A proof of the value of the polynomial at the point @p z
+ * @param[in]  s          The settings containing the secrets
+ * @retval C_KZG_OK All is well
+ */
+C_KZG_RET verify_kzg_proof_field_elements(bool *out,
+                                          const KZGCommitment *commitment,
+                                          const BLSFieldElement *z,
+                                          const BLSFieldElement *y,
+                                          const KZGProof *kzg_proof,
+                                          const KZGSettings *s) {
+    return verify_kzg_proof_impl(out, commitment, z, y, kzg_proof, s);
+}
+
 static C_KZG_RET evaluate_polynomial_in_evaluation_form(BLSFieldElement *out, const Polynomial *p, const BLSFieldElement *x, const KZGSettings *s) {
     C_KZG_RET ret;
     fr_t tmp;
@@ -1092,8 +1575,8 @@ static C_KZG_RET evaluate_polynomial_in_evaluation_form(BLSFieldElement *out, co
     fr_mul(out, out, &tmp);
 
 out:
-    if (inverses_in != NULL) free(inverses_in);
-    if (inverses != NULL) free(inverses);
+    if (inverses_in != NULL) free_fn(inverses_in);
+    if (inverses != NULL) free_fn(inverses);
     return ret;
 }
 
@@ -1112,12 +1595,13 @@ static C_KZG_RET compute_kzg_proof(KZGProof *out, const Polynomial *p, const BLS
     BLSFieldElement y;
     fr_t *inverses_in = NULL;
     fr_t *inverses = NULL;
+    DECLARE_POLY(q);
+    CHECK(s->g1_values != NULL);
 
     ret = evaluate_polynomial_in_evaluation_form(&y, p, x, s);
     if (ret != C_KZG_OK) goto out;
 
     fr_t tmp;
-    Polynomial q;
     const fr_t *roots_of_unity = s->fs->roots_of_unity;
     uint64_t i, m = 0;
 
@@ -1125,6 +1609,8 @@ static C_KZG_RET compute_kzg_proof(KZGProof *out, const Polynomial *p, const BLS
     if (ret != C_KZG_OK) goto out;
     ret = new_fr_array(&inverses, FIELD_ELEMENTS_PER_BLOB);
     if (ret != C_KZG_OK) goto out;
+    ret = ALLOC_POLY(q);
+    if (ret != C_KZG_OK) goto out;
 
     for (i = 0; i < FIELD_ELEMENTS_PER_BLOB; i++) {
         if (fr_equal(x, &roots_of_unity[i])) {
@@ -1132,7 +1618,7 @@ static C_KZG_RET compute_kzg_proof(KZGProof *out, const Polynomial *p, const BLS
             continue;
         }
         // (p_i - y) / (ω_i - x)
-        fr_sub(&q.evals[i], &p->evals[i], &y);
+        fr_sub(&q->evals[i], &p->evals[i], &y);
         fr_sub(&inverses_in[i], &roots_of_unity[i], x);
     }
 
@@ -1140,11 +1626,11 @@ static C_KZG_RET compute_kzg_proof(KZGProof *out, const Polynomial *p, const BLS
     if (ret != C_KZG_OK) goto out;
 
     for (i = 0; i < FIELD_ELEMENTS_PER_BLOB; i++) {
-        fr_mul(&q.evals[i], &q.evals[i], &inverses[i]);
+        fr_mul(&q->evals[i], &q->evals[i], &inverses[i]);
     }
 
     if (m) { // ω_m == x
-        q.evals[--m] = fr_zero;
+        q->evals[--m] = fr_zero;
         for (i = 0; i < FIELD_ELEMENTS_PER_BLOB; i++) {
             if (i == m) continue;
             // (p_i - y) * ω_i / (x * (x - ω_i))
@@ -1157,15 +1643,16 @@ static C_KZG_RET compute_kzg_proof(KZGProof *out, const Polynomial *p, const BLS
             fr_sub(&tmp, &p->evals[i], &y);
             fr_mul(&tmp, &tmp, &roots_of_unity[i]);
             fr_mul(&tmp, &tmp, &inverses[i]);
-            fr_add(&q.evals[m], &q.evals[m], &tmp);
+            fr_add(&q->evals[m], &q->evals[m], &tmp);
         }
     }
 
-    ret = g1_lincomb(out, s->g1_values, (const fr_t *)(&q.evals), FIELD_ELEMENTS_PER_BLOB);
+    ret = g1_lincomb(out, s->g1_values, (const fr_t *)(&q->evals), FIELD_ELEMENTS_PER_BLOB);
 
 out:
-    if (inverses_in != NULL) free(inverses_in);
-    if (inverses != NULL) free(inverses);
+    if (inverses_in != NULL) free_fn(inverses_in);
+    if (inverses != NULL) free_fn(inverses);
+    FREE_POLY(q);
     return ret;
 }
 
@@ -1202,8 +1689,8 @@ static C_KZG_RET compute_challenges(BLSFieldElement *out, BLSFieldElement r_powe
     const size_t np = ni + n * BYTES_PER_BLOB;
     const size_t nb = np + n * 48;
 
-    uint8_t* bytes = calloc(nb, sizeof(uint8_t));
-    if (bytes == NULL) return C_KZG_MALLOC;
+    uint8_t* bytes = calloc_fn(nb, sizeof(uint8_t));
+    if (bytes == NULL) return FAIL(C_KZG_MALLOC, "allocation failed");
 
     /* Copy domain seperator */
     memcpy(bytes, FIAT_SHAMIR_PROTOCOL_DOMAIN, 16);
@@ -1243,7 +1730,7 @@ static C_KZG_RET compute_challenges(BLSFieldElement *out, BLSFieldElement r_powe
     hash(eval_challenge, hash_input, 33);
     hash_to_bls_field(out, eval_challenge);
 
-    free(bytes);
+    free_fn(bytes);
     return C_KZG_OK;
 }
 
@@ -1251,8 +1738,8 @@ static C_KZG_RET compute_aggregated_poly_and_commitment(Polynomial *poly_out, KZ
         const Polynomial *polys,
         const KZGCommitment *kzg_commitments,
         size_t n) {
-    BLSFieldElement* r_powers = calloc(n, sizeof(BLSFieldElement));
-    if (0 < n && r_powers == NULL) return C_KZG_MALLOC;
+    BLSFieldElement* r_powers = calloc_fn(n, sizeof(BLSFieldElement));
+    if (0 < n && r_powers == NULL) return FAIL(C_KZG_MALLOC, "allocation failed");
 
     C_KZG_RET ret;
     ret = compute_challenges(chal_out, r_powers, polys, kzg_commitments, n);
@@ -1263,7 +1750,7 @@ static C_KZG_RET compute_aggregated_poly_and_commitment(Polynomial *poly_out, KZ
     ret = g1_lincomb(comm_out, kzg_commitments, r_powers, n);
 
 out:
-    if (r_powers != NULL) free(r_powers);
+    if (r_powers != NULL) free_fn(r_powers);
     return C_KZG_OK;
 }
 
@@ -1274,16 +1761,17 @@ C_KZG_RET compute_aggregate_kzg_proof(KZGProof *out,
     C_KZG_RET ret;
     Polynomial* polys = NULL;
     KZGCommitment* commitments = NULL;
+    DECLARE_POLY(aggregated_poly);
 
-    commitments = calloc(n, sizeof(KZGCommitment));
+    commitments = calloc_fn(n, sizeof(KZGCommitment));
     if (0 < n && commitments == NULL) {
-        ret = C_KZG_MALLOC;
+        ret = FAIL(C_KZG_MALLOC, "allocation failed");
         goto out;
     }
 
-    polys = calloc(n, sizeof(Polynomial));
+    polys = calloc_fn(n, sizeof(Polynomial));
     if (0 < n && polys == NULL) {
-        ret = C_KZG_MALLOC;
+        ret = FAIL(C_KZG_MALLOC, "allocation failed");
         goto out;
     }
 
@@ -1294,17 +1782,20 @@ C_KZG_RET compute_aggregate_kzg_proof(KZGProof *out,
         if (ret != C_KZG_OK) goto out;
     }
 
-    Polynomial aggregated_poly;
+    ret = ALLOC_POLY(aggregated_poly);
+    if (ret != C_KZG_OK) goto out;
+
     KZGCommitment aggregated_poly_commitment;
     BLSFieldElement evaluation_challenge;
-    ret = compute_aggregated_poly_and_commitment(&aggregated_poly, &aggregated_poly_commitment, &evaluation_challenge, polys, commitments, n);
+    ret = compute_aggregated_poly_and_commitment(aggregated_poly, &aggregated_poly_commitment, &evaluation_challenge, polys, commitments, n);
     if (ret != C_KZG_OK) goto out;
 
-    ret = compute_kzg_proof(out, &aggregated_poly, &evaluation_challenge, s);
+    ret = compute_kzg_proof(out, aggregated_poly, &evaluation_challenge, s);
 
 out:
-    if (commitments != NULL) free(commitments);
-    if (polys != NULL) free(polys);
+    if (commitments != NULL) free_fn(commitments);
+    if (polys != NULL) free_fn(polys);
+    FREE_POLY(aggregated_poly);
     return ret;
 }
 
@@ -1315,26 +1806,286 @@ C_KZG_RET verify_aggregate_kzg_proof(bool *out,
                                      const KZGProof *kzg_aggregated_proof,
                                      const KZGSettings *s) {
     C_KZG_RET ret;
-    Polynomial* polys = calloc(n, sizeof(Polynomial));
-    if (polys == NULL) return C_KZG_MALLOC;
+    DECLARE_POLY(aggregated_poly);
+    Polynomial* polys = calloc_fn(n, sizeof(Polynomial));
+    if (polys == NULL) return FAIL(C_KZG_MALLOC, "allocation failed");
     for (size_t i = 0; i < n; i++) {
         ret = poly_from_blob(&polys[i], &blobs[i]);
         if (ret != C_KZG_OK) goto out;
     }
 
-    Polynomial aggregated_poly;
+    ret = ALLOC_POLY(aggregated_poly);
+    if (ret != C_KZG_OK) goto out;
+
     KZGCommitment aggregated_poly_commitment;
     BLSFieldElement evaluation_challenge;
-    ret = compute_aggregated_poly_and_commitment(&aggregated_poly, &aggregated_poly_commitment, &evaluation_challenge, polys, expected_kzg_commitments, n);
+    ret = compute_aggregated_poly_and_commitment(aggregated_poly, &aggregated_poly_commitment, &evaluation_challenge, polys, expected_kzg_commitments, n);
     if (ret != C_KZG_OK) goto out;
 
     BLSFieldElement y;
-    ret = evaluate_polynomial_in_evaluation_form(&y, &aggregated_poly, &evaluation_challenge, s);
+    ret = evaluate_polynomial_in_evaluation_form(&y, aggregated_poly, &evaluation_challenge, s);
     if (ret != C_KZG_OK) goto out;
 
     ret = verify_kzg_proof_impl(out, &aggregated_poly_commitment, &evaluation_challenge, &y, kzg_aggregated_proof, s);
 
 out:
-    if (polys != NULL) free(polys);
+    if (polys != NULL) free_fn(polys);
+    FREE_POLY(aggregated_poly);
+    return ret;
+}
+
+/**
+ * Derive the evaluation point of a proof of equivalence.
+ *
+ * The point is the SHA-256 hash of #EQUIVALENCE_PROTOCOL_DOMAIN, the compressed @p commitment and @p data_hash, read
+ * as a little-endian integer and reduced modulo the field order.
+ */
+static void compute_equivalence_challenge(BLSFieldElement *out, const KZGCommitment *commitment,
+                                          const uint8_t data_hash[32]) {
+    uint8_t bytes[16 + 48 + 32];
+    uint8_t hashed_data[32];
+    memcpy(bytes, EQUIVALENCE_PROTOCOL_DOMAIN, 16);
+    bytes_from_g1(&bytes[16], commitment);
+    memcpy(&bytes[16 + 48], data_hash, 32);
+    hash(hashed_data, bytes, sizeof(bytes));
+    hash_to_bls_field(out, hashed_data);
+}
+
+/**
+ * Prove that a blob is the data behind both a KZG commitment and a hash of the data (a proof of equivalence).
+ *
+ * The blob polynomial is opened at a point derived from @p commitment and @p data_hash. A verifier that holds the data
+ * evaluates its own representation of it at the same point, compares the result with @p y_out, and checks the
+ * opening with #verify_equivalence_proof.
+ *
+ * @param[out] proof_out  The opening of @p commitment at the derived point
+ * @param[out] y_out      The evaluation of the blob polynomial at the derived point
+ * @param[in]  blob       The blob
+ * @param[in]  commitment The commitment to @p blob
+ * @param[in]  data_hash  The hash of the data under the other commitment scheme
+ * @param[in]  s          The trusted setup
+ * @retval C_KZG_OK      All is well
+ * @retval C_KZG_BADARGS A field element of @p blob is not canonical
+ * @retval C_KZG_MALLOC  Memory allocation failed
+ */
+C_KZG_RET compute_equivalence_proof(KZGProof *proof_out,
+                                    uint8_t y_out[BYTES_PER_FIELD_ELEMENT],
+                                    const Blob *blob,
+                                    const KZGCommitment *commitment,
+                                    const uint8_t data_hash[32],
+                                    const KZGSettings *s) {
+    C_KZG_RET ret;
+    BLSFieldElement z, y;
+    DECLARE_POLY(p);
+    ret = ALLOC_POLY(p);
+    if (ret != C_KZG_OK) goto out;
+    ret = poly_from_blob(p, blob);
+    if (ret != C_KZG_OK) goto out;
+
+    compute_equivalence_challenge(&z, commitment, data_hash);
+    ret = evaluate_polynomial_in_evaluation_form(&y, p, &z, s);
+    if (ret != C_KZG_OK) goto out;
+    ret = compute_kzg_proof(proof_out, p, &z, s);
+    if (ret != C_KZG_OK) goto out;
+    bytes_from_bls_field(y_out, &y);
+
+out:
+    FREE_POLY(p);
+    return ret;
+}
+
+/**
+ * Check a proof of equivalence computed by #compute_equivalence_proof.
+ *
+ * @param[out] out        `true` if @p commitment opens to @p y at the point derived from it and @p data_hash
+ * @param[in]  commitment The commitment to the blob
+ * @param[in]  data_hash  The hash of the data under the other commitment scheme
+ * @param[in]  y          The claimed evaluation
+ * @param[in]  proof      The opening
+ * @param[in]  s          The trusted setup
+ * @retval C_KZG_OK      All is well
+ * @retval C_KZG_BADARGS @p y is not canonical
+ */
+C_KZG_RET verify_equivalence_proof(bool *out,
+                                   const KZGCommitment *commitment,
+                                   const uint8_t data_hash[32],
+                                   const uint8_t y[BYTES_PER_FIELD_ELEMENT],
+                                   const KZGProof *proof,
+                                   const KZGSettings *s) {
+    BLSFieldElement z, fry;
+    C_KZG_RET ret = bytes_to_bls_field(&fry, y);
+    if (ret != C_KZG_OK) return ret;
+    compute_equivalence_challenge(&z, commitment, data_hash);
+    return verify_kzg_proof_impl(out, commitment, &z, &fry, proof, s);
+}
+
+/**
+ * The largest shift of one step of a degree bound proof, the highest power of tau in G2 in the trusted setup.
+ */
+#define DEGREE_BOUND_MAX_SHIFT 64
+
+/**
+ * The number of G1 points of a degree bound proof for @p degree_bound.
+ *
+ * A polynomial of degree less than @p degree_bound can be multiplied by `X^k` with `k = FIELD_ELEMENTS_PER_BLOB -
+ * degree_bound` and still be committed to with the trusted setup, and one of higher degree cannot. The verifier
+ * checks the shifted commitment with a pairing against `[tau^k]_2`, but the setup only holds powers of tau in G2 up
+ * to #DEGREE_BOUND_MAX_SHIFT, so the proof shifts in steps: the first step by the remainder, the others by
+ * #DEGREE_BOUND_MAX_SHIFT each, with one commitment per step.
+ *
+ * @param[in] degree_bound The degree bound, at most #FIELD_ELEMENTS_PER_BLOB
+ * @return `ceil((FIELD_ELEMENTS_PER_BLOB - degree_bound) / DEGREE_BOUND_MAX_SHIFT)`, or 0 if @p degree_bound is too large
+ */
+size_t degree_bound_proof_length(size_t degree_bound) {
+    if (degree_bound >= FIELD_ELEMENTS_PER_BLOB) return 0;
+    return (FIELD_ELEMENTS_PER_BLOB - degree_bound + DEGREE_BOUND_MAX_SHIFT - 1) / DEGREE_BOUND_MAX_SHIFT;
+}
+
+/**
+ * The shift of the first step of a degree bound proof of @p n points for @p degree_bound.
+ */
+static size_t degree_bound_first_shift(size_t degree_bound, size_t n) {
+    return FIELD_ELEMENTS_PER_BLOB - degree_bound - DEGREE_BOUND_MAX_SHIFT * (n - 1);
+}
+
+/**
+ * Prove that a polynomial in coefficient (monomial) form has a degree less than @p degree_bound.
+ *
+ * Proof point `i` is the commitment to `X^(k_i) * p(X)`, where `k_i` is the total shift of the first `i + 1` steps as
+ * described in #degree_bound_proof_length, so the last one is shifted by `FIELD_ELEMENTS_PER_BLOB - degree_bound`.
+ * The proof is checked against the commitment of #commit_coefficients with #verify_degree_bound_proof.
+ *
+ * @param[out] proofs_out   #degree_bound_proof_length(@p degree_bound) proof points
+ * @param[in]  coefficients @p n coefficients of #BYTES_PER_FIELD_ELEMENT bytes each, lowest degree first
+ * @param[in]  n            The number of coefficients, at most @p degree_bound
+ * @param[in]  degree_bound The degree bound, at most #FIELD_ELEMENTS_PER_BLOB
+ * @param[in]  s            The trusted setup
+ * @retval C_KZG_OK      All is well
+ * @retval C_KZG_BADARGS @p degree_bound is too large, there are more than @p degree_bound coefficients, or a
+ *                       coefficient is not canonical
+ * @retval C_KZG_MALLOC  Memory allocation failed
+ */
+C_KZG_RET compute_degree_bound_proof(KZGProof *proofs_out,
+                                     const uint8_t *coefficients,
+                                     size_t n,
+                                     size_t degree_bound,
+                                     const KZGSettings *s) {
+    fr_t *shifted = NULL;
+    DECLARE_POLY(p);
+    C_KZG_RET ret;
+    CHECK(degree_bound <= FIELD_ELEMENTS_PER_BLOB);
+    CHECK(n <= degree_bound);
+    CHECK(s->g1_values != NULL);
+
+    size_t len = degree_bound_proof_length(degree_bound);
+    ret = new_fr_array(&shifted, FIELD_ELEMENTS_PER_BLOB);
+    if (ret != C_KZG_OK) goto out;
+    ret = fr_array_from_bytes(shifted, coefficients, n);
+    if (ret != C_KZG_OK) goto out;
+    ret = ALLOC_POLY(p);
+    if (ret != C_KZG_OK) goto out;
+
+    size_t shift = 0;
+    for (size_t i = 0; i < len; i++) {
+        size_t step = i == 0 ? degree_bound_first_shift(degree_bound, len) : DEGREE_BOUND_MAX_SHIFT;
+        // Move the coefficients up by `step`, the highest first so that none is overwritten before it moves
+        for (size_t j = n; j > 0; j--) {
+            shifted[shift + step + j - 1] = shifted[shift + j - 1];
+        }
+        for (size_t j = shift; j < shift + step; j++) {
+            shifted[j] = fr_zero;
+        }
+        shift += step;
+        ret = poly_from_coefficients(p, shifted, shift + n, s);
+        if (ret != C_KZG_OK) goto out;
+        ret = poly_to_kzg_commitment(&proofs_out[i], p, s);
+        if (ret != C_KZG_OK) goto out;
+    }
+
+out:
+    if (shifted != NULL) free_fn(shifted);
+    FREE_POLY(p);
+    return ret;
+}
+
+/**
+ * Derive the powers of the challenge that batch the steps of a degree bound proof after the first.
+ *
+ * The challenge is the SHA-256 hash of #DEGREE_BOUND_PROTOCOL_DOMAIN, #FIELD_ELEMENTS_PER_BLOB and @p degree_bound as
+ * little-endian 8 byte integers, the compressed @p commitment and the @p n compressed proof points.
+ */
+static C_KZG_RET compute_degree_bound_challenges(BLSFieldElement r_powers[], const KZGCommitment *commitment,
+                                                 size_t degree_bound, const KZGProof *proofs, size_t n) {
+    uint8_t *bytes = NULL;
+    uint8_t hashed_data[32];
+    BLSFieldElement r;
+    const size_t nb = 16 + 8 + 8 + 48 + n * 48;
+    C_KZG_RET ret = c_kzg_malloc((void **)&bytes, nb);
+    if (ret != C_KZG_OK) return ret;
+
+    memcpy(bytes, DEGREE_BOUND_PROTOCOL_DOMAIN, 16);
+    bytes_of_uint64(&bytes[16], FIELD_ELEMENTS_PER_BLOB);
+    bytes_of_uint64(&bytes[16 + 8], degree_bound);
+    bytes_from_g1(&bytes[16 + 8 + 8], commitment);
+    for (size_t i = 0; i < n; i++) {
+        bytes_from_g1(&bytes[16 + 8 + 8 + 48 + i * 48], &proofs[i]);
+    }
+    hash(hashed_data, bytes, nb);
+    hash_to_bls_field(&r, hashed_data);
+    compute_powers(r_powers, &r, n - 1);
+
+    free_fn(bytes);
+    return C_KZG_OK;
+}
+
+/**
+ * Check a degree bound proof computed by #compute_degree_bound_proof.
+ *
+ * The first step is checked with `e(proofs[0], [1]_2) = e(commitment, [tau^(k_0)]_2)`. The others, with
+ * `e(proofs[i], [1]_2) = e(proofs[i - 1], [tau^64]_2)` each, are combined with the powers of a challenge derived from
+ * the inputs into a single pairing check.
+ *
+ * @param[out] out          `true` if @p commitment is to a polynomial of degree less than @p degree_bound
+ * @param[in]  commitment   The commitment to the polynomial
+ * @param[in]  degree_bound The degree bound, at most #FIELD_ELEMENTS_PER_BLOB
+ * @param[in]  proofs       The @p n proof points
+ * @param[in]  n            The number of proof points, #degree_bound_proof_length(@p degree_bound)
+ * @param[in]  s            The trusted setup
+ * @retval C_KZG_OK      All is well
+ * @retval C_KZG_BADARGS @p degree_bound is too large, or @p n does not match it
+ * @retval C_KZG_MALLOC  Memory allocation failed
+ */
+C_KZG_RET verify_degree_bound_proof(bool *out,
+                                    const KZGCommitment *commitment,
+                                    size_t degree_bound,
+                                    const KZGProof *proofs,
+                                    size_t n,
+                                    const KZGSettings *s) {
+    BLSFieldElement *r_powers = NULL;
+    g1_t shifted_sum, unshifted_sum;
+    C_KZG_RET ret;
+    CHECK(degree_bound <= FIELD_ELEMENTS_PER_BLOB);
+    CHECK(n == degree_bound_proof_length(degree_bound));
+
+    // Every commitment is to a polynomial of degree less than FIELD_ELEMENTS_PER_BLOB
+    *out = true;
+    if (n == 0) return C_KZG_OK;
+
+    size_t first_shift = degree_bound_first_shift(degree_bound, n);
+    *out = pairings_verify(&proofs[0], &g2_generator, commitment, &s->g2_values[first_shift]);
+    if (!*out || n == 1) return C_KZG_OK;
+
+    ret = new_fr_array(&r_powers, n - 1);
+    if (ret != C_KZG_OK) goto out;
+    ret = compute_degree_bound_challenges(r_powers, commitment, degree_bound, proofs, n);
+    if (ret != C_KZG_OK) goto out;
+    ret = g1_lincomb(&shifted_sum, &proofs[1], r_powers, n - 1);
+    if (ret != C_KZG_OK) goto out;
+    ret = g1_lincomb(&unshifted_sum, proofs, r_powers, n - 1);
+    if (ret != C_KZG_OK) goto out;
+    *out = pairings_verify(&shifted_sum, &g2_generator, &unshifted_sum, &s->g2_values[DEGREE_BOUND_MAX_SHIFT]);
+
+out:
+    if (r_powers != NULL) free_fn(r_powers);
     return ret;
 }
@@ -39,6 +39,8 @@ extern "C" {
 #define BYTES_PER_FIELD_ELEMENT 32
 #define BYTES_PER_BLOB FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT
 static const char *FIAT_SHAMIR_PROTOCOL_DOMAIN = "FSBLOBVERIFY_V1_";
+static const char *EQUIVALENCE_PROTOCOL_DOMAIN = "KZG_EQUIV_PROOF_";
+static const char *DEGREE_BOUND_PROTOCOL_DOMAIN = "KZG_DEG_BOUND_V1";
 
 typedef blst_p1 g1_t;         /**< Internal G1 group element type */
 typedef blst_p2 g2_t;         /**< Internal G2 group element type */
@@ -64,8 +66,8 @@ typedef enum {
  */
 typedef struct {
     uint64_t max_width;            /**< The maximum size of FFT these settings support, a power of 2. */
-    fr_t *expanded_roots_of_unity; /**< Ascending powers of the root of unity, size `width + 1`. */
-    fr_t *reverse_roots_of_unity;  /**< Descending powers of the root of unity, size `width + 1`. */
+    fr_t *expanded_roots_of_unity; /**< Ascending powers of the root of unity, size `width + 1`. `NULL` once loaded. */
+    fr_t *reverse_roots_of_unity;  /**< Descending powers of the root of unity, size `width + 1`. `NULL` once loaded. */
     fr_t *roots_of_unity;          /**< Powers of the root of unity in bit-reversal permutation, size `width`. */
 } FFTSettings;
 
@@ -78,10 +80,40 @@ typedef struct {
     g2_t *g2_values;       /**< G2 group elements from the trusted setup; both arrays have FIELD_ELEMENTS_PER_BLOB elements */
 } KZGSettings;
 
+/**
+ * Custom allocation functions, with the same semantics as `malloc`, `calloc` and `free`.
+ */
+typedef void *(*c_kzg_malloc_fn)(size_t size);
+typedef void *(*c_kzg_calloc_fn)(size_t nmemb, size_t size);
+typedef void (*c_kzg_free_fn)(void *ptr);
+
+/**
+ * Receives the reason a function failed: its return code, the source location and the failing check or a description
+ * of the failure. The strings are static and outlive the call.
+ */
+typedef void (*c_kzg_error_fn)(C_KZG_RET ret, const char *file, int line, const char *message);
+
 /**
  * Interface functions
  */
 
+/**
+ * Replace the functions used to allocate and free memory. Passing `NULL` restores the libc default.
+ *
+ * @remark This is not thread-safe and must be called before any other function. Memory is always released with the
+ * current `free_impl`, so settings loaded with one allocator must be freed before switching to another.
+ */
+void c_kzg_set_allocator(c_kzg_malloc_fn malloc_impl, c_kzg_calloc_fn calloc_impl, c_kzg_free_fn free_impl);
+
+/**
+ * Set the callback that is told why a function returns an error. Passing `NULL` disables reporting, the default.
+ *
+ * @remark This is not thread-safe and must be called before any other function. The callback may be called from any
+ * thread that uses the library.
+ */
+void c_kzg_set_error_callback(c_kzg_error_fn callback);
+
+
 C_KZG_RET bytes_to_g1(g1_t* out, const uint8_t in[48]);
 void bytes_from_g1(uint8_t out[48], const g1_t *in);
 
@@ -96,6 +128,16 @@ C_KZG_RET load_trusted_setup(KZGSettings *out,
 C_KZG_RET load_trusted_setup_file(KZGSettings *out,
                                   FILE *in);
 
+C_KZG_RET save_trusted_setup_lagrange(uint8_t *out, /* (FIELD_ELEMENTS_PER_BLOB + 65) * 96 + 32 bytes */
+                                      const KZGSettings *s);
+
+C_KZG_RET load_trusted_setup_lagrange(KZGSettings *out,
+                                      const uint8_t *bytes); /* (FIELD_ELEMENTS_PER_BLOB + 65) * 96 + 32 bytes */
+
+C_KZG_RET load_trusted_setup_verify_only(KZGSettings *out,
+                                         const uint8_t g2_bytes[], /* n2 * 96 bytes */
+                                         size_t n2);
+
 void free_trusted_setup(
     KZGSettings *s);
 
@@ -115,6 +157,28 @@ C_KZG_RET blob_to_kzg_commitment(KZGCommitment *out,
                                  const Blob *blob,
                                  const KZGSettings *s);
 
+C_KZG_RET blob_prefix_to_kzg_commitment(KZGCommitment *out,
+                                        const uint8_t *field_elements,
+                                        size_t n,
+                                        const KZGSettings *s);
+
+C_KZG_RET commit_coefficients(KZGCommitment *out,
+                              const uint8_t *coefficients,
+                              size_t n,
+                              const KZGSettings *s);
+
+C_KZG_RET compute_proof_monomial(KZGProof *proof_out,
+                                 uint8_t y_out[BYTES_PER_FIELD_ELEMENT],
+                                 const uint8_t *coefficients,
+                                 size_t n,
+                                 const uint8_t z[BYTES_PER_FIELD_ELEMENT],
+                                 const KZGSettings *s);
+
+C_KZG_RET g1_linear_combination(g1_t *out,
+                                const g1_t *points,
+                                const BLSFieldElement *scalars,
+                                size_t n);
+
 C_KZG_RET verify_kzg_proof(bool *out,
                            const KZGCommitment *polynomial_kzg,
                            const uint8_t z[BYTES_PER_FIELD_ELEMENT],
@@ -122,6 +186,42 @@ C_KZG_RET verify_kzg_proof(bool *out,
                            const KZGProof *kzg_proof,
                            const KZGSettings *s);
 
+C_KZG_RET verify_kzg_proof_field_elements(bool *out,
+                                          const KZGCommitment *commitment,
+                                          const BLSFieldElement *z,
+                                          const BLSFieldElement *y,
+                                          const KZGProof *kzg_proof,
+                                          const KZGSettings *s);
+
+C_KZG_RET compute_equivalence_proof(KZGProof *proof_out,
+                                    uint8_t y_out[BYTES_PER_FIELD_ELEMENT],
+                                    const Blob *blob,
+                                    const KZGCommitment *commitment,
+                                    const uint8_t data_hash[32],
+                                    const KZGSettings *s);
+
+C_KZG_RET verify_equivalence_proof(bool *out,
+                                   const KZGCommitment *commitment,
+                                   const uint8_t data_hash[32],
+                                   const uint8_t y[BYTES_PER_FIELD_ELEMENT],
+                                   const KZGProof *proof,
+                                   const KZGSettings *s);
+
+size_t degree_bound_proof_length(size_t degree_bound);
+
+C_KZG_RET compute_degree_bound_proof(KZGProof *proofs_out,
+                                     const uint8_t *coefficients,
+                                     size_t n,
+                                     size_t degree_bound,
+                                     const KZGSettings *s);
+
+C_KZG_RET verify_degree_bound_proof(bool *out,
+                                    const KZGCommitment *commitment,
+                                    size_t degree_bound,
+                                    const KZGProof *proofs,
+                                    size_t n,
+                                    const KZGSettings *s);
+
 #ifdef __cplusplus
 }
 #endif